@@ -11,17 +11,26 @@
 * limitations under the License.
 */
 
-use crate::{TokenValue, error::AbiError, event::Event, function::Function, param::Param, param_type::ParamType, token::Token};
+use crate::{TokenValue, error::AbiError, event::{Event, EventSelector}, function::Function, getter::Getter, param::{Param, ParamAttributes}, param_type::ParamType, token::Token};
 use serde::de::Error as SerdeError;
+use sha2::{Digest, Sha256};
 use serde::Deserialize;
 use serde_json;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Display;
-use std::io;
-use ton_block::Serializable;
-use ton_types::{error, fail, BuilderData, HashmapE, Result, SliceData};
+use std::io::{self, Read};
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+use ton_block::{CommonMsgInfo, ExternalInboundMessageHeader, Message, MsgAddressInt, Serializable, StateInit, Transaction};
+use ton_types::{error, fail, Error, BuilderData, Cell, HashmapE, HashmapType, Result, SliceData, UInt256};
 use crate::param::SerdeParam;
 use crate::token::Cursor;
+use crate::typedefs::{expand_type_aliases, StructDef};
+use crate::json_span::element_position;
+use crate::validation::ValidationIssue;
+use crate::signature::Signer;
+use crate::warnings::Warnings;
+use ed25519_dalek::Keypair;
 
 pub const MIN_SUPPORTED_VERSION: AbiVersion = ABI_VERSION_1_0;
 pub const MAX_SUPPORTED_VERSION: AbiVersion = ABI_VERSION_2_7;
@@ -37,7 +46,10 @@ pub const ABI_VERSION_2_7: AbiVersion = AbiVersion::from_parts(2, 7);
 pub type PublicKeyData = [u8; ed25519_dalek::PUBLIC_KEY_LENGTH];
 pub type SignatureData = [u8; ed25519_dalek::SIGNATURE_LENGTH];
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd)]
+/// Name of storage field holding contract public key since ABI 2.4
+pub const PUBKEY_FIELD: &str = "_pubkey";
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd)]
 pub struct AbiVersion {
     pub major: u8,
     pub minor: u8,
@@ -86,6 +98,41 @@ pub struct DataItem {
     pub value: Param,
 }
 
+/// State of initial value found in contract data
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum InitValueStatus {
+    /// Value is stored in data
+    Present,
+    /// Data has no value for the item which is not required by `Contract::encode_initial_data`,
+    /// default value is assumed
+    Defaulted,
+    /// Data has no value for the item which is required by `Contract::encode_initial_data`
+    Missing,
+}
+
+/// Initial value of data item or storage field decoded by `Contract::decode_init_data_detailed`
+#[derive(Debug, Clone, PartialEq)]
+pub struct InitValue {
+    pub name: String,
+    /// Value is required by `Contract::encode_initial_data`: storage fields marked as `init`
+    /// since ABI 2.4. Data items of earlier versions are optional
+    pub init: bool,
+    pub status: InitValueStatus,
+    /// Decoded or default value. `None` for missing values
+    pub value: Option<TokenValue>,
+}
+
+impl InitValue {
+    fn new(param: &Param, init: bool, value: Option<TokenValue>) -> Self {
+        let (status, value) = match value {
+            Some(value) => (InitValueStatus::Present, Some(value)),
+            None if init => (InitValueStatus::Missing, None),
+            None => (InitValueStatus::Defaulted, Some(TokenValue::default_value(&param.kind))),
+        };
+        Self { name: param.name.clone(), init, status, value }
+    }
+}
+
 struct StringVisitor;
 
 impl<'de> serde::de::Visitor<'de> for StringVisitor {
@@ -165,6 +212,109 @@ fn bool_true() -> bool {
     true
 }
 
+type InvalidElement = Option<(String, serde_json::Error)>;
+
+fn check_element<'a, T: Deserialize<'a>>(
+    value: &'a serde_json::Value,
+    pointer: String,
+) -> InvalidElement {
+    T::deserialize(value).err().map(|err| (pointer, err))
+}
+
+fn find_invalid_param(value: &serde_json::Value, pointer: String) -> InvalidElement {
+    if let Some(components) = value.get("components").and_then(|value| value.as_array()) {
+        for (i, component) in components.iter().enumerate() {
+            let found = find_invalid_param(component, format!("{}/components/{}", pointer, i));
+            if found.is_some() {
+                return found;
+            }
+        }
+    }
+    check_element::<Param>(value, pointer)
+}
+
+fn find_invalid_item<'a, T: Deserialize<'a>>(
+    value: &'a serde_json::Value,
+    pointer: String,
+    param_lists: &[&str],
+) -> InvalidElement {
+    for list in param_lists {
+        if let Some(params) = value.get(list).and_then(|value| value.as_array()) {
+            for (i, param) in params.iter().enumerate() {
+                let found = find_invalid_param(param, format!("{}/{}/{}", pointer, list, i));
+                if found.is_some() {
+                    return found;
+                }
+            }
+        }
+    }
+    check_element::<T>(value, pointer)
+}
+
+/// Finds the innermost element of ABI JSON which can not be parsed and returns JSON pointer
+/// to it together with parse error
+fn find_invalid_element(abi: &serde_json::Value) -> InvalidElement {
+    let sections: [(&str, fn(&serde_json::Value, String) -> InvalidElement); 6] = [
+        ("header", find_invalid_param),
+        ("functions", |value, pointer| find_invalid_item::<SerdeFunction>(value, pointer, &["inputs", "outputs"])),
+        ("getters", |value, pointer| find_invalid_item::<SerdeFunction>(value, pointer, &["inputs", "outputs"])),
+        ("events", |value, pointer| find_invalid_item::<SerdeEvent>(value, pointer, &["inputs"])),
+        ("data", |value, pointer| find_invalid_item::<DataItem>(value, pointer, &[])),
+        ("fields", |value, pointer| find_invalid_item::<SerdeParam>(value, pointer, &["components"])),
+    ];
+    for (section, find) in sections {
+        if let Some(items) = abi.get(section).and_then(|value| value.as_array()) {
+            for (i, item) in items.iter().enumerate() {
+                let found = find(item, format!("/{}/{}", section, i));
+                if found.is_some() {
+                    return found;
+                }
+            }
+        }
+    }
+    None
+}
+
+fn read_params_attributes(
+    params: Option<&serde_json::Value>,
+    location: &str,
+    result: &mut HashMap<String, ParamAttributes>,
+) {
+    for param in params.and_then(|value| value.as_array()).into_iter().flatten() {
+        let name = match param.get("name").and_then(|value| value.as_str()) {
+            Some(name) => name,
+            None => continue,
+        };
+        let attributes = ParamAttributes::deserialize(param).unwrap_or_default();
+        if !attributes.is_empty() {
+            result.insert(format!("{} {}", location, name), attributes);
+        }
+    }
+}
+
+/// Collects attributes of params declared in ABI JSON by param location
+fn read_param_attributes(abi: &serde_json::Value) -> HashMap<String, ParamAttributes> {
+    let mut result = HashMap::new();
+    read_params_attributes(abi.get("header"), "header", &mut result);
+    read_params_attributes(abi.get("data"), "data", &mut result);
+    read_params_attributes(abi.get("fields"), "fields", &mut result);
+    let sections = [("functions", "function"), ("getters", "getter"), ("events", "event")];
+    for (section, kind) in sections {
+        for item in abi.get(section).and_then(|value| value.as_array()).into_iter().flatten() {
+            let name = item.get("name").and_then(|value| value.as_str()).unwrap_or_default();
+            if kind == "event" {
+                read_params_attributes(item.get("inputs"), &format!("event {}", name), &mut result);
+                continue;
+            }
+            for list in ["inputs", "outputs"] {
+                let location = format!("{} {} {}", kind, name, list);
+                read_params_attributes(item.get(list), &location, &mut result);
+            }
+        }
+    }
+    result
+}
+
 #[derive(Debug, Clone, PartialEq, serde::Deserialize)]
 struct SerdeContract {
     /// ABI version up to 2.
@@ -200,8 +350,129 @@ pub struct DecodedMessage {
     pub tokens: Vec<Token>,
 }
 
+/// Kind of message body matched by contract ABI
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MessageBodyKind {
+    FunctionInput,
+    FunctionOutput,
+    Event,
+}
+
+/// Result of decoding message body against one of candidate ABIs
+#[derive(Debug, Clone, PartialEq)]
+pub struct CandidateMatch {
+    /// Index of matched contract in candidates list
+    pub contract_index: usize,
+    /// Kind of matched body
+    pub kind: MessageBodyKind,
+    /// Matched function or event name
+    pub name: String,
+    /// Decoded tokens
+    pub tokens: Vec<Token>,
+    /// `true` if body was decoded without trailing data
+    pub complete: bool,
+}
+
+/// Transaction messages decoded by contract ABI
+#[derive(Debug)]
+pub struct DecodedTransaction {
+    /// Decoded inbound message body
+    pub in_msg: Option<DecodedBody>,
+    /// Decoded outbound messages bodies: events and function responses
+    pub out_msgs: Vec<DecodedBody>,
+    /// Messages without body or with body id not matching any function or event of contract ABI
+    pub unmatched: Vec<Message>,
+    /// Messages with body id matching contract ABI which failed to decode
+    pub failed: Vec<(Message, Error)>,
+}
+
+impl DecodedTransaction {
+    fn push(&mut self, message: Message, decoded: Result<DecodedBody>, inbound: bool) {
+        match decoded {
+            Ok(DecodedBody::Unknown { .. }) => self.unmatched.push(message),
+            Ok(decoded) if inbound => self.in_msg = Some(decoded),
+            Ok(decoded) => self.out_msgs.push(decoded),
+            Err(err) => self.failed.push((message, err)),
+        }
+    }
+}
+
+/// Set of contract events selected for decoding. Message bodies with other ids
+/// are rejected before decoding.
+#[derive(Clone, Debug, Default)]
+pub struct EventFilter {
+    events: HashMap<u32, Event>,
+}
+
+impl EventFilter {
+    /// Creates filter for contract events with provided ids
+    pub fn new(contract: &Contract, ids: &HashSet<u32>) -> Self {
+        let events = contract.events
+            .values()
+            .filter(|event| ids.contains(&event.get_id()))
+            .map(|event| (event.get_id(), event.clone()))
+            .collect();
+        Self { events }
+    }
+
+    /// Creates filter for contract events with provided names
+    pub fn with_names(contract: &Contract, names: &[&str]) -> Result<Self> {
+        let mut events = HashMap::new();
+        for name in names {
+            let event = contract.events
+                .get(*name)
+                .ok_or_else(|| AbiError::InvalidName { name: (*name).to_owned() })?;
+            events.insert(event.get_id(), event.clone());
+        }
+        Ok(Self { events })
+    }
+
+    /// Checks if event with provided id passes filter
+    pub fn contains(&self, id: u32) -> bool {
+        self.events.contains_key(&id)
+    }
+
+    /// Decodes event body if its id passes filter, returns `None` otherwise
+    pub fn decode(&self, body: SliceData) -> Result<Option<DecodedMessage>> {
+        let id = Event::decode_id(body.clone())?;
+        match self.events.get(&id) {
+            Some(event) => Ok(Some(DecodedMessage {
+                function_name: event.name.clone(),
+                tokens: event.decode_input(body)?,
+            })),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Message body decoded by contract ABI
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodedBody {
+    /// Function call with decoded header
+    FunctionInput {
+        name: String,
+        header: Vec<Token>,
+        tokens: Vec<Token>,
+    },
+    /// Function response
+    FunctionOutput {
+        name: String,
+        tokens: Vec<Token>,
+    },
+    /// Event emitted by contract
+    Event {
+        name: String,
+        tokens: Vec<Token>,
+    },
+    /// Body doesn't match any function or event of contract
+    Unknown {
+        id: u32,
+        remainder: SliceData,
+    },
+}
+
 /// API building calls to contracts ABI.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct Contract {
     /// ABI version
     pub abi_version: AbiVersion,
@@ -218,12 +489,269 @@ pub struct Contract {
     /// List of `fields` parameters with `init == true`
     pub init_fields: HashSet<String>,
     /// Contract getters
-    pub getters: HashMap<String, Function>,
+    pub getters: HashMap<String, Getter>,
+    /// Function names by input id
+    pub(crate) function_input_ids: HashMap<u32, String>,
+    /// Function names by output id
+    pub(crate) function_output_ids: HashMap<u32, String>,
+    /// Event names by id
+    pub(crate) event_ids: HashMap<u32, String>,
+    /// Names of functions, events, getters and data items in ABI declaration order
+    pub(crate) order: DeclarationOrder,
+    /// Named structs in ABI declaration order
+    pub structs: Vec<StructDef>,
+    /// Attributes declared for params in ABI JSON by param location
+    pub(crate) param_attributes: HashMap<String, ParamAttributes>,
+}
+
+// Declaration order doesn't affect contract interface and is not compared
+impl PartialEq for Contract {
+    fn eq(&self, other: &Self) -> bool {
+        self.abi_version == other.abi_version
+            && self.header == other.header
+            && self.functions == other.functions
+            && self.events == other.events
+            && self.data == other.data
+            && self.fields == other.fields
+            && self.init_fields == other.init_fields
+            && self.getters == other.getters
+            && self.function_input_ids == other.function_input_ids
+            && self.function_output_ids == other.function_output_ids
+            && self.event_ids == other.event_ids
+            && self.structs == other.structs
+    }
+}
+
+impl Eq for Contract {}
+
+/// Additional checks and overrides applied when loading contract ABI
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LoadOptions {
+    /// Fail if several functions, getters, events or data items have the same name or if
+    /// functions and events ids collide. Otherwise later definitions replace earlier ones.
+    pub deny_duplicates: bool,
+    /// Fail if integer types sizes are out of range allowed by ABI specification
+    pub deny_experimental_sizes: bool,
+    /// Use provided ABI version instead of the one declared in ABI JSON. All types used in ABI
+    /// should be supported by overriding version
+    pub abi_version: Option<AbiVersion>,
+    /// Type aliases (alias name to type) accepted in addition to aliases declared in `types`
+    /// section of ABI JSON. Aliases declared in ABI take precedence
+    pub type_aliases: BTreeMap<String, String>,
+}
+
+/// Names of contract members in ABI declaration order
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct DeclarationOrder {
+    functions: Vec<String>,
+    events: Vec<String>,
+    getters: Vec<String>,
+    data: Vec<String>,
+}
+
+impl DeclarationOrder {
+    fn push(list: &mut Vec<String>, name: &str) {
+        if !list.iter().any(|existing| existing == name) {
+            list.push(name.to_owned());
+        }
+    }
+
+    fn extend(&mut self, other: &DeclarationOrder) {
+        for (list, names) in [
+            (&mut self.functions, &other.functions),
+            (&mut self.events, &other.events),
+            (&mut self.getters, &other.getters),
+            (&mut self.data, &other.data),
+        ] {
+            for name in names {
+                Self::push(list, name);
+            }
+        }
+    }
+
+    /// Returns map values in declaration order. Values missing in `order` follow sorted by name
+    fn ordered<'a, T>(map: &'a HashMap<String, T>, order: &[String]) -> Vec<&'a T> {
+        let mut result: Vec<_> = order.iter().filter_map(|name| map.get(name)).collect();
+        let mut rest: Vec<_> = map.iter().filter(|(name, _)| !order.contains(name)).collect();
+        rest.sort_by(|a, b| a.0.cmp(b.0));
+        result.extend(rest.into_iter().map(|(_, value)| value));
+        result
+    }
+}
+
+/// Maximum number of ABIs kept by `Contract::load_cached`
+pub const CONTRACT_CACHE_CAPACITY: usize = 64;
+
+/// LRU cache of loaded contracts keyed by sha256 hash of ABI JSON
+#[derive(Default)]
+struct ContractCache {
+    entries: HashMap<UInt256, (Arc<Contract>, u64)>,
+    tick: u64,
+}
+
+impl ContractCache {
+    fn instance() -> &'static Mutex<ContractCache> {
+        static CACHE: OnceLock<Mutex<ContractCache>> = OnceLock::new();
+        CACHE.get_or_init(Default::default)
+    }
+
+    fn get(&mut self, key: &UInt256) -> Option<Arc<Contract>> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.entries.get_mut(key).map(|(contract, used)| {
+            *used = tick;
+            contract.clone()
+        })
+    }
+
+    fn insert(&mut self, key: UInt256, contract: Arc<Contract>) {
+        if self.entries.len() >= CONTRACT_CACHE_CAPACITY && !self.entries.contains_key(&key) {
+            let oldest = self.entries.iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.tick += 1;
+        self.entries.insert(key, (contract, self.tick));
+    }
 }
 
 impl Contract {
+    /// Loads contract from ABI JSON reusing previously loaded instance for the same JSON.
+    /// Up to `CONTRACT_CACHE_CAPACITY` least recently used contracts are kept.
+    pub fn load_cached(bytes: &[u8]) -> Result<Arc<Self>> {
+        let key = UInt256::from_slice(&Sha256::digest(bytes));
+        let cache = ContractCache::instance();
+        if let Some(contract) = cache.lock().unwrap_or_else(|err| err.into_inner()).get(&key) {
+            return Ok(contract);
+        }
+
+        let contract = Arc::new(Self::load(bytes)?);
+        cache.lock().unwrap_or_else(|err| err.into_inner()).insert(key, contract.clone());
+        Ok(contract)
+    }
+
+    /// Loads contract from ABI JSON stored in the cell as a snake string (cell data continued
+    /// in the first reference).
+    pub fn load_from_cell(cell: Cell) -> Result<Self> {
+        Self::load(Self::read_snake_bytes(cell)?.as_slice())
+    }
+
+    /// Loads contract from ABI JSON stored in `StateInit` library with the given hash. Library
+    /// root cell is read as described in `load_from_cell`. Fails with `AbiError::NoEmbeddedAbi`
+    /// if state init has no such library.
+    pub fn try_from_state_init(state_init: &StateInit, library_hash: &UInt256) -> Result<Self> {
+        let library = SliceData::load_cell(state_init.library.serialize()?)?;
+        let map = HashmapE::with_hashmap(256, library.reference_opt(0));
+
+        let key = SliceData::from_raw(library_hash.as_slice().to_vec(), 256);
+        let mut value = map.get(key)?.ok_or(AbiError::NoEmbeddedAbi)?;
+        // simple_lib$_ public:Bool root:^Cell
+        value.get_next_bit()?;
+        Self::load_from_cell(value.reference(0)?)
+    }
+
+    fn read_snake_bytes(cell: Cell) -> Result<Vec<u8>> {
+        let mut bytes = vec![];
+        let mut next = Some(cell);
+        while let Some(cell) = next {
+            let slice = SliceData::load_cell(cell)?;
+            bytes.extend_from_slice(&slice.get_bytestring(0));
+            next = slice.reference_opt(0);
+        }
+        Ok(bytes)
+    }
+
+    /// Loads contract from ABI JSON. Later definitions of functions, getters, events or data
+    /// items replace earlier ones with the same name. Malformed JSON is reported as
+    /// `AbiError::InvalidAbiJson` with JSON pointer to the offending element and its position.
     pub fn load<T: io::Read>(reader: T) -> Result<Self> {
-        let mut serde_contract: SerdeContract = serde_json::from_reader(reader)?;
+        Self::load_with_options(reader, LoadOptions::default())
+    }
+
+    /// Loads contract from ABI JSON applying provided checks and overrides
+    pub fn load_with_options<T: io::Read>(reader: T, options: LoadOptions) -> Result<Self> {
+        Self::load_impl(reader, options)
+    }
+
+    /// Loads contract from ABI JSON file. Gzip-compressed files are decompressed transparently
+    /// if `gzip` feature is enabled. Errors are wrapped into `AbiError::InvalidFile` with file
+    /// path, source error is available through `std::error::Error::source`.
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file_error = |source: Box<dyn std::error::Error + Send + Sync>| AbiError::InvalidFile {
+            path: path.display().to_string(),
+            source,
+        };
+
+        let bytes = std::fs::read(path).map_err(|err| file_error(err.into()))?;
+        let bytes = Self::decompress(bytes).map_err(|err| file_error(err.into()))?;
+        Self::load(bytes.as_slice()).map_err(|err| file_error(err.into()).into())
+    }
+
+    #[cfg(feature = "gzip")]
+    fn decompress(bytes: Vec<u8>) -> Result<Vec<u8>> {
+        if !bytes.starts_with(&[0x1f, 0x8b]) {
+            return Ok(bytes);
+        }
+        let mut decompressed = vec![];
+        flate2::read::GzDecoder::new(bytes.as_slice()).read_to_end(&mut decompressed)?;
+        Ok(decompressed)
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    fn decompress(bytes: Vec<u8>) -> Result<Vec<u8>> {
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            fail!(AbiError::InvalidData {
+                msg: "Gzip-compressed ABI requires `gzip` feature".to_owned()
+            });
+        }
+        Ok(bytes)
+    }
+
+    /// Builds error with JSON pointer to the innermost ABI element which failed to parse and
+    /// its line and column in the source text
+    fn locate_json_error(abi: &serde_json::Value, text: &[u8], err: serde_json::Error) -> AbiError {
+        let (pointer, err) = find_invalid_element(abi).unwrap_or((String::new(), err));
+        let (line, column) = element_position(text, &pointer);
+        let msg = err.to_string();
+        AbiError::InvalidAbiJson { pointer, line, column, msg }
+    }
+
+    fn check_duplicate<T>(map: &HashMap<String, T>, kind: &str, name: &str, allow: bool) -> Result<()> {
+        if !allow && map.contains_key(name) {
+            fail!(AbiError::InvalidData {
+                msg: format!("Duplicate {} `{}` in ABI", kind, name)
+            });
+        }
+        Ok(())
+    }
+
+    fn load_impl<T: io::Read>(mut reader: T, options: LoadOptions) -> Result<Self> {
+        let allow_duplicates = !options.deny_duplicates;
+        let mut text = Vec::new();
+        reader.read_to_end(&mut text)?;
+        let mut abi: serde_json::Value = serde_json::from_slice(&text)
+            .map_err(|err| AbiError::InvalidAbiJson {
+                pointer: String::new(),
+                line: err.line(),
+                column: err.column(),
+                msg: err.to_string(),
+            })?;
+        let structs = expand_type_aliases(&mut abi, &options.type_aliases).map_err(|err| match err.downcast::<AbiError>() {
+            Ok(AbiError::InvalidAbiJson { pointer, msg, .. }) => {
+                let (line, column) = element_position(&text, &pointer);
+                AbiError::InvalidAbiJson { pointer, line, column, msg }.into()
+            }
+            Ok(err) => err.into(),
+            Err(err) => err,
+        })?;
+        let mut serde_contract = match SerdeContract::deserialize(&abi) {
+            Ok(contract) => contract,
+            Err(err) => fail!(Self::locate_json_error(&abi, &text, err)),
+        };
         let version = if let Some(str_version) = &serde_contract.version {
             AbiVersion::parse(str_version)?
         } else if let Some(version) = serde_contract.abi_version {
@@ -233,6 +761,7 @@ impl Contract {
                 "No version in ABI JSON".to_owned()
             ));
         };
+        let version = options.abi_version.unwrap_or(version);
 
         if !version.is_supported() {
             fail!(AbiError::InvalidVersion(format!(
@@ -262,6 +791,10 @@ impl Contract {
             });
         }
 
+        if options.abi_version.is_some() {
+            Self::check_params_support(&version, serde_contract.header.iter())?;
+        }
+
         let mut result = Self {
             abi_version: version,
             header: serde_contract.header,
@@ -271,11 +804,19 @@ impl Contract {
             fields: Vec::new(),
             init_fields: HashSet::new(),
             getters: HashMap::new(),
+            function_input_ids: HashMap::new(),
+            function_output_ids: HashMap::new(),
+            event_ids: HashMap::new(),
+            order: DeclarationOrder::default(),
+            structs,
+            param_attributes: read_param_attributes(&abi),
         };
 
         for function in serde_contract.functions {
             Self::check_params_support(&version, function.inputs.iter())?;
             Self::check_params_support(&version, function.outputs.iter())?;
+            Self::check_duplicate(&result.functions, "function", &function.name, allow_duplicates)?;
+            DeclarationOrder::push(&mut result.order.functions, &function.name);
             result.functions.insert(
                 function.name.clone(),
                 Function::from_serde(version, function, result.header.clone()),
@@ -285,14 +826,18 @@ impl Contract {
         for getter in serde_contract.getters {
             Self::check_params_support(&version, getter.inputs.iter())?;
             Self::check_params_support(&version, getter.outputs.iter())?;
+            Self::check_duplicate(&result.getters, "getter", &getter.name, allow_duplicates)?;
+            DeclarationOrder::push(&mut result.order.getters, &getter.name);
             result.getters.insert(
                 getter.name.clone(),
-                Function::from_serde(version, getter, result.header.clone()),
+                Getter::from_serde(version, getter),
             );
         }
 
         for event in serde_contract.events {
             Self::check_params_support(&version, event.inputs.iter())?;
+            Self::check_duplicate(&result.events, "event", &event.name, allow_duplicates)?;
+            DeclarationOrder::push(&mut result.order.events, &event.name);
             result.events.insert(
                 event.name.clone(),
                 Event::from_serde(version, event),
@@ -301,6 +846,8 @@ impl Contract {
 
         Self::check_params_support(&version, serde_contract.data.iter().map(|val| &val.value))?;
         for data in serde_contract.data {
+            Self::check_duplicate(&result.data, "data item", &data.value.name, allow_duplicates)?;
+            DeclarationOrder::push(&mut result.order.data, &data.value.name);
             result.data.insert(data.value.name.clone(), data);
         }
 
@@ -312,7 +859,173 @@ impl Contract {
                 .fields
                 .push(Param::from_serde(field).map_err(|err| AbiError::InvalidData { msg: err })?);
         }
+        if options.abi_version.is_some() {
+            Self::check_params_support(&version, result.fields.iter())?;
+        }
+
+        result.build_id_indexes();
+
+        if !allow_duplicates {
+            result.check_id_collisions()?;
+        }
+        if options.deny_experimental_sizes {
+            result.check_type_sizes()?;
+        }
+
+        Ok(result)
+    }
+
+    fn check_type_sizes(&self) -> Result<()> {
+        let check = |location: String, params: &[Param]| -> Result<()> {
+            for param in params {
+                if let Some(kind) = param.kind.find_invalid_size() {
+                    fail!(AbiError::InvalidTypeSize {
+                        location: format!("{} {}", location, param.name),
+                        kind: kind.type_signature(),
+                    });
+                }
+            }
+            Ok(())
+        };
+
+        check("header".to_owned(), &self.header)?;
+        for function in self.functions_in_order() {
+            check(format!("function {} inputs", function.name), &function.inputs)?;
+            check(format!("function {} outputs", function.name), &function.outputs)?;
+        }
+        for getter in self.getters_in_order() {
+            check(format!("getter {} inputs", getter.name), &getter.inputs)?;
+            check(format!("getter {} outputs", getter.name), &getter.outputs)?;
+        }
+        for event in self.events_in_order() {
+            check(format!("event {}", event.name), &event.inputs)?;
+        }
+        for item in self.data_in_order() {
+            check("data".to_owned(), std::slice::from_ref(&item.value))?;
+        }
+        check("fields".to_owned(), &self.fields)
+    }
+
+    fn id_collision(&self) -> Option<ValidationIssue> {
+        self.validate().into_iter().find(|issue| matches!(
+            issue,
+            ValidationIssue::DuplicateFunctionId { .. }
+                | ValidationIssue::DuplicateEventId { .. }
+                | ValidationIssue::EventFunctionIdCollision { .. }
+                | ValidationIssue::DuplicateGetterId { .. }
+        ))
+    }
+
+    fn check_id_collisions(&self) -> Result<()> {
+        match self.id_collision() {
+            Some(issue) => fail!(AbiError::InvalidData { msg: issue.to_string() }),
+            None => Ok(()),
+        }
+    }
+
+    /// Rebuilds functions and events id indexes after `functions` or `events` are loaded
+    pub(crate) fn build_id_indexes(&mut self) {
+        self.function_input_ids = self.functions
+            .values()
+            .map(|function| (function.get_input_id(), function.name.clone()))
+            .collect();
+        self.function_output_ids = self.functions
+            .values()
+            .map(|function| (function.get_output_id(), function.name.clone()))
+            .collect();
+        self.event_ids = self.events
+            .values()
+            .map(|event| (event.get_id(), event.name.clone()))
+            .collect();
+    }
+
+    /// Merges `other` ABI into this one, e.g. interface ABI with implementation-specific one.
+    /// Entries with the same name must be identical. ABI versions and headers must be equal.
+    /// Merged ABI is checked for id collisions of functions, events and getters.
+    pub fn merge(&self, other: &Contract) -> Result<Contract> {
+        let conflict = |msg: String| -> Result<Contract> {
+            fail!(AbiError::MergeConflict { msg })
+        };
+
+        if self.abi_version != other.abi_version {
+            return conflict(format!("ABI versions differ: {} and {}", self.abi_version, other.abi_version));
+        }
+        if self.header != other.header {
+            return conflict("headers differ".to_owned());
+        }
+        if !self.fields.is_empty() && !other.fields.is_empty()
+            && (self.fields != other.fields || self.init_fields != other.init_fields)
+        {
+            return conflict("storage fields differ".to_owned());
+        }
+
+        let mut result = self.clone();
+        if result.fields.is_empty() {
+            result.fields = other.fields.clone();
+            result.init_fields = other.init_fields.clone();
+        }
+
+        for (name, function) in &other.functions {
+            match result.functions.get(name) {
+                Some(existing) if existing != function => {
+                    return conflict(format!("function {} differs", name));
+                }
+                Some(_) => continue,
+                None => {}
+            }
+            result.functions.insert(name.clone(), function.clone());
+        }
+
+        for (name, getter) in &other.getters {
+            match result.getters.get(name) {
+                Some(existing) if existing != getter => return conflict(format!("getter {} differs", name)),
+                Some(_) => {}
+                None => { result.getters.insert(name.clone(), getter.clone()); }
+            }
+        }
+
+        for (name, event) in &other.events {
+            match result.events.get(name) {
+                Some(existing) if existing != event => {
+                    return conflict(format!("event {} differs", name));
+                }
+                Some(_) => continue,
+                None => {}
+            }
+            result.events.insert(name.clone(), event.clone());
+        }
 
+        for (name, item) in &other.data {
+            match result.data.get(name) {
+                Some(existing) if existing != item => {
+                    return conflict(format!("data item {} differs", name));
+                }
+                Some(_) => continue,
+                None => {}
+            }
+            if result.data.values().any(|existing| existing.key == item.key) {
+                return conflict(format!("data item {} key {} is already used", name, item.key));
+            }
+            result.data.insert(name.clone(), item.clone());
+        }
+
+        for item in &other.structs {
+            match result.structs.iter().find(|existing| existing.name == item.name) {
+                Some(existing) if existing != item => return conflict(format!("struct {} differs", item.name)),
+                Some(_) => {}
+                None => result.structs.push(item.clone()),
+            }
+        }
+
+        for (location, attributes) in &other.param_attributes {
+            result.param_attributes.entry(location.clone()).or_insert_with(|| attributes.clone());
+        }
+
+        result.order.extend(&other.order);
+        result.build_id_indexes();
+        if let Some(issue) = result.id_collision() {
+            return conflict(issue.to_string());
+        }
         Ok(result)
     }
 
@@ -356,8 +1069,58 @@ impl Contract {
         })
     }
 
-    /// Returns contract getter as `Function` struct with provided function name.
-    pub fn getter(&self, name: &str) -> Result<&Function> {
+    /// Returns contract functions in ABI declaration order
+    pub fn functions_in_order(&self) -> Vec<&Function> {
+        DeclarationOrder::ordered(&self.functions, &self.order.functions)
+    }
+
+    /// Returns contract events in ABI declaration order
+    pub fn events_in_order(&self) -> Vec<&Event> {
+        DeclarationOrder::ordered(&self.events, &self.order.events)
+    }
+
+    /// Returns contract getters in ABI declaration order
+    pub fn getters_in_order(&self) -> Vec<&Getter> {
+        DeclarationOrder::ordered(&self.getters, &self.order.getters)
+    }
+
+    /// Returns contract data items in ABI declaration order
+    pub fn data_in_order(&self) -> Vec<&DataItem> {
+        DeclarationOrder::ordered(&self.data, &self.order.data)
+    }
+
+    /// Returns attributes declared in ABI JSON for top-level param at `location`. Location
+    /// has the same form as in `ValidationIssue`: params list followed by param name, e.g.
+    /// `function transfer inputs dest`, `getter balance outputs value`, `event Sent amount`,
+    /// `data owner`, `fields seqno` or `header expire`
+    pub fn param_attributes(&self, location: &str) -> Option<&ParamAttributes> {
+        self.param_attributes.get(location)
+    }
+
+    /// Returns attributes of params list at `location` in the same order as `params`
+    pub(crate) fn params_attributes(&self, location: &str, params: &[Param]) -> Vec<ParamAttributes> {
+        params
+            .iter()
+            .map(|param| {
+                self.param_attributes(&format!("{} {}", location, param.name))
+                    .cloned()
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    /// Returns `Event` struct with provided event name.
+    pub fn event(&self, name: &str) -> Result<&Event> {
+        self.events.get(name).ok_or_else(|| {
+            AbiError::InvalidName {
+                name: name.to_owned(),
+            }
+            .into()
+        })
+    }
+
+    /// Returns contract getter as `Getter` struct with provided getter name.
+    pub fn getter(&self, name: &str) -> Result<&Getter> {
         self.getters.get(name).ok_or_else(|| {
             AbiError::InvalidName {
                 name: name.to_owned(),
@@ -366,31 +1129,160 @@ impl Contract {
         })
     }
 
+    /// Returns `Getter` struct with provided get-method id.
+    pub fn getter_by_id(&self, id: u32) -> Result<&Getter> {
+        self.getters
+            .values()
+            .find(|getter| getter.get_id() == id)
+            .ok_or_else(|| AbiError::InvalidFunctionId { id }.into())
+    }
+
+    /// Returns named struct declared in `structs` section
+    pub fn struct_def(&self, name: &str) -> Option<&StructDef> {
+        self.structs.iter().find(|item| item.name == name)
+    }
+
+    /// Decodes output of getter with provided name returned by get-method execution
+    pub fn decode_getter_output(&self, name: &str, data: SliceData) -> Result<Vec<Token>> {
+        self.getter(name)?.decode_output(data, false)
+    }
+
     /// Returns `Function` struct with provided function id.
     pub fn function_by_id(&self, id: u32, input: bool) -> Result<&Function> {
-        for func in self.functions.values() {
-            let func_id = if input {
-                func.get_input_id()
-            } else {
-                func.get_output_id()
-            };
-            if func_id == id {
-                return Ok(func);
-            }
+        if input {
+            self.function_by_input_id(id)
+        } else {
+            self.function_by_output_id(id)
         }
+    }
 
-        Err(AbiError::InvalidFunctionId { id }.into())
+    /// Returns `Function` struct with provided input id. Functions added after the contract
+    /// was loaded and missing in the id index are found by linear scan.
+    pub fn function_by_input_id(&self, id: u32) -> Result<&Function> {
+        self.function_input_ids
+            .get(&id)
+            .and_then(|name| self.functions.get(name))
+            .filter(|function| function.get_input_id() == id)
+            .or_else(|| self.functions.values().find(|function| function.get_input_id() == id))
+            .ok_or_else(|| AbiError::InvalidFunctionId { id }.into())
     }
 
-    /// Returns `Event` struct with provided function id.
-    pub fn event_by_id(&self, id: u32) -> Result<&Event> {
-        for event in self.events.values() {
-            if event.get_id() == id {
-                return Ok(event);
+    /// Returns `Function` struct with provided output id. Functions added after the contract
+    /// was loaded and missing in the id index are found by linear scan.
+    pub fn function_by_output_id(&self, id: u32) -> Result<&Function> {
+        self.function_output_ids
+            .get(&id)
+            .and_then(|name| self.functions.get(name))
+            .filter(|function| function.get_output_id() == id)
+            .or_else(|| self.functions.values().find(|function| function.get_output_id() == id))
+            .ok_or_else(|| AbiError::InvalidFunctionId { id }.into())
+    }
+
+    /// Returns canonical ABI representation used for fingerprint calculation.
+    ///
+    /// Canonical form consists of lines in the following order:
+    /// * `version:<major>.<minor>`
+    /// * `header:<params>`
+    /// * `function:<name>(<inputs>)(<outputs>)<input id>:<output id>` sorted by name
+    /// * `getter:<name>(<inputs>)(<outputs>)` sorted by name
+    /// * `event:<name>(<inputs>)<id>` sorted by name
+    /// * `data:<key>:<param>` sorted by key
+    /// * `fields:<params>` in declaration order, init fields are prefixed with `init `
+    ///
+    /// Params are written as `<name>:<type>` separated by `,`, where type is ABI type
+    /// signature with tuple components written as params. Ids are in lower case hex.
+    pub fn canonical_form(&self) -> String {
+        fn param_type(kind: &ParamType) -> String {
+            match kind {
+                ParamType::Tuple(components) => format!("({})", params(components)),
+                ParamType::Array(kind) => format!("{}[]", param_type(kind)),
+                ParamType::FixedArray(kind, size) => format!("{}[{}]", param_type(kind), size),
+                ParamType::Map(key, value) => format!("map({},{})", param_type(key), param_type(value)),
+                ParamType::Optional(kind) => format!("optional({})", param_type(kind)),
+                ParamType::Ref(kind) => format!("ref({})", param_type(kind)),
+                ParamType::Enum(variants) => format!("enum({})", variants.join(",")),
+                kind => kind.type_signature(),
             }
         }
+        fn param(param: &Param) -> String {
+            format!("{}:{}", param.name, param_type(&param.kind))
+        }
+        fn params(params: &[Param]) -> String {
+            params.iter().map(param).collect::<Vec<_>>().join(",")
+        }
+        fn sorted<T>(map: &HashMap<String, T>) -> Vec<&T> {
+            let mut entries = map.iter().collect::<Vec<_>>();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            entries.into_iter().map(|(_, value)| value).collect()
+        }
+
+        let mut lines = vec![
+            format!("version:{}", self.abi_version),
+            format!("header:{}", params(&self.header)),
+        ];
+        for function in sorted(&self.functions) {
+            lines.push(format!(
+                "function:{}({})({}){:08x}:{:08x}",
+                function.name,
+                params(&function.inputs),
+                params(&function.outputs),
+                function.get_input_id(),
+                function.get_output_id(),
+            ));
+        }
+        for getter in sorted(&self.getters) {
+            lines.push(format!(
+                "getter:{}({})({})",
+                getter.name,
+                params(&getter.inputs),
+                params(&getter.outputs),
+            ));
+        }
+        for event in sorted(&self.events) {
+            lines.push(format!("event:{}({}){:08x}", event.name, params(&event.inputs), event.get_id()));
+        }
+        let mut data = self.data.values().collect::<Vec<_>>();
+        data.sort_by_key(|item| item.key);
+        for item in data {
+            lines.push(format!("data:{}:{}", item.key, param(&item.value)));
+        }
+        let fields = self.fields
+            .iter()
+            .map(|field| if self.init_fields.contains(&field.name) {
+                format!("init {}", param(field))
+            } else {
+                param(field)
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        lines.push(format!("fields:{}", fields));
+
+        lines.join("\n")
+    }
+
+    /// Computes stable ABI fingerprint: sha256 hash of `canonical_form`. Fingerprint doesn't
+    /// depend on JSON formatting and order of functions, events and data items.
+    pub fn fingerprint(&self) -> UInt256 {
+        UInt256::from_slice(&Sha256::digest(self.canonical_form().as_bytes()))
+    }
+
+    /// Returns selectors of all contract events keyed by event name
+    pub fn event_selectors(&self) -> HashMap<String, EventSelector> {
+        self.events
+            .iter()
+            .map(|(name, event)| (name.clone(), event.selector()))
+            .collect()
+    }
 
-        Err(AbiError::InvalidFunctionId { id }.into())
+    /// Returns `Event` struct with provided function id. Events added after the contract
+    /// was loaded and missing in the id index are found by linear scan.
+    pub fn event_by_id(&self, id: u32) -> Result<&Event> {
+        self.event_ids
+            .get(&id)
+            .and_then(|name| self.events.get(name))
+            .filter(|event| event.get_id() == id)
+            .or_else(|| self.events.values().find(|event| event.get_id() == id))
+            .ok_or_else(|| AbiError::InvalidFunctionId { id }.into())
     }
 
     /// Decodes contract answer and returns name of the function called
@@ -417,6 +1309,11 @@ impl Contract {
         }
     }
 
+    /// Decodes contract answer stored in `Cell` and returns name of the function called
+    pub fn decode_output_from_cell(&self, data: &Cell, internal: bool) -> Result<DecodedMessage> {
+        self.decode_output(SliceData::load_cell(data.clone())?, internal)
+    }
+
     /// Decodes contract answer and returns name of the function called
     pub fn decode_input(
         &self,
@@ -424,18 +1321,248 @@ impl Contract {
         internal: bool,
         allow_partial: bool,
     ) -> Result<DecodedMessage> {
+        self.decode_input_with_header(data, internal, allow_partial)
+            .map(|(decoded, _)| decoded)
+    }
+
+    /// Decodes function call stored in `Cell` and returns name of the function called
+    pub fn decode_input_from_cell(
+        &self,
+        data: &Cell,
+        internal: bool,
+        allow_partial: bool,
+    ) -> Result<DecodedMessage> {
+        self.decode_input(SliceData::load_cell(data.clone())?, internal, allow_partial)
+    }
+
+    /// Decodes function call body. If body id doesn't match any function input, body is decoded
+    /// as event (some contracts send event-like callbacks in internal messages). Returns kind
+    /// of matched body along with decoded name and values
+    pub fn decode_input_or_event(
+        &self,
+        data: SliceData,
+        internal: bool,
+        allow_partial: bool,
+    ) -> Result<(MessageBodyKind, DecodedMessage)> {
+        let function = Function::decode_input_id(&self.abi_version, data.clone(), &self.header, internal)
+            .ok()
+            .and_then(|id| self.function_by_id(id, true).ok());
+        if let Some(function) = function {
+            let tokens = function.decode_input(data, internal, allow_partial)?;
+            return Ok((MessageBodyKind::FunctionInput, DecodedMessage {
+                function_name: function.name.clone(),
+                tokens,
+            }));
+        }
+
+        let event = self.event_by_id(Event::decode_id(data.clone())?)?;
+        let tokens = if allow_partial {
+            event.decode_input_partial(data)?.0
+        } else {
+            event.decode_input(data)?
+        };
+        Ok((MessageBodyKind::Event, DecodedMessage {
+            function_name: event.name.clone(),
+            tokens,
+        }))
+    }
+
+    /// Decodes function call body. Returns decoded function name and inputs along with
+    /// header values (`time`, `expire`, `pubkey` etc.)
+    pub fn decode_input_with_header(
+        &self,
+        data: SliceData,
+        internal: bool,
+        allow_partial: bool,
+    ) -> Result<(DecodedMessage, Vec<Token>)> {
         let original_data = data.clone();
 
         let func_id = Function::decode_input_id(&self.abi_version, data, &self.header, internal)?;
 
         let func = self.function_by_id(func_id, true)?;
 
-        let tokens = func.decode_input(original_data, internal, allow_partial)?;
+        let (header, tokens) = func.decode_input_with_header(original_data, internal, allow_partial)?;
 
-        Ok(DecodedMessage {
+        Ok((DecodedMessage {
             function_name: func.name.clone(),
             tokens,
-        })
+        }, header))
+    }
+
+    /// Decodes event body if its id is in provided `ids` set, returns `None` otherwise.
+    /// Use `EventFilter` to decode multiple messages with the same filter.
+    pub fn decode_event_filtered(&self, body: SliceData, ids: &HashSet<u32>) -> Result<Option<DecodedMessage>> {
+        let id = Event::decode_id(body.clone())?;
+        if !ids.contains(&id) {
+            return Ok(None);
+        }
+        match self.event_by_id(id) {
+            Ok(event) => Ok(Some(DecodedMessage {
+                function_name: event.name.clone(),
+                tokens: event.decode_input(body)?,
+            })),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Decodes message body as function call, function response or event.
+    /// Returns `DecodedBody::Unknown` if body id doesn't match any function or event.
+    pub fn decode_any(&self, body: SliceData, internal: bool) -> Result<DecodedBody> {
+        if let Some(decoded) = self.decode_any_input(body.clone(), internal)? {
+            return Ok(decoded);
+        }
+        self.decode_any_output(body, internal)
+    }
+
+    fn decode_any_input(&self, body: SliceData, internal: bool) -> Result<Option<DecodedBody>> {
+        if let Ok(id) = Function::decode_input_id(
+            &self.abi_version, body.clone(), &self.header, internal
+        ) {
+            if let Ok(function) = self.function_by_id(id, true) {
+                let (header, tokens) = function.decode_input_with_header(body, internal, false)?;
+                return Ok(Some(DecodedBody::FunctionInput {
+                    name: function.name.clone(),
+                    header,
+                    tokens,
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    fn decode_any_output(&self, body: SliceData, internal: bool) -> Result<DecodedBody> {
+        let mut remainder = body.clone();
+        let id = remainder.get_next_u32()?;
+        if let Ok(function) = self.function_by_id(id, false) {
+            return Ok(DecodedBody::FunctionOutput {
+                name: function.name.clone(),
+                tokens: function.decode_output(body, internal)?,
+            });
+        }
+        if let Ok(event) = self.event_by_id(id) {
+            return Ok(DecodedBody::Event {
+                name: event.name.clone(),
+                tokens: event.decode_input(body)?,
+            });
+        }
+
+        Ok(DecodedBody::Unknown { id, remainder })
+    }
+
+    /// Decodes message body according to message direction: external inbound message body
+    /// is decoded as function call, external outbound one as function response or event,
+    /// internal one as function call or function response.
+    /// Returns `DecodedBody::Unknown` if body id doesn't match any function or event.
+    pub fn decode_message(&self, message: &Message) -> Result<DecodedBody> {
+        let body = message.body().ok_or_else(|| AbiError::InvalidData {
+            msg: "Message has no body".to_owned(),
+        })?;
+        match message.header() {
+            CommonMsgInfo::ExtInMsgInfo(_) => match self.decode_any_input(body.clone(), false)? {
+                Some(decoded) => Ok(decoded),
+                None => {
+                    let mut remainder = body;
+                    let id = remainder.get_next_u32()?;
+                    Ok(DecodedBody::Unknown { id, remainder })
+                }
+            },
+            CommonMsgInfo::ExtOutMsgInfo(_) => self.decode_any_output(body, false),
+            CommonMsgInfo::IntMsgInfo(_) => self.decode_any(body, true),
+        }
+    }
+
+    /// Decodes inbound and outbound messages of transaction. Messages without body or with
+    /// unknown body id are returned in `DecodedTransaction::unmatched`, messages which match
+    /// contract ABI but can't be decoded are returned with decoding error in
+    /// `DecodedTransaction::failed`.
+    pub fn decode_transaction(&self, transaction: &Transaction) -> Result<DecodedTransaction> {
+        let mut result = DecodedTransaction {
+            in_msg: None,
+            out_msgs: vec![],
+            unmatched: vec![],
+            failed: vec![],
+        };
+
+        if let Some(message) = transaction.in_msg.as_ref().map(|msg| msg.read_struct()).transpose()? {
+            if message.body().is_none() {
+                result.unmatched.push(message);
+            } else {
+                let decoded = self.decode_message(&message);
+                result.push(message, decoded, true);
+            }
+        }
+
+        transaction.iterate_out_msgs(|message| {
+            if message.body().is_none() {
+                result.unmatched.push(message);
+            } else {
+                let decoded = self.decode_message(&message);
+                result.push(message, decoded, false);
+            }
+            Ok(true)
+        })?;
+
+        Ok(result)
+    }
+
+    /// Decodes message body against every provided contract (functions inputs, outputs
+    /// and events) and returns all successful decodings. Complete decodings (without
+    /// trailing data) go first.
+    pub fn decode_candidates(
+        contracts: &[Contract],
+        body: SliceData,
+        internal: bool,
+    ) -> Vec<CandidateMatch> {
+        let mut result = vec![];
+        for (contract_index, contract) in contracts.iter().enumerate() {
+            let mut push = |kind, name: &str, decode: &dyn Fn(bool) -> Result<Vec<Token>>| {
+                let decoded = decode(false)
+                    .map(|tokens| (tokens, true))
+                    .or_else(|_| decode(true).map(|tokens| (tokens, false)));
+                if let Ok((tokens, complete)) = decoded {
+                    result.push(CandidateMatch {
+                        contract_index,
+                        kind,
+                        name: name.to_owned(),
+                        tokens,
+                        complete,
+                    });
+                }
+            };
+
+            if let Ok(id) = Function::decode_input_id(
+                &contract.abi_version, body.clone(), &contract.header, internal
+            ) {
+                if let Ok(function) = contract.function_by_id(id, true) {
+                    push(MessageBodyKind::FunctionInput, &function.name, &|allow_partial| {
+                        function.decode_input(body.clone(), internal, allow_partial)
+                    });
+                }
+            }
+
+            if let Ok(id) = Function::decode_output_id(body.clone()) {
+                if let Ok(function) = contract.function_by_id(id, false) {
+                    push(MessageBodyKind::FunctionOutput, &function.name, &|allow_partial| {
+                        if allow_partial {
+                            function.decode_output_partial(body.clone(), internal)
+                        } else {
+                            function.decode_output(body.clone(), internal)
+                        }
+                    });
+                }
+                if let Ok(event) = contract.event_by_id(id) {
+                    push(MessageBodyKind::Event, &event.name, &|allow_partial| {
+                        if allow_partial {
+                            event.decode_input_partial(body.clone()).map(|(tokens, _)| tokens)
+                        } else {
+                            event.decode_input(body.clone())
+                        }
+                    });
+                }
+            }
+        }
+        result.sort_by_key(|candidate| !candidate.complete);
+        result
     }
 
     pub const DATA_MAP_KEYLEN: usize = 64;
@@ -462,19 +1589,72 @@ impl Contract {
 
         for token in tokens {
             let builder = token.value.pack_into_chain(&self.abi_version)?;
-            let key = self
-                .data
-                .get(&token.name)
-                .ok_or_else(|| AbiError::InvalidData {
-                    msg: format!("data item {} not found in contract ABI", token.name),
-                })?
-                .key;
+            let key = self.data_item(&token.name)?.key;
 
             map.set_builder(SliceData::load_builder(key.write_to_new_cell()?)?, &builder)?;
         }
         SliceData::load_cell(map.serialize()?)
     }
 
+    /// Builds initial data dictionary from provided data items values and public key
+    /// stored at key 0
+    pub fn encode_data(&self, tokens: &[Token], pubkey: Option<&PublicKeyData>) -> Result<Cell> {
+        for token in tokens {
+            if !token.value.type_check(&self.data_item(&token.name)?.value.kind) {
+                fail!(AbiError::WrongParameterType);
+            }
+        }
+        let mut data = self.update_data(SliceData::default(), tokens)?;
+        if let Some(pubkey) = pubkey {
+            data = Self::insert_pubkey(data, pubkey)?;
+        }
+        Ok(data.into_cell())
+    }
+
+    fn data_item(&self, name: &str) -> Result<&DataItem> {
+        self.data.get(name).ok_or_else(|| {
+            AbiError::InvalidData {
+                msg: format!("data item {} not found in contract ABI", name),
+            }
+            .into()
+        })
+    }
+
+    /// Decodes value of data item `name` from contract data. Returns `None` if data
+    /// has no value for the item key
+    pub fn get_data_item(&self, data: SliceData, name: &str) -> Result<Option<TokenValue>> {
+        self.check_data_map_support()?;
+        let item = self.data_item(name)?;
+        let map = HashmapE::with_hashmap(Self::DATA_MAP_KEYLEN, data.reference_opt(0));
+        let key = SliceData::load_builder(item.key.write_to_new_cell()?)?;
+        map.get(key)?
+            .map(|value| {
+                let (value, _) = TokenValue::read_from(
+                    &item.value.kind, Cursor::from(value), true, &self.abi_version, false)?;
+                Ok(value)
+            })
+            .transpose()
+    }
+
+    /// Sets value of data item `name` in contract data
+    pub fn set_data_item(&self, data: SliceData, name: &str, value: TokenValue) -> Result<SliceData> {
+        self.check_data_map_support()?;
+        let item = self.data_item(name)?;
+        if !value.type_check(&item.value.kind) {
+            fail!(AbiError::WrongParameterType);
+        }
+        self.update_data(data, &[Token::new(name, value)])
+    }
+
+    /// Removes value of data item `name` from contract data
+    pub fn remove_data_item(&self, data: SliceData, name: &str) -> Result<SliceData> {
+        self.check_data_map_support()?;
+        let key = self.data_item(name)?.key;
+        let mut map = HashmapE::with_hashmap(Self::DATA_MAP_KEYLEN, data.reference_opt(0));
+        map.remove(SliceData::load_builder(key.write_to_new_cell()?)?)?;
+        SliceData::load_cell(map.serialize()?)
+    }
+
 
     /// Decode init data or init fields of a contract based on its ABI version
     pub fn decode_init_data(&self, data: SliceData) -> Result<Vec<Token>> {
@@ -485,6 +1665,33 @@ impl Contract {
         }
     }
 
+    /// Decode init data or init fields of a contract reporting status of every declared
+    /// data item (before ABI 2.4) or storage field (since 2.4). Values absent in data are
+    /// reported as missing if `encode_initial_data` requires them and as defaulted otherwise
+    pub fn decode_init_data_detailed(&self, data: SliceData) -> Result<Vec<InitValue>> {
+        if self.data_map_supported() {
+            let mut items = self.data.values().collect::<Vec<_>>();
+            items.sort_by_key(|item| item.key);
+            items
+                .into_iter()
+                .map(|item| {
+                    let value = self.get_data_item(data.clone(), &item.value.name)?;
+                    Ok(InitValue::new(&item.value, false, value))
+                })
+                .collect()
+        } else {
+            let (tokens, defaulted) = self.decode_storage_fields_with_defaults(data, true)?;
+            Ok(self.fields
+                .iter()
+                .zip(tokens)
+                .map(|(param, token)| {
+                    let value = Some(token.value).filter(|_| !defaulted.contains(&token.name));
+                    InitValue::new(param, self.init_fields.contains(&param.name), value)
+                })
+                .collect())
+        }
+    }
+
     fn decode_init_data_internal(&self, data: SliceData) -> Result<Vec<Token>> {
         self.check_data_map_support()?;
         let map = HashmapE::with_hashmap(Contract::DATA_MAP_KEYLEN, data.reference_opt(0));
@@ -562,6 +1769,40 @@ impl Contract {
         SliceData::load_cell(map.serialize()?)
     }
 
+    /// Gets public key from contract data. Public key is read from data dictionary for ABI
+    /// versions before 2.4 and from `_pubkey` storage field since 2.4. Returns `None` if data
+    /// has no public key
+    pub fn read_pubkey(&self, data: &SliceData) -> Result<Option<PublicKeyData>> {
+        if self.data_map_supported() {
+            return Self::get_pubkey(data);
+        }
+        if !self.fields.iter().any(|param| param.name == PUBKEY_FIELD) {
+            return Ok(None);
+        }
+        let tokens = self.decode_selected_storage_fields(data.clone(), &[PUBKEY_FIELD])?;
+        Ok(tokens.first().and_then(|token| Self::pubkey_from_field_value(&token.value)))
+    }
+
+    /// Sets public key into contract data. Public key is written into data dictionary for ABI
+    /// versions before 2.4 and into `_pubkey` storage field since 2.4
+    pub fn update_pubkey(&self, data: SliceData, pubkey: &PublicKeyData) -> Result<SliceData> {
+        if self.data_map_supported() {
+            return Self::insert_pubkey(data, pubkey);
+        }
+        let param = self.fields.iter().find(|param| param.name == PUBKEY_FIELD).ok_or_else(|| {
+            AbiError::InvalidData {
+                msg: format!("Storage field '{}' not found in contract ABI", PUBKEY_FIELD),
+            }
+        })?;
+        let value = Self::pubkey_field_value(&param.kind, pubkey)?;
+
+        let mut tokens = self.decode_storage_fields(data, false)?;
+        for token in tokens.iter_mut().filter(|token| token.name == PUBKEY_FIELD) {
+            token.value = value.clone();
+        }
+        SliceData::load_builder(TokenValue::pack_values_into_chain(&tokens, vec![], &self.abi_version)?)
+    }
+
     /// Add sign to messsage body returned by `prepare_input_for_sign` function
     pub fn add_sign_to_encoded_input(
         &self,
@@ -572,13 +1813,273 @@ impl Contract {
         Function::add_sign_to_encoded_input(&self.abi_version, signature, public_key, function_call)
     }
 
+    /// Verifies signature of external call body of any contract function with provided
+    /// public key. Returns `false` if body is not signed
+    pub fn verify_signature(
+        &self,
+        body: SliceData,
+        pubkey: &PublicKeyData,
+        address: Option<MsgAddressInt>,
+        signature_id: Option<i32>,
+    ) -> Result<bool> {
+        let pubkey = ed25519_dalek::PublicKey::from_bytes(pubkey)?;
+        Function::verify_encoded_input(&self.abi_version, body, &pubkey, address, signature_id)
+    }
+
+    /// Computes address of contract deployed with provided `code`, initial values and
+    /// public key. Initial values are data items for ABI versions before 2.4 and `init`
+    /// storage fields since 2.4. If `pubkey` is not provided, zero public key is used.
+    pub fn compute_address(
+        &self,
+        code: Cell,
+        init: HashMap<String, TokenValue>,
+        workchain: i8,
+        pubkey: Option<&PublicKeyData>,
+    ) -> Result<MsgAddressInt> {
+        let hash = self.encode_state_init(code, init, pubkey)?.serialize()?.repr_hash();
+        MsgAddressInt::with_standart(None, workchain, hash.into())
+    }
+
+    /// Constructs `StateInit` with provided `code` and data built from initial values and
+    /// public key. Initial values are data items for ABI versions before 2.4 and `init`
+    /// storage fields since 2.4. If `pubkey` is not provided, zero public key is used.
+    pub fn encode_state_init(
+        &self,
+        code: Cell,
+        init: HashMap<String, TokenValue>,
+        pubkey: Option<&PublicKeyData>,
+    ) -> Result<StateInit> {
+        Ok(StateInit {
+            code: Some(code),
+            data: Some(self.encode_initial_data(init, pubkey)?),
+            ..Default::default()
+        })
+    }
+
+    /// Encodes external inbound message deploying contract: `StateInit` built from `code`,
+    /// initial values and public key of `pair` is attached to signed constructor call sent
+    /// to computed contract address.
+    pub fn encode_deploy_message(
+        &self,
+        header: &HashMap<String, TokenValue>,
+        input: &[Token],
+        code: Cell,
+        init: HashMap<String, TokenValue>,
+        pair: Option<(&Keypair, Option<i32>)>,
+        workchain: i8,
+    ) -> Result<Message> {
+        self.encode_deploy_message_with_signer(
+            header,
+            input,
+            code,
+            init,
+            pair.map(|(pair, signature_id)| (pair as &dyn Signer, signature_id)),
+            workchain,
+        )
+    }
+
+    /// Encodes external inbound message deploying contract. Constructor call is signed by
+    /// provided `Signer` and its public key is used in initial data and call header.
+    pub fn encode_deploy_message_with_signer(
+        &self,
+        header: &HashMap<String, TokenValue>,
+        input: &[Token],
+        code: Cell,
+        init: HashMap<String, TokenValue>,
+        signer: Option<(&dyn Signer, Option<i32>)>,
+        workchain: i8,
+    ) -> Result<Message> {
+        let constructor = self.function("constructor")?;
+        let public_key = signer.map(|(signer, _)| signer.public_key());
+
+        let state_init = self.encode_state_init(code, init, public_key.map(|key| key.to_bytes()).as_ref())?;
+        let address = MsgAddressInt::with_standart(None, workchain, state_init.serialize()?.repr_hash().into())?;
+
+        let mut header = header.clone();
+        if let Some(public_key) = public_key {
+            for param in constructor.header_params() {
+                if param.kind == ParamType::PublicKey {
+                    header.entry(param.name.clone()).or_insert(TokenValue::PublicKey(Some(public_key)));
+                }
+            }
+        }
+        let body = constructor.encode_input_with_signer(&header, input, false, signer, Some(address.clone()))?;
+
+        let mut message = Message::with_ext_in_header(ExternalInboundMessageHeader {
+            dst: address,
+            ..Default::default()
+        });
+        message.set_state_init(state_init);
+        message.set_body(SliceData::load_builder(body)?);
+        Ok(message)
+    }
+
+    /// Builds contract data from initial values and public key according to ABI version
+    pub fn encode_initial_data(
+        &self,
+        init: HashMap<String, TokenValue>,
+        pubkey: Option<&PublicKeyData>,
+    ) -> Result<Cell> {
+        let pubkey = pubkey.copied().unwrap_or_default();
+        if self.data_map_supported() {
+            let mut tokens = init
+                .into_iter()
+                .map(|(name, value)| Token { name, value })
+                .collect::<Vec<_>>();
+            tokens.sort_by(|a, b| a.name.cmp(&b.name));
+            self.encode_data(&tokens, Some(&pubkey))
+        } else {
+            self.encode_storage_fields_with_pubkey(init, Some(&pubkey))?.into_cell()
+        }
+    }
+
+    /// Replaces code in `state_init` keeping its data. If `migrate_from` ABI is provided, data
+    /// is decoded with it and re-encoded according to this ABI: values having the same name
+    /// and type in both ABIs and public key are preserved, new non-`init` storage fields
+    /// get default values.
+    pub fn replace_code(
+        &self,
+        state_init: &StateInit,
+        code: Cell,
+        migrate_from: Option<&Contract>,
+    ) -> Result<StateInit> {
+        let mut state_init = state_init.clone();
+        state_init.code = Some(code);
+        if let Some(old) = migrate_from {
+            let data = state_init.data.clone().ok_or_else(|| AbiError::InvalidData {
+                msg: "StateInit has no data".to_owned(),
+            })?;
+            state_init.data = Some(self.migrate_data(old, SliceData::load_cell(data)?)?);
+        }
+        Ok(state_init)
+    }
+
+    fn migrate_data(&self, old: &Contract, data: SliceData) -> Result<Cell> {
+        let (tokens, pubkey) = if old.data_map_supported() {
+            #[allow(deprecated)]
+            let tokens = old.decode_data(data.clone(), false)?;
+            (tokens, Self::get_pubkey(&data)?)
+        } else {
+            let (tokens, _) = old.decode_storage_fields_with_defaults(data.clone(), true)?;
+            let pubkey = tokens
+                .iter()
+                .find(|token| token.name == PUBKEY_FIELD)
+                .and_then(|token| Self::pubkey_from_field_value(&token.value));
+            (tokens, pubkey)
+        };
+
+        let new_params: Vec<&Param> = if self.data_map_supported() {
+            self.data.values().map(|item| &item.value).collect()
+        } else {
+            self.fields.iter().collect()
+        };
+        let mut values = tokens
+            .into_iter()
+            .filter(|token| new_params.iter().any(|param| {
+                param.name == token.name && token.value.type_check(&param.kind)
+            }))
+            .map(|token| (token.name, token.value))
+            .collect::<HashMap<_, _>>();
+
+        if self.data_map_supported() {
+            values.remove(PUBKEY_FIELD);
+            let mut tokens = values
+                .into_iter()
+                .map(|(name, value)| Token { name, value })
+                .collect::<Vec<_>>();
+            tokens.sort_by(|a, b| a.name.cmp(&b.name));
+            let data = if old.data_map_supported() {
+                self.update_data(data, &tokens)?
+            } else {
+                self.update_data(SliceData::default(), &tokens)?
+            };
+            let data = match pubkey {
+                Some(pubkey) => Self::insert_pubkey(data, &pubkey)?,
+                None => data,
+            };
+            return Ok(data.into_cell());
+        }
+
+        if let (Some(pubkey), Some(param)) =
+            (pubkey, self.fields.iter().find(|param| param.name == PUBKEY_FIELD))
+        {
+            if !values.contains_key(PUBKEY_FIELD) {
+                values.insert(PUBKEY_FIELD.to_owned(), Self::pubkey_field_value(&param.kind, &pubkey)?);
+            }
+        }
+        let mut tokens = Vec::with_capacity(self.fields.len());
+        for param in &self.fields {
+            let value = match values.remove(&param.name) {
+                Some(value) => value,
+                None if self.init_fields.contains(&param.name) => fail!(AbiError::InvalidData {
+                    msg: format!(
+                        "Storage field '{}' is marked as `init` and can't be migrated from old data",
+                        param.name
+                    ),
+                }),
+                None => TokenValue::default_value(&param.kind),
+            };
+            tokens.push(Token { name: param.name.clone(), value });
+        }
+        TokenValue::pack_values_into_chain(&tokens, vec![], &self.abi_version)?.into_cell()
+    }
+
+    fn pubkey_field_value(kind: &ParamType, pubkey: &PublicKeyData) -> Result<TokenValue> {
+        Ok(match kind {
+            ParamType::Uint(256) => TokenValue::Uint(crate::Uint {
+                number: num_bigint::BigUint::from_bytes_be(pubkey),
+                size: 256,
+            }),
+            ParamType::FixedBytes(32) => TokenValue::FixedBytes(pubkey.to_vec()),
+            _ => fail!(AbiError::InvalidData {
+                msg: format!(
+                    "Storage field '{}' should have type uint256 or fixedbytes32", PUBKEY_FIELD)
+            }),
+        })
+    }
+
+    fn pubkey_from_field_value(value: &TokenValue) -> Option<PublicKeyData> {
+        let bytes = match value {
+            TokenValue::Uint(uint) => {
+                let bytes = uint.number.to_bytes_be();
+                let mut pubkey = PublicKeyData::default();
+                pubkey.get_mut(32usize.checked_sub(bytes.len())?..)?.copy_from_slice(&bytes);
+                return Some(pubkey);
+            }
+            TokenValue::FixedBytes(bytes) => bytes,
+            _ => return None,
+        };
+        bytes.as_slice().try_into().ok()
+    }
+
     /// Encode account storage fields
     pub fn encode_storage_fields(
+        &self,
+        init_fields: HashMap<String, TokenValue>,
+    ) -> Result<BuilderData> {
+        self.encode_storage_fields_with_pubkey(init_fields, None)
+    }
+
+    /// Encode account storage fields setting `_pubkey` field to provided public key
+    /// even if it is not marked as `init`
+    fn encode_storage_fields_with_pubkey(
         &self,
         mut init_fields: HashMap<String, TokenValue>,
+        pubkey: Option<&PublicKeyData>,
     ) -> Result<BuilderData> {
         self.check_init_fields_support()?;
 
+        if let Some(pubkey) = pubkey {
+            if let Some(param) = self.fields.iter().find(|param| param.name == PUBKEY_FIELD) {
+                let value = Self::pubkey_field_value(&param.kind, pubkey)?;
+                if self.init_fields.contains(PUBKEY_FIELD) {
+                    init_fields.entry(PUBKEY_FIELD.to_owned()).or_insert(value);
+                } else {
+                    init_fields.insert(PUBKEY_FIELD.to_owned(), value);
+                }
+            }
+        }
+
         let mut tokens = vec![];
         for param in &self.fields {
             let token = init_fields
@@ -593,6 +2094,8 @@ impl Contract {
                     ),
                 })?;
                 tokens.push(token);
+            } else if param.name == PUBKEY_FIELD && pubkey.is_some() && token.is_some() {
+                tokens.extend(token);
             } else {
                 if token.is_some() {
                     return Err(error!(AbiError::InvalidInputData {
@@ -619,6 +2122,73 @@ impl Contract {
     ) -> Result<Vec<Token>> {
         TokenValue::decode_params(&self.fields, data, &self.abi_version, allow_partial)
     }
+
+    /// Decode account storage fields. Data remaining after fields (allowed only with
+    /// `allow_partial`) and deprecated field types are reported into `warnings`
+    pub fn decode_storage_fields_with_warnings(
+        &self,
+        data: SliceData,
+        allow_partial: bool,
+        warnings: &mut Warnings,
+    ) -> Result<Vec<Token>> {
+        warnings.check_deprecated_types("field", &self.fields);
+        TokenValue::decode_params_with_warnings(&self.fields, data, &self.abi_version, allow_partial, warnings)
+    }
+
+    /// Decode account storage fields stored in `Cell`
+    pub fn decode_storage_fields_from_cell(&self, data: &Cell, allow_partial: bool) -> Result<Vec<Token>> {
+        TokenValue::decode_params_from_cell(&self.fields, data, &self.abi_version, allow_partial)
+    }
+
+    /// Decode only storage fields with provided names. Fields preceding requested ones are
+    /// skipped without constructing tokens and fields after the last requested one are not read.
+    /// Tokens are returned in storage layout order.
+    pub fn decode_selected_storage_fields(
+        &self,
+        data: SliceData,
+        names: &[&str],
+    ) -> Result<Vec<Token>> {
+        for name in names {
+            if !self.fields.iter().any(|field| field.name == *name) {
+                fail!(AbiError::InvalidName { name: name.to_string() });
+            }
+        }
+        let last = match self.fields.iter().rposition(|field| names.contains(&field.name.as_str())) {
+            Some(last) => last,
+            None => return Ok(vec![]),
+        };
+
+        let mut tokens = Vec::with_capacity(names.len());
+        let mut cursor: Cursor = data.into();
+        let mut skipped = 0;
+        for (index, field) in self.fields[..=last].iter().enumerate() {
+            if !names.contains(&field.name.as_str()) {
+                continue;
+            }
+            cursor = TokenValue::skip(&self.fields[skipped..index], cursor, &self.abi_version)?;
+            let (mut decoded, next) = TokenValue::decode_params_with_cursor(
+                std::slice::from_ref(field),
+                cursor,
+                &self.abi_version,
+                true,
+                index + 1 == self.fields.len(),
+            )?;
+            tokens.append(&mut decoded);
+            cursor = next;
+            skipped = index + 1;
+        }
+        Ok(tokens)
+    }
+
+    /// Decode account storage fields filling fields missing at the end of data with
+    /// default values. Returns decoded fields and names of the defaulted ones.
+    pub fn decode_storage_fields_with_defaults(
+        &self,
+        data: SliceData,
+        allow_partial: bool,
+    ) -> Result<(Vec<Token>, Vec<String>)> {
+        TokenValue::decode_params_with_defaults(&self.fields, data, &self.abi_version, allow_partial)
+    }
 }
 
 #[cfg(test)]
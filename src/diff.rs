@@ -0,0 +1,177 @@
+/*
+* Copyright 2018-2020 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Contract ABI compatibility checks.
+
+use crate::contract::{AbiVersion, Contract};
+use crate::param::Param;
+
+/// Change of function or event present in both compared ABIs
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MemberChange {
+    /// Function or event name
+    pub name: String,
+    /// Function or event id changed, so old calls can't be matched
+    pub id_changed: bool,
+    /// Parameters types changed, so encoded data layout differs
+    pub layout_changed: bool,
+    /// Only parameters names changed, encoding is compatible
+    pub names_changed: bool,
+}
+
+impl MemberChange {
+    /// Checks if change breaks compatibility with existing callers
+    pub fn is_breaking(&self) -> bool {
+        self.id_changed || self.layout_changed
+    }
+}
+
+/// Difference between two contract ABIs
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct AbiDiff {
+    /// Old and new ABI versions if version changed
+    pub version: Option<(AbiVersion, AbiVersion)>,
+    pub added_functions: Vec<String>,
+    pub removed_functions: Vec<String>,
+    pub changed_functions: Vec<MemberChange>,
+    pub added_events: Vec<String>,
+    pub removed_events: Vec<String>,
+    pub changed_events: Vec<MemberChange>,
+    /// Header parameters types changed
+    pub header_changed: bool,
+    /// Existing storage fields layout changed. Appending new fields is not treated as a change
+    pub storage_layout_changed: bool,
+}
+
+impl AbiDiff {
+    /// Checks if no differences were found
+    pub fn is_empty(&self) -> bool {
+        self.version.is_none()
+            && self.added_functions.is_empty()
+            && self.removed_functions.is_empty()
+            && self.changed_functions.is_empty()
+            && self.added_events.is_empty()
+            && self.removed_events.is_empty()
+            && self.changed_events.is_empty()
+            && !self.header_changed
+            && !self.storage_layout_changed
+    }
+
+    /// Checks if new ABI is backward compatible with old one: nothing is removed,
+    /// all existing functions and events keep their ids and data layouts
+    pub fn is_backward_compatible(&self) -> bool {
+        self.removed_functions.is_empty()
+            && self.removed_events.is_empty()
+            && self.changed_functions.iter().all(|change| !change.is_breaking())
+            && self.changed_events.iter().all(|change| !change.is_breaking())
+            && !self.header_changed
+            && !self.storage_layout_changed
+    }
+}
+
+fn types_signature(params: &[Param]) -> Vec<String> {
+    params.iter().map(|param| param.kind.type_signature()).collect()
+}
+
+fn names(params: &[Param]) -> Vec<&str> {
+    params.iter().map(|param| param.name.as_str()).collect()
+}
+
+fn member_change(
+    name: &str,
+    id_changed: bool,
+    old_params: &[&[Param]],
+    new_params: &[&[Param]],
+) -> Option<MemberChange> {
+    let layout_changed = old_params.iter().zip(new_params)
+        .any(|(old, new)| types_signature(old) != types_signature(new));
+    let names_changed = old_params.iter().zip(new_params)
+        .any(|(old, new)| names(old) != names(new));
+    if id_changed || layout_changed || names_changed {
+        Some(MemberChange { name: name.to_owned(), id_changed, layout_changed, names_changed })
+    } else {
+        None
+    }
+}
+
+impl Contract {
+    /// Compares this ABI (old) with `other` (new) one
+    pub fn diff(&self, other: &Contract) -> AbiDiff {
+        let mut diff = AbiDiff::default();
+
+        if self.abi_version != other.abi_version {
+            diff.version = Some((self.abi_version, other.abi_version));
+        }
+
+        for (name, old) in &self.functions {
+            match other.functions.get(name) {
+                Some(new) => {
+                    let id_changed = old.get_input_id() != new.get_input_id()
+                        || old.get_output_id() != new.get_output_id();
+                    if let Some(change) = member_change(
+                        name,
+                        id_changed,
+                        &[old.inputs.as_slice(), old.outputs.as_slice()],
+                        &[new.inputs.as_slice(), new.outputs.as_slice()],
+                    ) {
+                        diff.changed_functions.push(change);
+                    }
+                }
+                None => diff.removed_functions.push(name.clone()),
+            }
+        }
+        diff.added_functions = other.functions.keys()
+            .filter(|name| !self.functions.contains_key(*name))
+            .cloned()
+            .collect();
+
+        for (name, old) in &self.events {
+            match other.events.get(name) {
+                Some(new) => {
+                    if let Some(change) = member_change(
+                        name,
+                        old.get_id() != new.get_id(),
+                        &[old.inputs.as_slice()],
+                        &[new.inputs.as_slice()],
+                    ) {
+                        diff.changed_events.push(change);
+                    }
+                }
+                None => diff.removed_events.push(name.clone()),
+            }
+        }
+        diff.added_events = other.events.keys()
+            .filter(|name| !self.events.contains_key(*name))
+            .cloned()
+            .collect();
+
+        diff.header_changed = types_signature(&self.header) != types_signature(&other.header);
+
+        let old_fields = types_signature(&self.fields);
+        let new_fields = types_signature(&other.fields);
+        diff.storage_layout_changed = !new_fields.starts_with(&old_fields);
+
+        diff.added_functions.sort();
+        diff.removed_functions.sort();
+        diff.changed_functions.sort_by(|a, b| a.name.cmp(&b.name));
+        diff.added_events.sort();
+        diff.removed_events.sort();
+        diff.changed_events.sort_by(|a, b| a.name.cmp(&b.name));
+
+        diff
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_diff.rs"]
+mod tests;
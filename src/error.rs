@@ -11,6 +11,12 @@
 * limitations under the License.
 */
 use crate::contract::AbiVersion;
+use crate::token::ParamPath;
+
+/// Result of `json_abi` functions which fail with typed `AbiError`. `Contract`, `Function`,
+/// `Event` and `TokenValue` methods keep returning `ton_types::Result` until the next major
+/// release, use `IntoAbiResult` to convert their results
+pub type AbiResult<T> = std::result::Result<T, AbiError>;
 
 #[derive(Debug, thiserror::Error)]
 pub enum AbiError {
@@ -104,6 +110,7 @@ pub enum AbiError {
 
     #[error( "Serde json error: {}", err)]
     SerdeError {
+        #[from]
         err: serde_json::Error
     },
 
@@ -118,9 +125,170 @@ pub enum AbiError {
     #[error( "Type description contains non empty `components` field but it is not a tuple")]
     UnusedComponents,
 
+    #[error( "Type description contains several tuples which can't share one `components` field: {}", paths)]
+    AmbiguousComponents {
+        paths: String
+    },
+
+    #[error( "Enum description should contain non empty `variants` field")]
+    EmptyVariants,
+
+    #[error( "Type description contains non empty `variants` field but it is not an enum")]
+    UnusedVariants,
+
     #[error("Message destination address is required to encode signed external inbound message body since ABI version 2.3")]
     AddressRequired,
 
     #[error("Wrong data layout")]
     WrongDataLayout,
+
+    #[error("Failed to load ABI from {}: {}", path, source)]
+    InvalidFile {
+        path: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[error("No embedded ABI found in contract")]
+    NoEmbeddedAbi,
+
+    #[error("ABI merge conflict: {}", msg)]
+    MergeConflict {
+        msg: String
+    },
+
+    #[error("Invalid header: {}", msg)]
+    InvalidHeader {
+        msg: String
+    },
+
+    #[error("Type {} of {} has size out of range allowed by ABI specification", .kind, .location)]
+    InvalidTypeSize {
+        location: String,
+        kind: String,
+    },
+
+    #[error("Parameter `{}` nesting depth {} exceeds maximum {}", .name, .depth, .max)]
+    MaxDepthExceeded {
+        name: String,
+        depth: usize,
+        max: usize,
+    },
+
+    #[error("{} (param `{}`)", .err, .path)]
+    InParam {
+        path: String,
+        err: Box<AbiError>,
+    },
+
+    /// Error of cells, blocks or other crates used by ABI
+    #[error("{}", .0)]
+    Other(Box<dyn std::error::Error + Send + Sync>),
+
+    #[error("Invalid ABI JSON{}: {}", json_location(.pointer, .line, .column), .msg)]
+    InvalidAbiJson {
+        pointer: String,
+        line: usize,
+        column: usize,
+        msg: String,
+    },
+
+    #[error("Pruned branch cell can not be read")]
+    PrunedCellAccess,
+}
+
+fn json_location(pointer: &str, line: &usize, column: &usize) -> String {
+    let mut location = String::new();
+    if !pointer.is_empty() {
+        location += &format!(" at `{}`", pointer);
+    }
+    if *line != 0 {
+        location += &format!(" (line {}, column {})", line, column);
+    }
+    location
+}
+
+impl AbiError {
+    /// Returns stable numeric code of error variant. Codes are never reused or changed:
+    /// new variants get new codes and removed variants leave gaps. `InParam` has code of
+    /// wrapped error
+    pub fn code(&self) -> u32 {
+        match self {
+            AbiError::InvalidData { .. } => 1,
+            AbiError::NotSupported { .. } => 2,
+            AbiError::InvalidName { .. } => 3,
+            AbiError::InvalidFunctionId { .. } => 4,
+            AbiError::DeserializationError { .. } => 5,
+            AbiError::NotImplemented => 6,
+            AbiError::WrongParametersCount { .. } => 7,
+            AbiError::WrongParameterType => 8,
+            AbiError::WrongDataFormat { .. } => 9,
+            AbiError::InvalidParameterLength { .. } => 10,
+            AbiError::InvalidParameterValue { .. } => 11,
+            AbiError::IncompleteDeserializationError => 12,
+            AbiError::InvalidInputData { .. } => 13,
+            AbiError::InvalidVersion(_) => 14,
+            AbiError::WrongId { .. } => 15,
+            AbiError::Io { .. } => 16,
+            AbiError::SerdeError { .. } => 17,
+            AbiError::TryFromIntError { .. } => 18,
+            AbiError::EmptyComponents => 19,
+            AbiError::UnusedComponents => 20,
+            AbiError::AmbiguousComponents { .. } => 21,
+            AbiError::EmptyVariants => 22,
+            AbiError::UnusedVariants => 23,
+            AbiError::AddressRequired => 24,
+            AbiError::WrongDataLayout => 25,
+            AbiError::InvalidFile { .. } => 26,
+            AbiError::NoEmbeddedAbi => 27,
+            AbiError::MergeConflict { .. } => 28,
+            AbiError::InvalidHeader { .. } => 29,
+            AbiError::InvalidTypeSize { .. } => 30,
+            AbiError::MaxDepthExceeded { .. } => 31,
+            AbiError::InParam { err, .. } => err.code(),
+            AbiError::Other(_) => 32,
+            AbiError::InvalidAbiJson { .. } => 33,
+            AbiError::PrunedCellAccess => 34,
+        }
+    }
+
+    /// Returns error itself or error wrapped into `InParam` with path to param which
+    /// caused it
+    pub fn unwrap_param(&self) -> &AbiError {
+        match self {
+            AbiError::InParam { err, .. } => err.unwrap_param(),
+            err => err,
+        }
+    }
+
+    /// Returns numeric code of `AbiError` wrapped into generic error or `None` if error
+    /// is produced by other crate
+    pub fn code_of(err: &ton_types::Error) -> Option<u32> {
+        err.downcast_ref::<AbiError>().map(AbiError::code)
+    }
+}
+
+impl From<anyhow::Error> for AbiError {
+    fn from(err: anyhow::Error) -> Self {
+        let path = ParamPath::of(&err).map(str::to_owned);
+        match err.downcast::<AbiError>() {
+            Ok(err) => match path {
+                Some(path) => AbiError::InParam { path, err: Box::new(err) },
+                None => err,
+            },
+            Err(err) => AbiError::Other(err.into()),
+        }
+    }
+}
+
+/// Conversion of `ton_types::Result` returned by `Contract`, `Function` and other types
+/// into `AbiResult`. These types return `ton_types::Result` until the next major release
+pub trait IntoAbiResult<T> {
+    fn into_abi_result(self) -> AbiResult<T>;
+}
+
+impl<T> IntoAbiResult<T> for ton_types::Result<T> {
+    fn into_abi_result(self) -> AbiResult<T> {
+        self.map_err(AbiError::from)
+    }
 }
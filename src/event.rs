@@ -11,11 +11,14 @@
 * limitations under the License.
 */
 
-use ton_types::{Result, SliceData};
+use ton_block::Serializable;
+use sha2::{Digest, Sha256};
+use ton_types::{BuilderData, Cell, fail, Result, SliceData, UInt256};
 
 use crate::{Function, Param, Token, TokenValue};
 use crate::contract::{SerdeEvent, AbiVersion};
 use crate::error::AbiError;
+use crate::token::Cursor;
 
 /// Contract event specification.
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -30,6 +33,17 @@ pub struct Event {
     pub id: u32
 }
 
+/// Values identifying event in external indexes
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct EventSelector {
+    /// Event signature
+    pub signature: String,
+    /// Event ID
+    pub id: u32,
+    /// Sha256 hash of event signature
+    pub topic: UInt256,
+}
+
 impl Event {
     /// Creates `Function` struct from parsed JSON struct `SerdeFunction`
     pub fn from_serde(abi_version: AbiVersion, serde_event: SerdeEvent) -> Self {
@@ -74,6 +88,20 @@ impl Event {
         Function::calc_function_id(&signature)
     }
 
+    /// Computes canonical event topic: sha256 hash of event signature
+    pub fn get_topic_hash(&self) -> UInt256 {
+        UInt256::from_slice(&Sha256::digest(self.get_function_signature().as_bytes()))
+    }
+
+    /// Returns event selector values used by external indexers
+    pub fn selector(&self) -> EventSelector {
+        EventSelector {
+            signature: self.get_function_signature(),
+            id: self.get_id(),
+            topic: self.get_topic_hash(),
+        }
+    }
+
     /// Returns ID for event emitting message
     pub fn get_id(&self) -> u32 {
         self.id
@@ -88,6 +116,34 @@ impl Event {
         TokenValue::decode_params(self.input_params(), data, &self.abi_version, false)
     }
 
+    /// Parses the ABI event body stored in `Cell` to list of tokens.
+    pub fn decode_input_from_cell(&self, data: &Cell) -> Result<Vec<Token>> {
+        self.decode_input(SliceData::load_cell(data.clone())?)
+    }
+
+    /// Parses the ABI event body to list of tokens. Allows partial decoding.
+    /// Returns cursor pointing to the data remaining after event params.
+    pub fn decode_input_partial(&self, mut data: SliceData) -> Result<(Vec<Token>, Cursor)> {
+        let id = data.get_next_u32()?;
+
+        if id != self.get_id() { Err(AbiError::WrongId { id } )? }
+
+        TokenValue::decode_params_with_cursor(self.input_params(), data.into(), &self.abi_version, true, true)
+    }
+
+    /// Encodes provided event parameters into `BuilderData` containing event message body.
+    pub fn encode_message_body(&self, input: &[Token]) -> Result<BuilderData> {
+        if !Token::types_check(input, self.input_params()) {
+            fail!(AbiError::WrongParameterType);
+        }
+
+        TokenValue::pack_values_into_chain(
+            input,
+            vec![self.get_id().write_to_new_cell()?.into()],
+            &self.abi_version
+        )
+    }
+
     /// Decodes function id from contract answer
     pub fn decode_id(mut data: SliceData) -> Result<u32> {
         data.get_next_u32()
@@ -13,17 +13,30 @@
 
 //! Contract function call builder.
 
-use crate::{contract::{ABI_VERSION_1_0, ABI_VERSION_2_3}, error::AbiError, param::Param, param_type::ParamType, token::{SerializedValue, Token, TokenValue}};
+use crate::{contract::{ABI_VERSION_1_0, ABI_VERSION_2_3}, error::AbiError, int::Uint, param::Param, param_type::ParamType, token::{SerializedValue, Token, TokenValue}};
 
 use crate::contract::{AbiVersion, SerdeFunction};
-use crate::signature::sign_with_signature_id;
-use ed25519_dalek::{Keypair, SIGNATURE_LENGTH};
+use crate::signature::{constant_time_eq, extend_signature_with_id, Signer};
+#[cfg(feature = "async")]
+use crate::signature::AsyncSigner;
+use ed25519_dalek::{Keypair, PublicKey, Signature, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
 use sha2::{Digest, Sha256};
 use smallvec::SmallVec;
-use std::collections::HashMap;
-use ton_block::{Serializable, MsgAddressInt};
-use ton_types::{BuilderData, fail, IBitstring, Result, SliceData};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use ton_block::{
+    CurrencyCollection, ExternalInboundMessageHeader, InternalMessageHeader, Message, MsgAddressInt,
+    Serializable, StateInit,
+};
+use ton_types::{BuilderData, Cell, fail, IBitstring, Result, SliceData, UInt256};
 use crate::token::Cursor;
+use crate::warnings::{Warning, Warnings};
+
+/// Name of the first input of `responsible` functions
+pub const ANSWER_ID_NAME: &str = "answerId";
+
+/// Maximum allowed difference between `time` header value and current time in milliseconds
+pub const MAX_TIME_DRIFT_MS: u64 = 30 * 60 * 1000;
 
 /// Contract function specification.
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -44,6 +57,218 @@ pub struct Function {
     pub output_id: u32,
 }
 
+/// Function call body prepared for signing
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnsignedCall {
+    /// ABI version
+    pub abi_version: AbiVersion,
+    /// Call body without signature
+    pub builder: BuilderData,
+    /// Is space for signature reserved in call body
+    pub reserve_sign: bool,
+    /// Signature placement in call body
+    pub placement: SignaturePlacement,
+}
+
+/// Placement of external call signature for ABI 2.x
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SignaturePlacement {
+    /// Signature is stored in the root cell data right after the prefix bit (standard layout)
+    #[default]
+    InCell,
+    /// Signature is stored in the first reference of the root cell followed by signer public
+    /// key if it is provided. Prefix bit is still
+    /// stored in the root cell data and indicates if the signature is present.
+    /// Has no effect for ABI 1.0 where signature is always stored in reference.
+    InReference,
+}
+
+/// Maximum number of signatures in multi-signature container
+pub const MAX_MULTI_SIGNATURES: usize = u8::MAX as usize;
+
+/// Size of encoded function call body
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CallSize {
+    /// Data bits in all unique cells
+    pub bits: usize,
+    /// References in all unique cells
+    pub refs: usize,
+    /// Unique cells count
+    pub cells: usize,
+}
+
+impl CallSize {
+    fn calc(cell: &Cell, visited: &mut HashSet<UInt256>, size: &mut CallSize) -> Result<()> {
+        if !visited.insert(cell.repr_hash()) {
+            return Ok(());
+        }
+        size.cells += 1;
+        size.bits += cell.bit_length();
+        size.refs += cell.references_count();
+        for i in 0..cell.references_count() {
+            Self::calc(&cell.reference(i)?, visited, size)?;
+        }
+        Ok(())
+    }
+}
+
+/// Encodes function calls caching encoded header cells, so repeated calls of the same
+/// function with the same header values don't encode header again.
+/// Header is cached only if all header values are provided explicitly.
+#[derive(Default)]
+pub struct CallEncoder {
+    cache: HashMap<(u32, AbiVersion, bool), CachedHeader>,
+}
+
+struct CachedHeader {
+    params: Vec<Param>,
+    values: HashMap<String, TokenValue>,
+    cells: Vec<SerializedValue>,
+}
+
+impl CallEncoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Encodes provided function parameters into `BuilderData` containing ABI contract call
+    /// reusing cached header cells if possible
+    pub fn encode_input(
+        &mut self,
+        function: &Function,
+        header: &HashMap<String, TokenValue>,
+        input: &[Token],
+        internal: bool,
+        pair: Option<(&Keypair, Option<i32>)>,
+        address: Option<MsgAddressInt>,
+    ) -> Result<BuilderData> {
+        let cells = self.header_cells(function, header, internal)?;
+        let call = function.build_unsigned_call(cells, input, internal, pair.is_some(), SignaturePlacement::InCell)?;
+        let signer = pair.map(|(pair, signature_id)| (pair as &dyn Signer, signature_id));
+        Function::complete_call(call, internal, signer, address)
+    }
+
+    /// Removes all cached headers
+    pub fn clear(&mut self) {
+        self.cache.clear()
+    }
+
+    fn header_cells(
+        &mut self,
+        function: &Function,
+        header: &HashMap<String, TokenValue>,
+        internal: bool,
+    ) -> Result<Vec<SerializedValue>> {
+        let cacheable = internal || function.header.iter().all(|param| header.contains_key(&param.name));
+        if !cacheable {
+            return function.encode_header(header, internal);
+        }
+
+        let key = (function.get_input_id(), function.abi_version, internal);
+        if let Some(cached) = self.cache.get(&key) {
+            if cached.params == function.header && (internal || &cached.values == header) {
+                return Ok(cached.cells.clone());
+            }
+        }
+
+        let cells = function.encode_header(header, internal)?;
+        self.cache.insert(key, CachedHeader {
+            params: function.header.clone(),
+            values: header.clone(),
+            cells: cells.clone(),
+        });
+        Ok(cells)
+    }
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let params = |params: &[Param]| params
+            .iter()
+            .map(|param| format!("{}: {}", param.name, param.kind))
+            .collect::<Vec<String>>()
+            .join(", ");
+        write!(
+            f,
+            "{}({}) -> ({}) [in 0x{:08X} / out 0x{:08X}]",
+            self.name,
+            params(&self.inputs),
+            params(&self.outputs),
+            self.input_id,
+            self.output_id,
+        )
+    }
+}
+
+impl UnsignedCall {
+    /// Computes hash to be signed. Since ABI 2.3 destination address is required for signed calls
+    pub fn hash(&self, address: Option<MsgAddressInt>) -> Result<ton_types::UInt256> {
+        if self.abi_version >= ABI_VERSION_2_3 && self.reserve_sign {
+            let address = address.ok_or(AbiError::AddressRequired)?;
+            let mut address_builder = address.write_to_new_cell()?;
+            address_builder.append_builder(&self.builder)?;
+            Ok(address_builder.into_cell()?.repr_hash())
+        } else {
+            Ok(self.builder.clone().into_cell()?.repr_hash())
+        }
+    }
+
+    /// Returns data to be signed: call hash extended with signature id
+    pub fn data_to_sign(
+        &self,
+        address: Option<MsgAddressInt>,
+        signature_id: Option<i32>,
+    ) -> Result<Vec<u8>> {
+        let hash = self.hash(address)?;
+        Ok(extend_signature_with_id(hash.as_slice(), signature_id).into_owned())
+    }
+
+    /// Signs call with provided `Signer` and returns complete call body
+    pub fn sign(
+        self,
+        signer: &dyn Signer,
+        address: Option<MsgAddressInt>,
+        signature_id: Option<i32>,
+    ) -> Result<BuilderData> {
+        let signature = signer.sign(&self.data_to_sign(address, signature_id)?)?;
+        self.fill_sign(Some(&signature.to_bytes()), Some(&signer.public_key().to_bytes()))
+    }
+
+    /// Signs call with provided `AsyncSigner` and returns complete call body
+    #[cfg(feature = "async")]
+    pub async fn sign_async<S: AsyncSigner + ?Sized>(
+        self,
+        signer: &S,
+        address: Option<MsgAddressInt>,
+        signature_id: Option<i32>,
+    ) -> Result<BuilderData> {
+        let data = self.data_to_sign(address, signature_id)?;
+        let signature = signer.sign(&data).await?;
+        self.fill_sign(Some(&signature.to_bytes()), Some(&signer.public_key().to_bytes()))
+    }
+
+    /// Adds several signatures to call body prepared with `SignaturePlacement::InReference`.
+    /// All signers should sign the same data returned by `data_to_sign`
+    pub fn fill_multi_sign(self, signatures: &[(Signature, PublicKey)]) -> Result<BuilderData> {
+        if self.placement != SignaturePlacement::InReference {
+            fail!(AbiError::InvalidInputData {
+                msg: "Multiple signatures require signature placement in reference".to_owned()
+            });
+        }
+        Function::fill_multi_sign(&self.abi_version, signatures, self.builder)
+    }
+
+    /// Adds provided signature to call body
+    pub fn fill_sign(
+        self,
+        signature: Option<&[u8]>,
+        public_key: Option<&[u8]>,
+    ) -> Result<BuilderData> {
+        Function::fill_sign_with_placement(
+            &self.abi_version, signature, public_key, self.builder, self.placement)
+    }
+}
+
 impl Function {
     /// Creates `Function` struct from parsed JSON struct `SerdeFunction`
     pub fn from_serde(abi_version: AbiVersion, serde_function: SerdeFunction, header: Vec<Param>) -> Self {
@@ -82,6 +307,36 @@ impl Function {
         &self.outputs
     }
 
+    /// Returns header param with provided name.
+    pub fn header_param(&self, name: &str) -> Option<&Param> {
+        self.header.iter().find(|param| param.name == name)
+    }
+
+    /// Returns input param with provided name.
+    pub fn input_param(&self, name: &str) -> Option<&Param> {
+        self.inputs.iter().find(|param| param.name == name)
+    }
+
+    /// Returns output param with provided name.
+    pub fn output_param(&self, name: &str) -> Option<&Param> {
+        self.outputs.iter().find(|param| param.name == name)
+    }
+
+    /// Returns index of header param with provided name.
+    pub fn header_param_index(&self, name: &str) -> Option<usize> {
+        self.header.iter().position(|param| param.name == name)
+    }
+
+    /// Returns index of input param with provided name.
+    pub fn input_param_index(&self, name: &str) -> Option<usize> {
+        self.inputs.iter().position(|param| param.name == name)
+    }
+
+    /// Returns index of output param with provided name.
+    pub fn output_param_index(&self, name: &str) -> Option<usize> {
+        self.outputs.iter().position(|param| param.name == name)
+    }
+
     /// Returns true if function has input parameters, false in not
     pub fn has_input(&self) -> bool {
         !self.inputs.is_empty()
@@ -94,8 +349,13 @@ impl Function {
 
     /// Retruns ABI function signature
     pub fn get_function_signature(&self) -> String {
+        self.get_function_signature_for(&self.abi_version)
+    }
+
+    /// Returns function signature as it would be computed under provided ABI version
+    pub fn get_function_signature_for(&self, abi_version: &AbiVersion) -> String {
         let mut input_types = vec![];
-        if self.abi_version.major == 1 {
+        if abi_version.major == 1 {
             input_types.append(&mut self.header.iter()
                 .map(|param| param.kind.type_signature())
                 .collect::<Vec<String>>())
@@ -118,7 +378,7 @@ impl Function {
             .collect::<Vec<String>>()
             .join(",");
 
-        format!("{}({})({})v{}", self.name, input_types, output_types, self.abi_version.major)
+        format!("{}({})({})v{}", self.name, input_types, output_types, abi_version.major)
     }
 
     pub fn calc_function_id(signature: &str) -> u32 {
@@ -143,6 +403,14 @@ impl Function {
         Self::calc_function_id(&signature)
     }
 
+    /// Computes function ID as it would be computed under provided ABI version.
+    /// Explicit `id` from ABI is not taken into account
+    pub fn get_function_id_for(&self, abi_version: &AbiVersion) -> u32 {
+        let signature = self.get_function_signature_for(abi_version);
+
+        Self::calc_function_id(&signature)
+    }
+
     /// Returns ID for call message
     pub fn get_input_id(&self) -> u32 {
         self.input_id
@@ -160,11 +428,38 @@ impl Function {
         TokenValue::decode_params(self.output_params(), data, &self.abi_version, false)
     }
 
+    /// Parses the ABI function output to list of tokens. Data remaining after outputs (allowed
+    /// only with `allow_partial`) and deprecated output types are reported into `warnings`
+    pub fn decode_output_with_warnings(
+        &self,
+        mut data: SliceData,
+        _internal: bool,
+        allow_partial: bool,
+        warnings: &mut Warnings,
+    ) -> Result<Vec<Token>> {
+        let id = data.get_next_u32()?;
+        if id != self.get_output_id() { Err(AbiError::WrongId { id } )? }
+        warnings.check_deprecated_types(&format!("function {} output", self.name), self.output_params());
+        TokenValue::decode_params_with_warnings(
+            self.output_params(), data, &self.abi_version, allow_partial, warnings)
+    }
+
+    /// Parses the ABI function output stored in `Cell` to list of tokens.
+    pub fn decode_output_from_cell(&self, data: &Cell, internal: bool) -> Result<Vec<Token>> {
+        self.decode_output(SliceData::load_cell(data.clone())?, internal)
+    }
+
+    /// Parses the ABI function output to list of tokens. Allows partial decoding.
+    pub fn decode_output_partial(&self, data: SliceData, internal: bool) -> Result<Vec<Token>> {
+        self.decode_output_with_cursor(data, internal).map(|(tokens, _)| tokens)
+    }
+
     /// Parses the ABI function output to list of tokens. Allows partial decoding.
-    pub fn decode_output_partial(&self, mut data: SliceData, _internal: bool) -> Result<Vec<Token>> {
+    /// Returns cursor pointing to the data remaining after ABI outputs.
+    pub fn decode_output_with_cursor(&self, mut data: SliceData, _internal: bool) -> Result<(Vec<Token>, Cursor)> {
         let id = data.get_next_u32()?;
         if id != self.get_output_id() { Err(AbiError::WrongId { id } )? }
-        TokenValue::decode_params(self.output_params(), data, &self.abi_version, true)
+        TokenValue::decode_params_with_cursor(self.output_params(), data.into(), &self.abi_version, true, true)
     }
 
     /// Parses the ABI function call to list of tokens.
@@ -174,20 +469,42 @@ impl Function {
         internal: bool,
         allow_partial: bool,
     ) -> Result<Vec<Token>> {
-        let (_, id, cursor) = Self::decode_header(&self.abi_version, data, &self.header, internal)?;
+        self.decode_input_with_header(data, internal, allow_partial)
+            .map(|(_, tokens)| tokens)
+    }
+
+    /// Parses the ABI function call stored in `Cell` to list of tokens.
+    pub fn decode_input_from_cell(
+        &self,
+        data: &Cell,
+        internal: bool,
+        allow_partial: bool,
+    ) -> Result<Vec<Token>> {
+        self.decode_input(SliceData::load_cell(data.clone())?, internal, allow_partial)
+    }
+
+    /// Parses the ABI function call to lists of header and input tokens.
+    pub fn decode_input_with_header(
+        &self,
+        data: SliceData,
+        internal: bool,
+        allow_partial: bool,
+    ) -> Result<(Vec<Token>, Vec<Token>)> {
+        let (header, id, cursor) = Self::decode_header(&self.abi_version, data, &self.header, internal)?;
 
         if id != self.get_input_id() {
             Err(AbiError::WrongId { id })?
         }
 
-        TokenValue::decode_params_with_cursor(
+        let (tokens, _) = TokenValue::decode_params_with_cursor(
             self.input_params(),
             cursor,
             &self.abi_version,
             allow_partial,
             true,
-        )
-            .map(|(tokens, _)| tokens)
+        )?;
+
+        Ok((header, tokens))
     }
 
     /// Decodes function id from contract answer
@@ -215,18 +532,38 @@ impl Function {
         pair: Option<(&Keypair, Option<i32>)>,
         address: Option<MsgAddressInt>,
     ) -> Result<BuilderData> {
+        self.encode_input_with_signer(
+            header,
+            input,
+            internal,
+            pair.map(|(pair, signature_id)| (pair as &dyn Signer, signature_id)),
+            address,
+        )
+    }
+
+    /// Encodes provided function parameters into `BuilderData` containing ABI contract call
+    /// signed by provided `Signer`
+    pub fn encode_input_with_signer(
+        &self,
+        header: &HashMap<String, TokenValue>,
+        input: &[Token],
+        internal: bool,
+        signer: Option<(&dyn Signer, Option<i32>)>,
+        address: Option<MsgAddressInt>,
+    ) -> Result<BuilderData> {
 
         let (mut builder, hash) =
-            self.create_unsigned_call(header, input, internal, pair.is_some(), address)?;
+            self.create_unsigned_call(header, input, internal, signer.is_some(), address)?;
 
         if !internal {
-            builder = match pair {
-                Some((pair, signature_id)) => {
-                    let signature = sign_with_signature_id(pair, hash.as_slice(), signature_id);
+            builder = match signer {
+                Some((signer, signature_id)) => {
+                    let data = extend_signature_with_id(hash.as_slice(), signature_id);
+                    let signature = signer.sign(&data)?;
                     Self::fill_sign(
                         &self.abi_version,
                         Some(&signature.to_bytes()),
-                        Some(&pair.public.to_bytes()),
+                        Some(&signer.public_key().to_bytes()),
                         builder)?
                 },
                 None => Self::fill_sign(&self.abi_version, None, None, builder)?
@@ -236,6 +573,94 @@ impl Function {
         Ok(builder)
     }
 
+    /// Encodes provided function parameters into `BuilderData` containing ABI contract call
+    /// signed by provided `Signer`. Header values replaced with defaults (except `time` and
+    /// `expire` which are generated by design) and deprecated header and input types are
+    /// reported into `warnings`
+    pub fn encode_input_with_warnings(
+        &self,
+        header: &HashMap<String, TokenValue>,
+        input: &[Token],
+        internal: bool,
+        signer: Option<(&dyn Signer, Option<i32>)>,
+        address: Option<MsgAddressInt>,
+        warnings: &mut Warnings,
+    ) -> Result<BuilderData> {
+        if !internal {
+            warnings.check_deprecated_types("header", self.header_params());
+        }
+        warnings.check_deprecated_types(&format!("function {} input", self.name), self.input_params());
+
+        let cells = self.encode_header_with_id(header, internal, self.get_input_id(), Some(warnings))?;
+        let call = self.build_unsigned_call(cells, input, internal, signer.is_some(), SignaturePlacement::InCell)?;
+        Self::complete_call(call, internal, signer, address)
+    }
+
+    /// Encodes provided function parameters into `BuilderData` containing external ABI contract
+    /// call signed by provided `AsyncSigner`
+    #[cfg(feature = "async")]
+    pub async fn encode_input_with_async_signer<S: AsyncSigner + ?Sized>(
+        &self,
+        header: &HashMap<String, TokenValue>,
+        input: &[Token],
+        signer: &S,
+        signature_id: Option<i32>,
+        address: Option<MsgAddressInt>,
+    ) -> Result<BuilderData> {
+        let call = self.prepare_unsigned_call(header, input, false, true)?;
+        call.sign_async(signer, address, signature_id).await
+    }
+
+    /// Encodes provided function parameters into external inbound `Message` addressed to `dst`.
+    /// `state_init` is attached to message if provided. Message body is placed into
+    /// reference if it doesn't fit into message root cell.
+    pub fn encode_external_message(
+        &self,
+        dst: MsgAddressInt,
+        header: &HashMap<String, TokenValue>,
+        input: &[Token],
+        pair: Option<(&Keypair, Option<i32>)>,
+        state_init: Option<StateInit>,
+    ) -> Result<Message> {
+        let body = self.encode_input(header, input, false, pair, Some(dst.clone()))?;
+
+        let mut message = Message::with_ext_in_header(ExternalInboundMessageHeader {
+            dst,
+            ..Default::default()
+        });
+        if let Some(state_init) = state_init {
+            message.set_state_init(state_init);
+        }
+        message.set_body(SliceData::load_builder(body)?);
+
+        Ok(message)
+    }
+
+    /// Encodes provided function parameters into internal `Message` addressed to `dst` and
+    /// carrying `value` nanotokens. Message body is placed into reference if it doesn't fit
+    /// into message root cell.
+    pub fn encode_internal_message(
+        &self,
+        dst: MsgAddressInt,
+        value: u64,
+        bounce: bool,
+        ihr_disabled: bool,
+        input: &[Token],
+    ) -> Result<Message> {
+        let body = self.encode_internal_input(input)?;
+
+        let mut message = Message::with_int_header(InternalMessageHeader {
+            ihr_disabled,
+            bounce,
+            dst,
+            value: CurrencyCollection::with_grams(value),
+            ..Default::default()
+        });
+        message.set_body(SliceData::load_builder(body)?);
+
+        Ok(message)
+    }
+
     /// Encodes provided function return values into `BuilderData`
     pub fn encode_internal_output(&self, answer_id: u32, input: &[Token]) -> Result<BuilderData> {
         let vec = vec![answer_id.write_to_new_cell()?.into()];
@@ -248,6 +673,16 @@ impl Function {
         &self,
         header_tokens: &HashMap<String, TokenValue>,
         internal: bool
+    ) -> Result<Vec<SerializedValue>> {
+        self.encode_header_with_id(header_tokens, internal, self.get_input_id(), None)
+    }
+
+    fn encode_header_with_id(
+        &self,
+        header_tokens: &HashMap<String, TokenValue>,
+        internal: bool,
+        id: u32,
+        mut warnings: Option<&mut Warnings>,
     ) -> Result<Vec<SerializedValue>> {
         let mut vec = vec![];
         if !internal {
@@ -258,14 +693,24 @@ impl Function {
                     }
                     vec.append(&mut token.write_to_cells(&self.abi_version)?);
                 } else {
-                    vec.append(&mut TokenValue::get_default_value_for_header(&param.kind)?.write_to_cells(&self.abi_version)?);
+                    let value = TokenValue::get_default_value_for_header(&param.kind)?;
+                    // time and expire are generated when not provided, it is not a default
+                    if let Some(warnings) = &mut warnings {
+                        if !matches!(param.kind, ParamType::Time | ParamType::Expire) {
+                            warnings.push(Warning::DefaultedHeaderValue {
+                                name: param.name.clone(),
+                                kind: param.kind.type_signature(),
+                            });
+                        }
+                    }
+                    vec.append(&mut value.write_to_cells(&self.abi_version)?);
                 }
             }
         }
         if self.abi_version.major == 1 {
-            vec.insert(0, self.get_input_id().write_to_new_cell()?.into());
+            vec.insert(0, id.write_to_new_cell()?.into());
         } else {
-            vec.push(self.get_input_id().write_to_new_cell()?.into());
+            vec.push(id.write_to_new_cell()?.into());
         }
         Ok(vec)
     }
@@ -274,11 +719,19 @@ impl Function {
     fn encode_default_header(
         &self,
         time: u64,
+        header_tokens: &HashMap<String, TokenValue>,
         internal: bool
     ) -> Result<Vec<SerializedValue>> {
         let mut vec = vec![];
         if !internal {
             for param in &self.header {
+                if let Some(token) = header_tokens.get(&param.name) {
+                    if !token.type_check(&param.kind) {
+                        return Err(AbiError::WrongParameterType.into());
+                    }
+                    vec.append(&mut token.write_to_cells(&self.abi_version)?);
+                    continue;
+                }
                 let header_value = match &param.kind {
                     ParamType::Time => TokenValue::Time(time),
                     ParamType::Expire => TokenValue::Expire(u32::MAX),
@@ -308,6 +761,16 @@ impl Function {
         cursor: SliceData,
         header: &[Param],
         internal: bool,
+    ) -> Result<(Vec<Token>, u32, Cursor)> {
+        Self::decode_header_with_placement(abi_version, cursor, header, internal, SignaturePlacement::InCell)
+    }
+
+    fn decode_header_with_placement(
+        abi_version: &AbiVersion,
+        cursor: SliceData,
+        header: &[Param],
+        internal: bool,
+        placement: SignaturePlacement,
     ) -> Result<(Vec<Token>, u32, Cursor)> {
         let mut tokens = vec![];
         let mut id = 0;
@@ -321,12 +784,22 @@ impl Function {
             if abi_version == &ABI_VERSION_1_0 {
                 cursor.slice.checked_drain_reference()?;
                 cursor.used_refs += 1;
+            } else if placement == SignaturePlacement::InReference {
+                if cursor.slice.get_next_bit()? {
+                    cursor.slice.checked_drain_reference()?;
+                    cursor.used_refs += 1;
+                }
+                cursor.used_bits += if abi_version >= &ABI_VERSION_2_3 {
+                    ParamType::Address.max_bit_size(abi_version)
+                } else {
+                    1
+                };
             } else {
                 if cursor.slice.get_next_bit()? {
                     cursor.slice.get_next_bytes(SIGNATURE_LENGTH)?;
                 }
                 cursor.used_bits += if abi_version >= &ABI_VERSION_2_3 {
-                    TokenValue::max_bit_size(&ParamType::Address, abi_version)
+                    ParamType::Address.max_bit_size(abi_version)
                 } else {
                     1 + SIGNATURE_LENGTH * 8
                 };
@@ -353,6 +826,129 @@ impl Function {
         reserve_sign: bool,
         address: Option<MsgAddressInt>,
     ) -> Result<(BuilderData, ton_types::UInt256)> {
+        let call = self.prepare_unsigned_call(header, input, internal, reserve_sign)?;
+        let hash = call.hash(address)?;
+        Ok((call.builder, hash))
+    }
+
+    /// Encodes provided function parameters into `UnsignedCall` containing ABI contract call.
+    /// Destination address and signature id (required for hash calculation since ABI 2.3)
+    /// can be supplied later while signing the call.
+    pub fn prepare_unsigned_call(
+        &self,
+        header: &HashMap<String, TokenValue>,
+        input: &[Token],
+        internal: bool,
+        reserve_sign: bool,
+    ) -> Result<UnsignedCall> {
+        self.prepare_unsigned_call_with_placement(
+            header, input, internal, reserve_sign, SignaturePlacement::InCell)
+    }
+
+    /// Encodes provided function parameters into `UnsignedCall` containing ABI contract call
+    /// with signature reserved according to provided `placement`.
+    pub fn prepare_unsigned_call_with_placement(
+        &self,
+        header: &HashMap<String, TokenValue>,
+        input: &[Token],
+        internal: bool,
+        reserve_sign: bool,
+        placement: SignaturePlacement,
+    ) -> Result<UnsignedCall> {
+        let cells = self.encode_header(header, internal)?;
+        self.build_unsigned_call(cells, input, internal, reserve_sign, placement)
+    }
+
+    /// Estimates size of external call body with provided inputs including header and
+    /// signature. Header values are replaced with defaults of corresponding types
+    pub fn estimate_call_size(&self, input: &[Token], signed: bool) -> Result<CallSize> {
+        let mut header = HashMap::new();
+        for param in &self.header {
+            let value = match param.kind {
+                ParamType::PublicKey if signed => TokenValue::PublicKey(Some(
+                    PublicKey::from_bytes(&[0; PUBLIC_KEY_LENGTH])?)),
+                _ => TokenValue::default_value(&param.kind),
+            };
+            header.insert(param.name.clone(), value);
+        }
+        let cells = self.encode_header(&header, false)?;
+        let call = self.build_unsigned_call(cells, input, false, signed, SignaturePlacement::InCell)?;
+        let builder = if signed {
+            call.fill_sign(
+                Some(&[0; SIGNATURE_LENGTH]),
+                Some(&[0; PUBLIC_KEY_LENGTH]),
+            )?
+        } else {
+            call.fill_sign(None, None)?
+        };
+
+        let mut size = CallSize::default();
+        CallSize::calc(&builder.into_cell()?, &mut HashSet::new(), &mut size)?;
+        Ok(size)
+    }
+
+    /// Encodes several calls of this function sharing the same header values and signer.
+    /// Header is encoded once and reused for every call.
+    pub fn encode_input_batch(
+        &self,
+        header: &HashMap<String, TokenValue>,
+        inputs: &[Vec<Token>],
+        internal: bool,
+        signer: Option<(&dyn Signer, Option<i32>)>,
+        address: Option<MsgAddressInt>,
+    ) -> Result<Vec<BuilderData>> {
+        let header_cells = self.encode_header(header, internal)?;
+
+        let mut result = Vec::with_capacity(inputs.len());
+        for input in inputs {
+            let call = self.build_unsigned_call(
+                header_cells.clone(), input, internal, signer.is_some(), SignaturePlacement::InCell)?;
+            result.push(Self::complete_call(call, internal, signer, address.clone())?);
+        }
+
+        Ok(result)
+    }
+
+    /// Encodes provided function parameters into `BuilderData` containing ABI contract call
+    /// using provided function id instead of the one from function specification
+    pub fn encode_input_with_id(
+        &self,
+        id: u32,
+        header: &HashMap<String, TokenValue>,
+        input: &[Token],
+        internal: bool,
+        pair: Option<(&Keypair, Option<i32>)>,
+        address: Option<MsgAddressInt>,
+    ) -> Result<BuilderData> {
+        let cells = self.encode_header_with_id(header, internal, id, None)?;
+        let call = self.build_unsigned_call(cells, input, internal, pair.is_some(), SignaturePlacement::InCell)?;
+        let signer = pair.map(|(pair, signature_id)| (pair as &dyn Signer, signature_id));
+        Self::complete_call(call, internal, signer, address)
+    }
+
+    fn complete_call(
+        call: UnsignedCall,
+        internal: bool,
+        signer: Option<(&dyn Signer, Option<i32>)>,
+        address: Option<MsgAddressInt>,
+    ) -> Result<BuilderData> {
+        if internal {
+            return Ok(call.builder);
+        }
+        match signer {
+            Some((signer, signature_id)) => call.sign(signer, address, signature_id),
+            None => call.fill_sign(None, None),
+        }
+    }
+
+    fn build_unsigned_call(
+        &self,
+        mut cells: Vec<SerializedValue>,
+        input: &[Token],
+        internal: bool,
+        reserve_sign: bool,
+        placement: SignaturePlacement,
+    ) -> Result<UnsignedCall> {
         let params = self.input_params();
 
         if !Token::types_check(input, params.as_slice()) {
@@ -360,7 +956,6 @@ impl Function {
         }
 
         // prepare standard message
-        let mut cells = self.encode_header(header, internal)?;
 
         let mut remove_ref = false;
         let mut remove_bits = 0;
@@ -370,16 +965,33 @@ impl Function {
                 // reserve reference for sign
                 sign_builder.checked_append_reference(ton_types::Cell::default())?;
                 remove_ref = true;
+            } else if placement == SignaturePlacement::InReference {
+                // reserve reference for sign and prefix bit, or space for address
+                // prepended to call body while computing hash
+                if reserve_sign {
+                    sign_builder.checked_append_reference(ton_types::Cell::default())?;
+                    remove_ref = true;
+                }
+                if reserve_sign && self.abi_version >= ABI_VERSION_2_3 {
+                    sign_builder.append_raw(
+                        &[0u8; 128], //MAX DATA BYTES
+                        ParamType::Address.max_bit_size(&self.abi_version),
+                    )?;
+                    remove_bits = ParamType::Address.max_bit_size(&self.abi_version);
+                } else {
+                    sign_builder.append_bit_one()?;
+                    remove_bits = 1;
+                }
             } else {
                 // reserve in-cell data
                 if reserve_sign {
                     if self.abi_version >= ABI_VERSION_2_3 {
                         sign_builder.append_raw(
                             &[0u8; 128], //MAX DATA BYTES
-                            TokenValue::max_bit_size(&ParamType::Address, &self.abi_version),
+                            ParamType::Address.max_bit_size(&self.abi_version),
                         )?;
                         remove_bits =
-                            TokenValue::max_bit_size(&ParamType::Address, &self.abi_version);
+                            ParamType::Address.max_bit_size(&self.abi_version);
                     } else {
                         sign_builder.append_bit_one()?;
                         sign_builder.append_raw(
@@ -398,7 +1010,9 @@ impl Function {
                 SerializedValue {
                     data: sign_builder,
                     max_bits: if self.abi_version >= ABI_VERSION_2_3 {
-                        TokenValue::max_bit_size(&ParamType::Address, &self.abi_version)
+                        ParamType::Address.max_bit_size(&self.abi_version)
+                    } else if self.abi_version.major != 1 && placement == SignaturePlacement::InReference {
+                        1
                     } else {
                         1 + SIGNATURE_LENGTH * 8
                     },
@@ -422,19 +1036,21 @@ impl Function {
             builder = BuilderData::from_slice(&slice);
         }
 
-        let hash = if self.abi_version >= ABI_VERSION_2_3 && reserve_sign {
-            let address = address.ok_or(AbiError::AddressRequired)?;
-            let mut address_builder = address.write_to_new_cell()?;
-            address_builder.append_builder(&builder)?;
-            address_builder.into_cell()?.repr_hash()
-        } else {
-            builder.clone().into_cell()?.repr_hash()
-        };
-
-        Ok((builder, hash))
+        Ok(UnsignedCall {
+            abi_version: self.abi_version,
+            builder,
+            reserve_sign,
+            placement,
+        })
     }
 
     pub fn encode_internal_input(&self, input: &[Token]) -> Result<BuilderData> {
+        self.encode_internal_input_with_id(self.get_input_id(), input)
+    }
+
+    /// Encodes provided function parameters into `BuilderData` containing internal ABI call
+    /// using provided function id instead of the one from function specification
+    pub fn encode_internal_input_with_id(&self, id: u32, input: &[Token]) -> Result<BuilderData> {
         let params = self.input_params();
 
         if !Token::types_check(input, params.as_slice()) {
@@ -443,13 +1059,61 @@ impl Function {
 
         TokenValue::pack_values_into_chain(
             input,
-            vec![self.get_input_id().write_to_new_cell()?.into()],
+            vec![id.write_to_new_cell()?.into()],
             &self.abi_version
         )
     }
 
+    /// Checks if function follows `responsible` convention: first input is `answerId` of type uint32
+    pub fn is_responsible(&self) -> bool {
+        self.inputs.first().map_or(false, |param| {
+            param.name == ANSWER_ID_NAME && param.kind == ParamType::Uint(32)
+        })
+    }
+
+    /// Encodes internal call of `responsible` function. `answerId` input is injected
+    /// as the first parameter if it is not present in provided `input`
+    pub fn encode_internal_input_with_answer_id(
+        &self,
+        answer_id: u32,
+        input: &[Token],
+    ) -> Result<BuilderData> {
+        if !self.is_responsible() {
+            fail!(AbiError::InvalidInputData {
+                msg: format!("Function {} is not responsible", self.name)
+            });
+        }
+        if input.first().map_or(false, |token| token.name == ANSWER_ID_NAME) {
+            return self.encode_internal_input(input);
+        }
+
+        let mut tokens = Vec::with_capacity(input.len() + 1);
+        tokens.push(Token::new(ANSWER_ID_NAME, TokenValue::Uint(Uint::new(answer_id as u128, 32))));
+        tokens.extend_from_slice(input);
+        self.encode_internal_input(&tokens)
+    }
+
+    /// Decodes callback of `responsible` function call. Callback id should be equal to
+    /// `answer_id` passed in originating call
+    pub fn decode_responsible_output(&self, mut data: SliceData, answer_id: u32) -> Result<Vec<Token>> {
+        let id = data.get_next_u32()?;
+        if id != answer_id { Err(AbiError::WrongId { id } )? }
+        TokenValue::decode_params(self.output_params(), data, &self.abi_version, false)
+    }
+
     /// Encodes provided function parameters into `BuilderData` containing ABI contract call.
     pub fn encode_run_local_input(&self, time: u64, input: &[Token]) -> Result<BuilderData> {
+        self.encode_run_local_input_with_header(time, &HashMap::new(), input)
+    }
+
+    /// Encodes provided function parameters into `BuilderData` containing ABI contract call.
+    /// Provided header values are used instead of defaults.
+    pub fn encode_run_local_input_with_header(
+        &self,
+        time: u64,
+        header: &HashMap<String, TokenValue>,
+        input: &[Token],
+    ) -> Result<BuilderData> {
         let params = self.input_params();
 
         if !Token::types_check(input, params) {
@@ -457,7 +1121,7 @@ impl Function {
         }
 
         // prepare standard message
-        let mut cells = self.encode_default_header(time, false)?;
+        let mut cells = self.encode_default_header(time, header, false)?;
 
         let mut remove_ref = false;
         let mut remove_bits = 0;
@@ -477,7 +1141,7 @@ impl Function {
             SerializedValue {
                 data: sign_builder,
                 max_bits: if self.abi_version >= ABI_VERSION_2_3 {
-                    TokenValue::max_bit_size(&ParamType::Address, &self.abi_version)
+                    ParamType::Address.max_bit_size(&self.abi_version)
                 } else {
                     1 + SIGNATURE_LENGTH * 8
                 },
@@ -518,10 +1182,22 @@ impl Function {
 
     /// Add sign to messsage body returned by `prepare_input_for_sign` function
     pub fn fill_sign(
+        abi_version: &AbiVersion,
+        signature: Option<&[u8]>,
+        public_key: Option<&[u8]>,
+        builder: BuilderData,
+    ) -> Result<BuilderData> {
+        Self::fill_sign_with_placement(
+            abi_version, signature, public_key, builder, SignaturePlacement::InCell)
+    }
+
+    /// Adds signature to unsigned call body according to provided `placement`
+    pub fn fill_sign_with_placement(
         abi_version: &AbiVersion,
         signature: Option<&[u8]>,
         public_key: Option<&[u8]>,
         mut builder: BuilderData,
+        placement: SignaturePlacement,
     ) -> Result<BuilderData> {
 
         if abi_version == &ABI_VERSION_1_0 {
@@ -542,6 +1218,27 @@ impl Function {
             } else {
                 builder.checked_prepend_reference(ton_types::Cell::default())?;
             }
+        } else if placement == SignaturePlacement::InReference {
+            // prefix bit in cell body, sign in reference
+            let mut sign_builder = BuilderData::new();
+            if let Some(signature) = signature {
+                if builder.references_free() == 0 {
+                    fail!(AbiError::InvalidInputData {
+                        msg: "No free reference for signature".to_owned()
+                    });
+                }
+                let mut signature = SmallVec::from_slice(signature);
+                if let Some(public_key) = public_key {
+                    signature.extend_from_slice(public_key);
+                }
+
+                let len = signature.len() * 8;
+                builder.checked_prepend_reference(BuilderData::with_raw(signature, len)?.into_cell()?)?;
+                sign_builder.append_bit_one()?;
+            } else {
+                sign_builder.append_bit_zero()?;
+            }
+            builder.prepend_builder(&sign_builder)?;
         } else {
             // sign in cell body
             let mut sign_builder = BuilderData::new();
@@ -558,6 +1255,115 @@ impl Function {
         Ok(builder)
     }
 
+    /// Adds several signatures to unsigned call body prepared with
+    /// `SignaturePlacement::InReference`. Prefix bit is set and signatures are stored in the
+    /// first reference as `count:uint8` cell referencing chain of `signature:bits512 pubkey:bits256`
+    /// cells. Empty list produces unsigned call body. Placement of the call is not checked here,
+    /// public API is `UnsignedCall::fill_multi_sign`.
+    pub(crate) fn fill_multi_sign(
+        abi_version: &AbiVersion,
+        signatures: &[(Signature, PublicKey)],
+        mut builder: BuilderData,
+    ) -> Result<BuilderData> {
+        if abi_version == &ABI_VERSION_1_0 {
+            fail!(AbiError::InvalidInputData {
+                msg: "Multiple signatures are not supported in ABI 1.0".to_owned()
+            });
+        }
+        if signatures.is_empty() {
+            return Self::fill_sign_with_placement(
+                abi_version, None, None, builder, SignaturePlacement::InReference);
+        }
+        if signatures.len() > MAX_MULTI_SIGNATURES {
+            fail!(AbiError::InvalidInputData {
+                msg: format!("Call can't have more than {} signatures", MAX_MULTI_SIGNATURES)
+            });
+        }
+        if builder.references_free() == 0 {
+            fail!(AbiError::InvalidInputData {
+                msg: "No free reference for signature".to_owned()
+            });
+        }
+
+        let mut next = None;
+        for (signature, public_key) in signatures.iter().rev() {
+            let mut entry = BuilderData::new();
+            entry.append_raw(&signature.to_bytes(), SIGNATURE_LENGTH * 8)?;
+            entry.append_raw(public_key.as_bytes(), PUBLIC_KEY_LENGTH * 8)?;
+            if let Some(next) = next {
+                entry.checked_append_reference(next)?;
+            }
+            next = Some(entry.into_cell()?);
+        }
+        let mut list = BuilderData::new();
+        list.append_u8(signatures.len() as u8)?;
+        if let Some(first) = next {
+            list.checked_append_reference(first)?;
+        }
+
+        builder.checked_prepend_reference(list.into_cell()?)?;
+        let mut sign_builder = BuilderData::new();
+        sign_builder.append_bit_one()?;
+        builder.prepend_builder(&sign_builder)?;
+        Ok(builder)
+    }
+
+    /// Extracts signatures from call body encoded by `fill_multi_sign`. Returns signatures with
+    /// public keys of signers and body remaining after signatures.
+    pub fn extract_signatures(
+        abi_version: &AbiVersion,
+        mut body: SliceData,
+    ) -> Result<(Vec<(Signature, PublicKey)>, SliceData)> {
+        if abi_version == &ABI_VERSION_1_0 {
+            fail!(AbiError::InvalidInputData {
+                msg: "Multiple signatures are not supported in ABI 1.0".to_owned()
+            });
+        }
+        if !body.get_next_bit()? {
+            return Ok((vec![], body));
+        }
+        let malformed = |msg: &str| AbiError::InvalidInputData {
+            msg: format!("Malformed signature list: {}", msg)
+        };
+        let mut entry = SliceData::load_cell(body.checked_drain_reference()?)?;
+        let count = entry.get_next_byte()? as usize;
+        if count == 0 {
+            fail!(malformed("signed call should have at least one signature"));
+        }
+        let mut signatures = Vec::with_capacity(count);
+        for _ in 0..count {
+            if entry.remaining_bits() != 0 || entry.remaining_references() != 1 {
+                fail!(malformed(&format!("expected {} signatures", count)));
+            }
+            entry = SliceData::load_cell(entry.checked_drain_reference()?)?;
+            let signature = Signature::from_bytes(&entry.get_next_bytes(SIGNATURE_LENGTH)?)?;
+            let public_key = PublicKey::from_bytes(&entry.get_next_bytes(PUBLIC_KEY_LENGTH)?)?;
+            signatures.push((signature, public_key));
+        }
+        if entry.remaining_bits() != 0 || entry.remaining_references() != 0 {
+            fail!(malformed(&format!("data found beyond {} signatures", count)));
+        }
+        Ok((signatures, body))
+    }
+
+    /// Appends signature to call body encoded by `fill_multi_sign`, possibly with empty
+    /// signature list. Used for partial signing when signers add their signatures one by one.
+    pub fn append_signature(
+        abi_version: &AbiVersion,
+        signature: Signature,
+        public_key: PublicKey,
+        body: SliceData,
+    ) -> Result<BuilderData> {
+        let (mut signatures, body) = Self::extract_signatures(abi_version, body)?;
+        if signatures.iter().any(|(_, key)| constant_time_eq(key.as_bytes(), public_key.as_bytes())) {
+            fail!(AbiError::InvalidInputData {
+                msg: format!("Call is already signed by {}", hex::encode(public_key.as_bytes()))
+            });
+        }
+        signatures.push((signature, public_key));
+        Self::fill_multi_sign(abi_version, &signatures, BuilderData::from_slice(&body))
+    }
+
     /// Add sign to messsage body returned by `prepare_input_for_sign` function
     pub fn add_sign_to_encoded_input(
         abi_version: &AbiVersion,
@@ -570,6 +1376,188 @@ impl Function {
         Self::fill_sign(abi_version, Some(signature), public_key, builder)
     }
 
+    /// Extracts signature from external function call body without decoding header and
+    /// parameters. Public key is stored along with signature only in ABI 1.0, for other
+    /// versions it is a part of header. Returns body remaining after signature.
+    pub fn unpack_signature(
+        abi_version: &AbiVersion,
+        body: SliceData,
+    ) -> Result<(Option<Signature>, Option<PublicKey>, SliceData)> {
+        Self::unpack_signature_with_placement(abi_version, body, SignaturePlacement::InCell)
+    }
+
+    /// Extracts signature and signer public key from external call body of this function.
+    /// Public key stored along with signature is preferred, otherwise it is taken from
+    /// `pubkey` header param. Returns body remaining after signature.
+    pub fn unpack_call_signature(
+        &self,
+        body: SliceData,
+        placement: SignaturePlacement,
+    ) -> Result<(Option<Signature>, Option<PublicKey>, SliceData)> {
+        let (signature, public_key, rest) =
+            Self::unpack_signature_with_placement(&self.abi_version, body.clone(), placement)?;
+        if signature.is_none() || public_key.is_some() {
+            return Ok((signature, public_key, rest));
+        }
+        let (tokens, _, _) =
+            Self::decode_header_with_placement(&self.abi_version, body, &self.header, false, placement)?;
+        let public_key = tokens.into_iter().find_map(|token| match token.value {
+            TokenValue::PublicKey(key) => key,
+            _ => None,
+        });
+        Ok((signature, public_key, rest))
+    }
+
+    /// Extracts signature from external function call body signed with provided `placement`.
+    /// Public key is returned if it is stored along with signature: in ABI 1.0 and for
+    /// `SignaturePlacement::InReference`.
+    pub fn unpack_signature_with_placement(
+        abi_version: &AbiVersion,
+        mut body: SliceData,
+        placement: SignaturePlacement,
+    ) -> Result<(Option<Signature>, Option<PublicKey>, SliceData)> {
+        if abi_version == &ABI_VERSION_1_0 {
+            let mut sign_slice = SliceData::load_cell(body.checked_drain_reference()?)?;
+            if sign_slice.remaining_bits() < SIGNATURE_LENGTH * 8 {
+                return Ok((None, None, body));
+            }
+            let signature = Signature::from_bytes(&sign_slice.get_next_bytes(SIGNATURE_LENGTH)?)?;
+            let public_key = if sign_slice.remaining_bits() >= PUBLIC_KEY_LENGTH * 8 {
+                Some(PublicKey::from_bytes(&sign_slice.get_next_bytes(PUBLIC_KEY_LENGTH)?)?)
+            } else {
+                None
+            };
+            Ok((Some(signature), public_key, body))
+        } else if !body.get_next_bit()? {
+            Ok((None, None, body))
+        } else if placement == SignaturePlacement::InReference {
+            let mut sign_slice = SliceData::load_cell(body.checked_drain_reference()?)?;
+            let signature = Signature::from_bytes(&sign_slice.get_next_bytes(SIGNATURE_LENGTH)?)?;
+            let public_key = if sign_slice.remaining_bits() >= PUBLIC_KEY_LENGTH * 8 {
+                Some(PublicKey::from_bytes(&sign_slice.get_next_bytes(PUBLIC_KEY_LENGTH)?)?)
+            } else {
+                None
+            };
+            Ok((Some(signature), public_key, body))
+        } else {
+            let signature = Signature::from_bytes(&body.get_next_bytes(SIGNATURE_LENGTH)?)?;
+            Ok((Some(signature), None, body))
+        }
+    }
+
+    /// Verifies signature of external function call body with provided public key.
+    /// Returns `false` if body is not signed
+    pub fn verify_signature(
+        &self,
+        body: SliceData,
+        pubkey: &PublicKey,
+        address: Option<MsgAddressInt>,
+        signature_id: Option<i32>,
+    ) -> Result<bool> {
+        Self::verify_encoded_input(&self.abi_version, body, pubkey, address, signature_id)
+    }
+
+    /// Verifies signature of external function call body signed with provided `placement`
+    pub fn verify_signature_with_placement(
+        &self,
+        body: SliceData,
+        pubkey: &PublicKey,
+        address: Option<MsgAddressInt>,
+        signature_id: Option<i32>,
+        placement: SignaturePlacement,
+    ) -> Result<bool> {
+        Self::verify_encoded_input_with_placement(
+            &self.abi_version, body, pubkey, address, signature_id, placement)
+    }
+
+    /// Verifies signature of external function call body encoded with particular ABI version.
+    /// Function is not decoded so it can be used for any function of the contract
+    pub fn verify_encoded_input(
+        abi_version: &AbiVersion,
+        body: SliceData,
+        pubkey: &PublicKey,
+        address: Option<MsgAddressInt>,
+        signature_id: Option<i32>,
+    ) -> Result<bool> {
+        Self::verify_encoded_input_with_placement(
+            abi_version, body, pubkey, address, signature_id, SignaturePlacement::InCell)
+    }
+
+    /// Verifies signature of external function call body encoded with particular ABI version
+    /// and signed with provided `placement`
+    pub fn verify_encoded_input_with_placement(
+        abi_version: &AbiVersion,
+        body: SliceData,
+        pubkey: &PublicKey,
+        address: Option<MsgAddressInt>,
+        signature_id: Option<i32>,
+        placement: SignaturePlacement,
+    ) -> Result<bool> {
+        let (signature, public_key, body) = Self::unpack_signature_with_placement(abi_version, body, placement)?;
+        let signature = match signature {
+            Some(signature) => signature,
+            None => return Ok(false),
+        };
+        // call signed with key stored along with signature is not valid for other keys
+        if let Some(public_key) = public_key {
+            if !constant_time_eq(public_key.as_bytes(), pubkey.as_bytes()) {
+                return Ok(false);
+            }
+        }
+
+        let call = UnsignedCall {
+            abi_version: *abi_version,
+            builder: BuilderData::from_slice(&body),
+            reserve_sign: true,
+            placement,
+        };
+        let data = call.data_to_sign(address, signature_id)?;
+
+        Ok(ed25519::signature::Verifier::verify(pubkey, &data, &signature).is_ok())
+    }
+
+    /// Validates decoded `time` and `expire` header values using contract replay protection rules:
+    /// `time` should be greater than `last_time` and not ahead of `now_ms` by more than
+    /// `MAX_TIME_DRIFT_MS`, `expire` should be in range `(now, now + expire_window]` seconds
+    pub fn check_header(
+        header: &[Token],
+        now_ms: u64,
+        last_time: u64,
+        expire_window: u32,
+    ) -> Result<()> {
+        for token in header {
+            match token.value {
+                TokenValue::Time(time) => {
+                    if time <= last_time {
+                        fail!(AbiError::InvalidHeader {
+                            msg: format!("time {} is not greater than last time {}", time, last_time)
+                        });
+                    }
+                    if time > now_ms.saturating_add(MAX_TIME_DRIFT_MS) {
+                        fail!(AbiError::InvalidHeader {
+                            msg: format!("time {} is too far in the future", time)
+                        });
+                    }
+                }
+                TokenValue::Expire(expire) => {
+                    let now = now_ms / 1000;
+                    if expire as u64 <= now {
+                        fail!(AbiError::InvalidHeader {
+                            msg: format!("message expired at {}", expire)
+                        });
+                    }
+                    if expire as u64 > now + expire_window as u64 {
+                        fail!(AbiError::InvalidHeader {
+                            msg: format!("expire {} exceeds allowed window of {} seconds", expire, expire_window)
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
     /// Check if message body is related to this function
     pub fn is_my_input_message(&self, data: SliceData, internal: bool) -> Result<bool> {
         let decoded_id = Self::decode_input_id(&self.abi_version, data, &self.header, internal)?;
@@ -0,0 +1,107 @@
+/*
+* Copyright 2018-2020 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use ton_types::{BuilderData, fail, Result, SliceData};
+
+use crate::{Param, Token, TokenValue};
+use crate::contract::{SerdeFunction, AbiVersion};
+use crate::error::AbiError;
+
+/// Contract getter (get-method) specification.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Getter {
+    /// ABI version
+    pub abi_version: AbiVersion,
+    /// Getter name.
+    pub name: String,
+    /// Getter input.
+    pub inputs: Vec<Param>,
+    /// Getter output.
+    pub outputs: Vec<Param>,
+    /// Get-method ID
+    pub id: u32,
+}
+
+impl Getter {
+    /// Creates `Getter` struct from parsed JSON struct `SerdeFunction`
+    pub fn from_serde(abi_version: AbiVersion, serde_getter: SerdeFunction) -> Self {
+        let id = serde_getter.id.unwrap_or_else(|| Self::calc_method_id(&serde_getter.name));
+        Getter {
+            abi_version,
+            name: serde_getter.name,
+            inputs: serde_getter.inputs,
+            outputs: serde_getter.outputs,
+            id,
+        }
+    }
+
+    /// Returns all input params of given getter.
+    pub fn input_params(&self) -> &Vec<Param> {
+        &self.inputs
+    }
+
+    /// Returns all output params of given getter.
+    pub fn output_params(&self) -> &Vec<Param> {
+        &self.outputs
+    }
+
+    /// Retruns ABI getter signature
+    pub fn get_function_signature(&self) -> String {
+        let types = |params: &[Param]| params.iter()
+            .map(|param| param.kind.type_signature())
+            .collect::<Vec<String>>()
+            .join(",");
+
+        format!("{}({})({})v{}", self.name, types(&self.inputs), types(&self.outputs), self.abi_version.major)
+    }
+
+    /// Returns get-method ID used to invoke getter
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+
+    /// Computes TVM get-method ID: CRC16/XMODEM of getter name with 17th bit set
+    pub fn calc_method_id(name: &str) -> u32 {
+        let mut crc = 0u16;
+        for byte in name.as_bytes() {
+            crc ^= (*byte as u16) << 8;
+            for _ in 0..8 {
+                crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+            }
+        }
+        crc as u32 | 0x10000
+    }
+
+    /// Encodes provided getter parameters into `BuilderData`.
+    pub fn encode_input(&self, input: &[Token]) -> Result<BuilderData> {
+        if !Token::types_check(input, self.input_params()) {
+            fail!(AbiError::WrongParameterType);
+        }
+
+        TokenValue::pack_values_into_chain(input, vec![], &self.abi_version)
+    }
+
+    /// Parses encoded getter parameters to list of tokens.
+    pub fn decode_input(&self, data: SliceData, allow_partial: bool) -> Result<Vec<Token>> {
+        TokenValue::decode_params(self.input_params(), data, &self.abi_version, allow_partial)
+    }
+
+    /// Parses encoded getter result to list of tokens.
+    pub fn decode_output(&self, data: SliceData, allow_partial: bool) -> Result<Vec<Token>> {
+        TokenValue::decode_params(self.output_params(), data, &self.abi_version, allow_partial)
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_getter.rs"]
+mod tests;
@@ -12,9 +12,12 @@
 */
 
 use crate::{
-    error::AbiError,
-    contract::Contract,
-    token::{Detokenizer, Tokenizer, TokenValue}
+    error::{AbiError, AbiResult},
+    contract::{AbiVersion, Contract, LoadOptions, MessageBodyKind, PublicKeyData},
+    function::Function,
+    signature::Signer,
+    token::{Detokenizer, Token, Tokenizer, TokenValue},
+    warnings::Warnings,
 };
 
 use std::collections::{HashMap};
@@ -23,8 +26,10 @@ use std::str::FromStr;
 use ed25519_dalek::{Keypair};
 use serde_json::Value;
 
-use ton_types::{Result, BuilderData, SliceData};
-use ton_block::MsgAddressInt;
+use ton_types::{BuilderData, Cell, SliceData};
+use ton_block::{MsgAddressInt, Serializable, StateInit};
+
+pub mod compat;
 
 
 /// Encodes `parameters` for given `function` of contract described by `abi` into `BuilderData`
@@ -37,7 +42,225 @@ pub fn encode_function_call(
     internal: bool,
     pair: Option<(&Keypair, Option<i32>)>,
     address: Option<String>,
-) -> Result<BuilderData> {
+) -> AbiResult<BuilderData> {
+    encode_function_call_with_signer(
+        abi,
+        function,
+        header,
+        parameters,
+        internal,
+        pair.map(|(pair, signature_id)| (pair as &dyn Signer, signature_id)),
+        address,
+    )
+}
+
+/// Encodes `parameters` for given `function` of contract described by `abi` into base64
+/// encoded BOC which can be used as message body for calling contract
+pub fn encode_function_call_to_boc(
+    abi: &str,
+    function: &str,
+    header: Option<&str>,
+    parameters: &str,
+    internal: bool,
+    pair: Option<(&Keypair, Option<i32>)>,
+    address: Option<String>,
+) -> AbiResult<String> {
+    let body = encode_function_call(abi, function, header, parameters, internal, pair, address)?;
+    let boc = ton_types::serialize_toc(&body.into_cell()?)?;
+    Ok(base64::encode(boc))
+}
+
+/// Encodes `parameters` for given `function` of contract described by `abi` into internal
+/// message addressed to `dst` and carrying `value` nanotokens. Returns base64 encoded BOC of
+/// the message
+pub fn encode_internal_message(
+    abi: &str,
+    function: &str,
+    parameters: &str,
+    value: u64,
+    bounce: bool,
+    dst: &str,
+) -> AbiResult<String> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    let function = contract.function(function)?;
+
+    let v: Value = serde_json::from_str(parameters).map_err(|err| AbiError::SerdeError { err } )?;
+    let attributes = contract.params_attributes(&format!("function {} inputs", function.name), function.input_params());
+    let input_tokens = Tokenizer::tokenize_all_params_with_defaults(function.input_params(), &attributes, &v)?;
+
+    let dst = MsgAddressInt::from_str(dst)?;
+    let message = function.encode_internal_message(dst, value, bounce, true, &input_tokens)?;
+
+    let boc = ton_types::serialize_toc(&message.serialize()?)?;
+    Ok(base64::encode(boc))
+}
+
+/// Decodes function call from base64 encoded BOC. Returns parametes and function name
+pub fn decode_function_call_from_boc(
+    abi: &str,
+    boc: &str,
+    internal: bool,
+    allow_partial: bool,
+) -> AbiResult<DecodedMessage> {
+    let boc = base64::decode(boc).map_err(|err| AbiError::InvalidData {
+        msg: format!("Invalid base64 BOC: {}", err)
+    })?;
+    let call = ton_types::deserialize_tree_of_cells(&mut boc.as_slice())?;
+    decode_unknown_function_call_from_cell(abi, &call, internal, allow_partial)
+}
+
+/// Encodes `parameters` for given `function` of contract described by `abi` into `BuilderData`
+/// which can be used as message body for calling contract. Message is signed by provided `Signer`
+pub fn encode_function_call_with_signer(
+    abi: &str,
+    function: &str,
+    header: Option<&str>,
+    parameters: &str,
+    internal: bool,
+    signer: Option<(&dyn Signer, Option<i32>)>,
+    address: Option<String>,
+) -> AbiResult<BuilderData> {
+    let mut options = CallOptions::new().internal(internal);
+    if let Some(header) = header {
+        options = options.header(header);
+    }
+    if let Some((signer, signature_id)) = signer {
+        options = options.signer(signer);
+        if let Some(signature_id) = signature_id {
+            options = options.signature_id(signature_id);
+        }
+    }
+    if let Some(address) = address {
+        options = options.address(address);
+    }
+    encode_function_call_with_options(abi, function, parameters, options)
+}
+
+/// Options of function call encoding used by `encode_function_call_with_options`.
+/// By default call is external, unsigned, with empty header and without destination address
+#[derive(Default)]
+pub struct CallOptions<'a> {
+    header: Option<&'a str>,
+    internal: bool,
+    signer: Option<&'a dyn Signer>,
+    signature_id: Option<i32>,
+    address: Option<String>,
+    abi_version: Option<&'a str>,
+    warnings: Option<&'a mut Warnings>,
+}
+
+impl<'a> CallOptions<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// JSON with header values
+    pub fn header(mut self, header: &'a str) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    /// Encode internal call (without header and signature)
+    pub fn internal(mut self, internal: bool) -> Self {
+        self.internal = internal;
+        self
+    }
+
+    /// Sign external call with provided signer
+    pub fn signer(mut self, signer: &'a dyn Signer) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// Signature id of the network, see `signature_id_from_global_id`
+    pub fn signature_id(mut self, signature_id: i32) -> Self {
+        self.signature_id = Some(signature_id);
+        self
+    }
+
+    /// Destination address, required for signed calls since ABI 2.3
+    pub fn address(mut self, address: impl Into<String>) -> Self {
+        self.address = Some(address.into());
+        self
+    }
+
+    /// Encode call by rules of provided ABI version (e.g. `"2.2"`) instead of the version declared
+    /// in ABI. All types used in ABI should be supported by this version
+    pub fn abi_version(mut self, abi_version: &'a str) -> Self {
+        self.abi_version = Some(abi_version);
+        self
+    }
+
+    /// Collect recoverable issues (defaulted header values, ignored JSON fields, deprecated
+    /// types) into `warnings` instead of silently accepting them
+    pub fn warnings(mut self, warnings: &'a mut Warnings) -> Self {
+        self.warnings = Some(warnings);
+        self
+    }
+}
+
+/// Encodes `parameters` for given `function` of contract described by `abi` into `BuilderData`
+/// which can be used as message body for calling contract
+pub fn encode_function_call_with_options(
+    abi: &str,
+    function: &str,
+    parameters: &str,
+    options: CallOptions,
+) -> AbiResult<BuilderData> {
+    let CallOptions { header, internal, signer, signature_id, address, abi_version, warnings } = options;
+    let signer = signer.map(|signer| (signer, signature_id));
+
+    let load_options = LoadOptions {
+        abi_version: abi_version.map(AbiVersion::parse).transpose()?,
+        ..Default::default()
+    };
+    let contract = Contract::load_with_options(abi.as_bytes(), load_options)?;
+
+    let function = contract.function(function)?;
+
+    let mut header_tokens = if let Some(header) = header {
+        let v: Value = serde_json::from_str(header).map_err(|err| AbiError::SerdeError { err } )?;
+        Tokenizer::tokenize_optional_params(function.header_params(), &v)?
+    } else {
+        HashMap::new()
+    };
+    // add public key into header
+    if let Some((signer, _)) = signer {
+        if !header_tokens.contains_key("pubkey") {
+            header_tokens.insert("pubkey".to_owned(), TokenValue::PublicKey(Some(signer.public_key())));
+        }
+    }
+
+    let v: Value = serde_json::from_str(parameters).map_err(|err| AbiError::SerdeError { err } )?;
+    let address = address.map(|string| MsgAddressInt::from_str(&string)).transpose()?;
+
+    let attributes = contract.params_attributes(&format!("function {} inputs", function.name), function.input_params());
+    match warnings {
+        Some(warnings) => {
+            let input_tokens = Tokenizer::tokenize_all_params_with_warnings(function.input_params(), &attributes, &v, warnings)?;
+            Ok(function.encode_input_with_warnings(&header_tokens, &input_tokens, internal, signer, address, warnings)?)
+        }
+        None => {
+            let input_tokens = Tokenizer::tokenize_all_params_with_defaults(function.input_params(), &attributes, &v)?;
+            Ok(function.encode_input_with_signer(&header_tokens, &input_tokens, internal, signer, address)?)
+        }
+    }
+}
+
+/// Encodes `parameters` for given `function` of contract described by `abi` into `BuilderData`
+/// which can be used as message body for external call of contract. Message is signed by
+/// provided `AsyncSigner`
+#[cfg(feature = "async")]
+pub async fn encode_function_call_with_async_signer<S: crate::signature::AsyncSigner + ?Sized>(
+    abi: &str,
+    function: &str,
+    header: Option<&str>,
+    parameters: &str,
+    signer: &S,
+    signature_id: Option<i32>,
+    address: Option<String>,
+) -> AbiResult<BuilderData> {
     let contract = Contract::load(abi.as_bytes())?;
 
     let function = contract.function(function)?;
@@ -49,16 +272,19 @@ pub fn encode_function_call(
         HashMap::new()
     };
     // add public key into header
-    if pair.is_some() && !header_tokens.contains_key("pubkey") {
-        header_tokens.insert("pubkey".to_owned(), TokenValue::PublicKey(pair.map(|(pair, _)| pair.public)));
+    if !header_tokens.contains_key("pubkey") {
+        header_tokens.insert("pubkey".to_owned(), TokenValue::PublicKey(Some(signer.public_key())));
     }
 
     let v: Value = serde_json::from_str(parameters).map_err(|err| AbiError::SerdeError { err } )?;
-    let input_tokens = Tokenizer::tokenize_all_params(function.input_params(), &v)?;
+    let attributes = contract.params_attributes(&format!("function {} inputs", function.name), function.input_params());
+    let input_tokens = Tokenizer::tokenize_all_params_with_defaults(function.input_params(), &attributes, &v)?;
 
     let address = address.map(|string| MsgAddressInt::from_str(&string)).transpose()?;
 
-    function.encode_input(&header_tokens, &input_tokens, internal, pair, address)
+    Ok(function
+        .encode_input_with_async_signer(&header_tokens, &input_tokens, signer, signature_id, address)
+        .await?)
 }
 
 /// Encodes `parameters` for given `function` of contract described by `abi` into `BuilderData`
@@ -70,7 +296,7 @@ pub fn prepare_function_call_for_sign(
     header: Option<&str>,
     parameters: &str,
     address: Option<String>,
-) -> Result<(BuilderData, ton_types::UInt256)> {
+) -> AbiResult<(BuilderData, ton_types::UInt256)> {
     let contract = Contract::load(abi.as_bytes())?;
 
     let function = contract.function(function)?;
@@ -83,11 +309,12 @@ pub fn prepare_function_call_for_sign(
     };
 
     let v: Value = serde_json::from_str(parameters).map_err(|err| AbiError::SerdeError { err } )?;
-    let input_tokens = Tokenizer::tokenize_all_params(function.input_params(), &v)?;
+    let attributes = contract.params_attributes(&format!("function {} inputs", function.name), function.input_params());
+    let input_tokens = Tokenizer::tokenize_all_params_with_defaults(function.input_params(), &attributes, &v)?;
 
     let address = address.map(|string| MsgAddressInt::from_str(&string)).transpose()?;
 
-    function.create_unsigned_call(&header_tokens, &input_tokens, false, true, address)
+    Ok(function.create_unsigned_call(&header_tokens, &input_tokens, false, true, address)?)
 }
 
 /// Add sign to messsage body returned by `prepare_function_call_for_sign` function
@@ -96,9 +323,26 @@ pub fn add_sign_to_function_call(
     signature: &[u8],
     public_key: Option<&[u8]>,
     function_call: SliceData
-) -> Result<BuilderData> {
+) -> AbiResult<BuilderData> {
+    let contract = Contract::load(abi.as_bytes())?;
+    Ok(contract.add_sign_to_encoded_input(signature, public_key, function_call)?)
+}
+
+/// Verifies signature of external function call `body` of contract described by `abi`.
+/// Signed data is derived according to ABI version, so `address` is required since ABI 2.3.
+/// Returns `false` if body is not signed or signature doesn't match `pubkey`
+pub fn verify_function_call(
+    abi: &str,
+    body: SliceData,
+    pubkey: &PublicKeyData,
+    address: Option<String>,
+    signature_id: Option<i32>,
+) -> AbiResult<bool> {
     let contract = Contract::load(abi.as_bytes())?;
-    contract.add_sign_to_encoded_input(signature, public_key, function_call)
+
+    let address = address.map(|string| MsgAddressInt::from_str(&string)).transpose()?;
+
+    Ok(contract.verify_signature(body, pubkey, address, signature_id)?)
 }
 
 /// Decodes output parameters returned by contract function call
@@ -107,19 +351,130 @@ pub fn decode_function_response(
     function: &str,
     response: SliceData,
     internal: bool,
-) -> Result<String> {
+) -> AbiResult<String> {
+    let tokens = decode_function_response_tokens(abi, function, response, internal, false)?;
+
+    Ok(Detokenizer::detokenize(&tokens)?)
+}
+
+/// Decodes output parameters returned by contract function call. Data remaining after
+/// declared outputs (e.g. returned by newer contract version) is ignored
+pub fn decode_function_response_partial(
+    abi: &str,
+    function: &str,
+    response: SliceData,
+    internal: bool,
+) -> AbiResult<String> {
+    let tokens = decode_function_response_tokens(abi, function, response, internal, true)?;
+
+    Ok(Detokenizer::detokenize(&tokens)?)
+}
+
+/// Decodes output parameters returned by contract function call into tokens
+pub fn decode_function_response_tokens(
+    abi: &str,
+    function: &str,
+    response: SliceData,
+    internal: bool,
+    allow_partial: bool,
+) -> AbiResult<Vec<Token>> {
+    decode_function_response_impl(abi, function, response, internal, allow_partial, None)
+}
+
+/// Decodes output parameters returned by contract function call. Ignored trailing data and
+/// deprecated output types are reported into `warnings`
+pub fn decode_function_response_with_warnings(
+    abi: &str,
+    function: &str,
+    response: SliceData,
+    internal: bool,
+    allow_partial: bool,
+    warnings: &mut Warnings,
+) -> AbiResult<String> {
+    let tokens = decode_function_response_impl(abi, function, response, internal, allow_partial, Some(warnings))?;
+
+    Ok(Detokenizer::detokenize(&tokens)?)
+}
+
+fn decode_function_response_impl(
+    abi: &str,
+    function: &str,
+    response: SliceData,
+    internal: bool,
+    allow_partial: bool,
+    warnings: Option<&mut Warnings>,
+) -> AbiResult<Vec<Token>> {
     let contract = Contract::load(abi.as_bytes())?;
 
     let function = contract.function(function)?;
 
-    let tokens = function.decode_output(response, internal)?;
+    let tokens = match warnings {
+        Some(warnings) => function.decode_output_with_warnings(response, internal, allow_partial, warnings)?,
+        None if allow_partial => function.decode_output_partial(response, internal)?,
+        None => function.decode_output(response, internal)?,
+    };
+
+    Ok(tokens)
+}
+
+/// Decodes output parameters returned by contract function call from `Cell`
+pub fn decode_function_response_from_cell(
+    abi: &str,
+    function: &str,
+    response: &Cell,
+    internal: bool,
+    allow_partial: bool,
+) -> AbiResult<String> {
+    let response = SliceData::load_cell(response.clone())?;
+    let tokens = decode_function_response_tokens(abi, function, response, internal, allow_partial)?;
+
+    Ok(Detokenizer::detokenize(&tokens)?)
+}
+
+/// Encodes `parameters` for given `getter` of contract described by `abi` into `BuilderData`
+pub fn encode_getter_input(abi: &str, getter: &str, parameters: &str) -> AbiResult<BuilderData> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    let getter = contract.getter(getter)?;
+
+    let v: Value = serde_json::from_str(parameters).map_err(|err| AbiError::SerdeError { err })?;
+    let attributes = contract.params_attributes(&format!("getter {} inputs", getter.name), getter.input_params());
+    let input_tokens = Tokenizer::tokenize_all_params_with_defaults(getter.input_params(), &attributes, &v)?;
+
+    Ok(getter.encode_input(&input_tokens)?)
+}
+
+/// Decodes output parameters returned by contract getter
+pub fn decode_getter_output(
+    abi: &str,
+    getter: &str,
+    response: SliceData,
+    allow_partial: bool,
+) -> AbiResult<String> {
+    let tokens = decode_getter_output_tokens(abi, getter, response, allow_partial)?;
+
+    Ok(Detokenizer::detokenize(&tokens)?)
+}
+
+/// Decodes output parameters returned by contract getter into tokens
+pub fn decode_getter_output_tokens(
+    abi: &str,
+    getter: &str,
+    response: SliceData,
+    allow_partial: bool,
+) -> AbiResult<Vec<Token>> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    let getter = contract.getter(getter)?;
 
-    Detokenizer::detokenize(&tokens)
+    Ok(getter.decode_output(response, allow_partial)?)
 }
 
 pub struct DecodedMessage {
     pub function_name: String,
-    pub params: String
+    pub params: String,
+    /// JSON with decoded header values, provided only by `*_with_header` functions
+    pub header: Option<String>,
 }
 
 /// Decodes output parameters returned by some function call. Returns parametes and function name
@@ -127,7 +482,7 @@ pub fn decode_unknown_function_response(
     abi: &str,
     response: SliceData,
     internal: bool,
-) -> Result<DecodedMessage> {
+) -> AbiResult<DecodedMessage> {
     let contract = Contract::load(abi.as_bytes())?;
 
     let result = contract.decode_output(response, internal)?;
@@ -136,17 +491,65 @@ pub fn decode_unknown_function_response(
 
     Ok(DecodedMessage {
         function_name: result.function_name,
-        params: output
+        params: output,
+        header: None,
     })
 }
 
+/// Result of decoding message body which may not match any function or event of contract
+pub enum DecodedResponse {
+    /// Body matched contract function output or event
+    Decoded(DecodedMessage),
+    /// Body id doesn't match any function output or event
+    Unknown {
+        id: u32,
+        body: SliceData,
+    },
+}
+
+/// Decodes output parameters returned by some function call. Unlike
+/// `decode_unknown_function_response` returns `DecodedResponse::Unknown` instead of error if
+/// body id doesn't match any function output or event
+pub fn try_decode_unknown_function_response(
+    abi: &str,
+    response: SliceData,
+    internal: bool,
+) -> AbiResult<DecodedResponse> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    let id = Function::decode_output_id(response.clone())?;
+    if contract.function_by_id(id, false).is_err() && contract.event_by_id(id).is_err() {
+        return Ok(DecodedResponse::Unknown { id, body: response });
+    }
+
+    let result = contract.decode_output(response, internal)?;
+
+    let output = Detokenizer::detokenize(&result.tokens)?;
+
+    Ok(DecodedResponse::Decoded(DecodedMessage {
+        function_name: result.function_name,
+        params: output,
+        header: None,
+    }))
+}
+
+/// Decodes output parameters returned by some function call from `Cell`. Returns parametes and
+/// function name
+pub fn decode_unknown_function_response_from_cell(
+    abi: &str,
+    response: &Cell,
+    internal: bool,
+) -> AbiResult<DecodedMessage> {
+    decode_unknown_function_response(abi, SliceData::load_cell(response.clone())?, internal)
+}
+
 /// Decodes output parameters returned by some function call. Returns parametes and function name
 pub fn decode_unknown_function_call(
     abi: &str,
     response: SliceData,
     internal: bool,
     allow_partial: bool,
-) -> Result<DecodedMessage> {
+) -> AbiResult<DecodedMessage> {
     let contract = Contract::load(abi.as_bytes())?;
 
     let result = contract.decode_input(response, internal, allow_partial)?;
@@ -156,11 +559,104 @@ pub fn decode_unknown_function_call(
     Ok(DecodedMessage {
         function_name: result.function_name,
         params: input,
+        header: None,
     })
 }
 
-/// Changes initial values for public contract variables
-pub fn update_contract_data(abi: &str, parameters: &str, data: SliceData) -> Result<SliceData> {
+/// Decodes function call falling back to events if body doesn't match any function input.
+/// Returns parameters, function or event name and kind of matched body
+pub fn decode_unknown_function_call_or_event(
+    abi: &str,
+    call: SliceData,
+    internal: bool,
+    allow_partial: bool,
+) -> AbiResult<(MessageBodyKind, DecodedMessage)> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    let (kind, result) = contract.decode_input_or_event(call, internal, allow_partial)?;
+
+    Ok((kind, DecodedMessage {
+        function_name: result.function_name,
+        params: Detokenizer::detokenize(&result.tokens)?,
+        header: None,
+    }))
+}
+
+/// Decodes function call. Returns parameters, function name and header values
+pub fn decode_unknown_function_call_with_header(
+    abi: &str,
+    call: SliceData,
+    internal: bool,
+    allow_partial: bool,
+) -> AbiResult<DecodedMessage> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    let (result, header) = contract.decode_input_with_header(call, internal, allow_partial)?;
+
+    Ok(DecodedMessage {
+        function_name: result.function_name,
+        params: Detokenizer::detokenize(&result.tokens)?,
+        header: Some(Detokenizer::detokenize(&header)?),
+    })
+}
+
+/// Decodes header of function call without decoding its parameters. Returns JSON object with
+/// `header` values, `function_id` and name of matched function (`null` if there is no such
+/// function in ABI)
+pub fn decode_header_to_json_value(abi: &str, call: SliceData, internal: bool) -> AbiResult<Value> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    let (header, id, _) = Function::decode_header(&contract.abi_version, call, &contract.header, internal)?;
+    let function_name = contract.function_by_id(id, true).ok().map(|function| function.name.clone());
+
+    Ok(serde_json::json!({
+        "header": Detokenizer::detokenize_to_json_value(&header)?,
+        "function_id": format!("0x{:08x}", id),
+        "function_name": function_name,
+    }))
+}
+
+/// Decodes header of function call without decoding its parameters. Returns JSON string with
+/// `header` values, `function_id` and name of matched function
+pub fn decode_header(abi: &str, call: SliceData, internal: bool) -> AbiResult<String> {
+    Ok(decode_header_to_json_value(abi, call, internal)?.to_string())
+}
+
+/// Decodes call of given `function`. Returns parameters, function name and header values
+pub fn decode_function_call_with_header(
+    abi: &str,
+    function: &str,
+    call: SliceData,
+    internal: bool,
+    allow_partial: bool,
+) -> AbiResult<DecodedMessage> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    let function = contract.function(function)?;
+
+    let (header, tokens) = function.decode_input_with_header(call, internal, allow_partial)?;
+
+    Ok(DecodedMessage {
+        function_name: function.name.clone(),
+        params: Detokenizer::detokenize(&tokens)?,
+        header: Some(Detokenizer::detokenize(&header)?),
+    })
+}
+
+/// Decodes function call from `Cell`. Returns parametes and function name
+pub fn decode_unknown_function_call_from_cell(
+    abi: &str,
+    call: &Cell,
+    internal: bool,
+    allow_partial: bool,
+) -> AbiResult<DecodedMessage> {
+    decode_unknown_function_call(abi, SliceData::load_cell(call.clone())?, internal, allow_partial)
+}
+
+/// Changes initial values for public contract variables. All data items declared in ABI
+/// should be provided (except ones with default values), use `merge_contract_data` to change
+/// only some of them
+pub fn update_contract_data(abi: &str, parameters: &str, data: SliceData) -> AbiResult<SliceData> {
     let contract = Contract::load(abi.as_bytes())?;
 
     let data_json: serde_json::Value = serde_json::from_str(parameters)?;
@@ -171,31 +667,211 @@ pub fn update_contract_data(abi: &str, parameters: &str, data: SliceData) -> Res
         .map(|item| item.value.clone())
         .collect();
 
-    let tokens = Tokenizer::tokenize_all_params(&params[..], &data_json)?;
+    let attributes = contract.params_attributes("data", &params);
+    let tokens = Tokenizer::tokenize_all_params_with_defaults(&params[..], &attributes, &data_json)?;
+
+    Ok(contract.update_data(data, &tokens)?)
+}
+
+/// Changes initial values of data items present in `parameters` JSON object preserving values
+/// of other items. Data item with `null` value is removed from contract data
+pub fn merge_contract_data(abi: &str, parameters: &str, data: SliceData) -> AbiResult<SliceData> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    let data_json: serde_json::Value = serde_json::from_str(parameters)?;
+    let data_json = data_json.as_object().ok_or_else(|| AbiError::InvalidInputData {
+        msg: "Contract data should be a JSON object".to_owned(),
+    })?;
+
+    let mut data = data;
+    let mut tokens = vec![];
+    for (name, value) in data_json {
+        if value.is_null() {
+            data = contract.remove_data_item(data, name)?;
+            continue;
+        }
+        let kind = &contract.data.get(name).ok_or_else(|| AbiError::InvalidData {
+            msg: format!("data item {} not found in contract ABI", name),
+        })?.value.kind;
+        tokens.push(Token::new(name, Tokenizer::tokenize_parameter(kind, value, name)?));
+    }
+
+    Ok(contract.update_data(data, &tokens)?)
+}
+
+/// Builds initial data dictionary from data items values `parameters` and public key
+pub fn encode_contract_data(abi: &str, parameters: &str, pubkey: Option<&PublicKeyData>) -> AbiResult<Cell> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    let data_json: serde_json::Value = serde_json::from_str(parameters)?;
+
+    let params: Vec<_> = contract
+        .data_in_order()
+        .into_iter()
+        .map(|item| item.value.clone())
+        .collect();
+
+    let attributes = contract.params_attributes("data", &params);
+    let tokens = Tokenizer::tokenize_all_params_with_defaults(&params[..], &attributes, &data_json)?;
+
+    Ok(contract.encode_data(&tokens, pubkey)?)
+}
+
+/// Decodes value of single data item `name` from contract data. Returns JSON object
+/// `{ "<name>": <value> }` or `None` if data has no value for the item
+pub fn get_contract_data_item(abi: &str, data: SliceData, name: &str) -> AbiResult<Option<String>> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    contract
+        .get_data_item(data, name)?
+        .map(|value| Detokenizer::detokenize(&[Token::new(name, value)]))
+        .transpose()
+        .map_err(AbiError::from)
+}
+
+/// Sets value of single data item `name` in contract data. `value` is JSON representation
+/// of the item value
+pub fn set_contract_data_item(abi: &str, data: SliceData, name: &str, value: &str) -> AbiResult<SliceData> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    let kind = &contract.data.get(name).ok_or_else(|| AbiError::InvalidData {
+        msg: format!("data item {} not found in contract ABI", name),
+    })?.value.kind;
+    let value_json: serde_json::Value = serde_json::from_str(value)?;
+    let value = Tokenizer::tokenize_parameter(kind, &value_json, name)?;
+
+    Ok(contract.set_data_item(data, name, value)?)
+}
+
+/// Returns id of call message of contract function with provided name
+pub fn get_function_id(abi: &str, function: &str) -> AbiResult<u32> {
+    let contract = Contract::load(abi.as_bytes())?;
 
-    contract.update_data(data, &tokens)
+    Ok(contract.function(function)?.get_input_id())
 }
 
+/// Returns id of contract event with provided name
+pub fn get_event_id(abi: &str, event: &str) -> AbiResult<u32> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    Ok(contract.event(event)?.get_id())
+}
+
+/// Returns signature of contract function with provided name used for id calculation
+pub fn get_function_signature(abi: &str, function: &str) -> AbiResult<String> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    Ok(contract.function(function)?.get_function_signature())
+}
+
+/// Gets public key from contract data using data layout of contract ABI version
+pub fn get_contract_pubkey(abi: &str, data: SliceData) -> AbiResult<Option<PublicKeyData>> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    Ok(contract.read_pubkey(&data)?)
+}
+
+/// Sets public key into contract data using data layout of contract ABI version
+pub fn set_contract_pubkey(abi: &str, data: SliceData, pubkey: &PublicKeyData) -> AbiResult<SliceData> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    Ok(contract.update_pubkey(data, pubkey)?)
+}
 
 /// Decode initial values of public contract variables
-pub fn decode_contract_data(abi: &str, data: SliceData) -> Result<String> {
+pub fn decode_contract_data(abi: &str, data: SliceData) -> AbiResult<String> {
+    Ok(Detokenizer::detokenize(&decode_contract_data_tokens(abi, data)?)?)
+}
+
+/// Decode initial values of public contract variables into tokens
+pub fn decode_contract_data_tokens(abi: &str, data: SliceData) -> AbiResult<Vec<Token>> {
     let contract = Contract::load(abi.as_bytes())?;
 
-    Detokenizer::detokenize(&contract.decode_init_data(data)?)
+    Ok(contract.decode_init_data(data)?)
+}
+
+/// Decode initial values of public contract variables from `Cell`
+pub fn decode_contract_data_from_cell(abi: &str, data: &Cell) -> AbiResult<String> {
+    decode_contract_data(abi, SliceData::load_cell(data.clone())?)
 }
 
 /// Decode account storage fields
-pub fn decode_storage_fields(abi: &str, data: SliceData, allow_partial: bool) -> Result<String> {
+pub fn decode_storage_fields(abi: &str, data: SliceData, allow_partial: bool) -> AbiResult<String> {
+    Ok(Detokenizer::detokenize(&decode_storage_fields_tokens(abi, data, allow_partial)?)?)
+}
+
+/// Decode account storage fields into tokens
+pub fn decode_storage_fields_tokens(abi: &str, data: SliceData, allow_partial: bool) -> AbiResult<Vec<Token>> {
     let contract = Contract::load(abi.as_bytes())?;
 
-    let decoded = contract.decode_storage_fields(data, allow_partial)?;
+    Ok(contract.decode_storage_fields(data, allow_partial)?)
+}
+
+/// Decode account storage fields into JSON string. Ignored trailing data and deprecated field
+/// types are reported into `warnings`
+pub fn decode_storage_fields_with_warnings(
+    abi: &str,
+    data: SliceData,
+    allow_partial: bool,
+    warnings: &mut Warnings,
+) -> AbiResult<String> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    let tokens = contract.decode_storage_fields_with_warnings(data, allow_partial, warnings)?;
+
+    Ok(Detokenizer::detokenize(&tokens)?)
+}
+
+/// Decode account storage fields from `Cell`
+pub fn decode_storage_fields_from_cell(abi: &str, data: &Cell, allow_partial: bool) -> AbiResult<String> {
+    decode_storage_fields(abi, SliceData::load_cell(data.clone())?, allow_partial)
+}
+
+/// Decode only account storage fields with provided names skipping the rest of storage
+pub fn decode_selected_storage_fields(abi: &str, data: SliceData, names: &[&str]) -> AbiResult<String> {
+    Ok(Detokenizer::detokenize(&decode_selected_storage_fields_tokens(abi, data, names)?)?)
+}
+
+/// Decode only account storage fields with provided names into tokens
+pub fn decode_selected_storage_fields_tokens(
+    abi: &str,
+    data: SliceData,
+    names: &[&str],
+) -> AbiResult<Vec<Token>> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    Ok(contract.decode_selected_storage_fields(data, names)?)
+}
+
+/// Constructs `StateInit` with provided `code` and data built from initial values and public key.
+/// Initial values are data items for ABI versions before 2.4 and `init` storage fields since 2.4
+pub fn encode_state_init(
+    abi: &str,
+    code: Cell,
+    init_values: Option<&str>,
+    pubkey: Option<&PublicKeyData>,
+) -> AbiResult<StateInit> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    let init_values = if let Some(init_values) = init_values {
+        let v: Value =
+            serde_json::from_str(init_values).map_err(|err| AbiError::SerdeError { err })?;
+        if contract.data_map_supported() {
+            let params: Vec<_> = contract.data.values().map(|item| item.value.clone()).collect();
+            Tokenizer::tokenize_optional_params(&params, &v)?
+        } else {
+            Tokenizer::tokenize_optional_params(&contract.fields, &v)?
+        }
+    } else {
+        HashMap::new()
+    };
 
-    Detokenizer::detokenize(&decoded)
+    Ok(contract.encode_state_init(code, init_values, pubkey)?)
 }
 
 /// Encodes `parameters` for given `function` of contract described by `abi` into `BuilderData`
 /// which can be used as message body for calling contract
-pub fn encode_storage_fields(abi: &str, init_fields: Option<&str>) -> Result<BuilderData> {
+pub fn encode_storage_fields(abi: &str, init_fields: Option<&str>) -> AbiResult<BuilderData> {
     let contract = Contract::load(abi.as_bytes())?;
 
     let init_fields = if let Some(init_fields) = init_fields {
@@ -206,7 +882,7 @@ pub fn encode_storage_fields(abi: &str, init_fields: Option<&str>) -> Result<Bui
         HashMap::new()
     };
 
-    contract.encode_storage_fields(init_fields)
+    Ok(contract.encode_storage_fields(init_fields)?)
 }
 
 
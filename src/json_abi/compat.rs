@@ -0,0 +1,117 @@
+/*
+* Copyright 2018-2020 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! `json_abi` functions returning `ton_types::Result` as before typed `AbiError` results
+//! were introduced. Kept for one release cycle, switch to functions of `json_abi` module.
+
+use crate::json_abi::DecodedMessage;
+use ed25519_dalek::Keypair;
+
+use ton_types::{BuilderData, Result, SliceData};
+
+#[deprecated(note = "use `json_abi::encode_function_call` returning `AbiResult`")]
+pub fn encode_function_call(
+    abi: &str,
+    function: &str,
+    header: Option<&str>,
+    parameters: &str,
+    internal: bool,
+    pair: Option<(&Keypair, Option<i32>)>,
+    address: Option<String>,
+) -> Result<BuilderData> {
+    Ok(super::encode_function_call(abi, function, header, parameters, internal, pair, address)?)
+}
+
+#[deprecated(note = "use `json_abi::prepare_function_call_for_sign` returning `AbiResult`")]
+pub fn prepare_function_call_for_sign(
+    abi: &str,
+    function: &str,
+    header: Option<&str>,
+    parameters: &str,
+    address: Option<String>,
+) -> Result<(BuilderData, ton_types::UInt256)> {
+    Ok(super::prepare_function_call_for_sign(abi, function, header, parameters, address)?)
+}
+
+#[deprecated(note = "use `json_abi::add_sign_to_function_call` returning `AbiResult`")]
+pub fn add_sign_to_function_call(
+    abi: &str,
+    signature: &[u8],
+    public_key: Option<&[u8]>,
+    function_call: SliceData,
+) -> Result<BuilderData> {
+    Ok(super::add_sign_to_function_call(abi, signature, public_key, function_call)?)
+}
+
+#[deprecated(note = "use `json_abi::decode_function_response` returning `AbiResult`")]
+pub fn decode_function_response(
+    abi: &str,
+    function: &str,
+    response: SliceData,
+    internal: bool,
+) -> Result<String> {
+    Ok(super::decode_function_response(abi, function, response, internal)?)
+}
+
+#[deprecated(note = "use `json_abi::decode_unknown_function_response` returning `AbiResult`")]
+pub fn decode_unknown_function_response(
+    abi: &str,
+    response: SliceData,
+    internal: bool,
+) -> Result<DecodedMessage> {
+    Ok(super::decode_unknown_function_response(abi, response, internal)?)
+}
+
+#[deprecated(note = "use `json_abi::decode_unknown_function_call` returning `AbiResult`")]
+pub fn decode_unknown_function_call(
+    abi: &str,
+    response: SliceData,
+    internal: bool,
+    allow_partial: bool,
+) -> Result<DecodedMessage> {
+    Ok(super::decode_unknown_function_call(abi, response, internal, allow_partial)?)
+}
+
+#[deprecated(note = "use `json_abi::update_contract_data` returning `AbiResult`")]
+pub fn update_contract_data(
+    abi: &str,
+    parameters: &str,
+    data: SliceData,
+) -> Result<SliceData> {
+    Ok(super::update_contract_data(abi, parameters, data)?)
+}
+
+#[deprecated(note = "use `json_abi::decode_contract_data` returning `AbiResult`")]
+pub fn decode_contract_data(
+    abi: &str,
+    data: SliceData,
+) -> Result<String> {
+    Ok(super::decode_contract_data(abi, data)?)
+}
+
+#[deprecated(note = "use `json_abi::decode_storage_fields` returning `AbiResult`")]
+pub fn decode_storage_fields(
+    abi: &str,
+    data: SliceData,
+    allow_partial: bool,
+) -> Result<String> {
+    Ok(super::decode_storage_fields(abi, data, allow_partial)?)
+}
+
+#[deprecated(note = "use `json_abi::encode_storage_fields` returning `AbiResult`")]
+pub fn encode_storage_fields(
+    abi: &str,
+    init_fields: Option<&str>,
+) -> Result<BuilderData> {
+    Ok(super::encode_storage_fields(abi, init_fields)?)
+}
@@ -0,0 +1,166 @@
+/*
+* Copyright 2018-2020 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Positions of JSON elements in source text, used to report where invalid ABI elements
+//! are located. Source text is expected to be valid JSON.
+
+struct Scanner<'a> {
+    text: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\r' | b'\n')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        self.skip_whitespace();
+        if self.peek()? != byte {
+            return None;
+        }
+        self.pos += 1;
+        Some(())
+    }
+
+    fn skip_string(&mut self) -> Option<()> {
+        self.pos += 1;
+        loop {
+            match self.peek()? {
+                b'\\' => self.pos += 2,
+                b'"' => {
+                    self.pos += 1;
+                    return Some(());
+                }
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    fn read_key(&mut self) -> Option<String> {
+        self.skip_whitespace();
+        let start = self.pos;
+        self.skip_string()?;
+        serde_json::from_slice(&self.text[start..self.pos]).ok()
+    }
+
+    fn skip_value(&mut self) -> Option<()> {
+        self.skip_whitespace();
+        match self.peek()? {
+            b'"' => self.skip_string(),
+            b'{' | b'[' => {
+                let mut depth = 0usize;
+                loop {
+                    match self.peek()? {
+                        b'"' => {
+                            self.skip_string()?;
+                            continue;
+                        }
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => depth -= 1,
+                        _ => {}
+                    }
+                    self.pos += 1;
+                    if depth == 0 {
+                        return Some(());
+                    }
+                }
+            }
+            _ => {
+                while !matches!(self.peek(), None | Some(b',' | b'}' | b']' | b' ' | b'\t' | b'\r' | b'\n')) {
+                    self.pos += 1;
+                }
+                Some(())
+            }
+        }
+    }
+
+    /// Moves to the start of object member or array item referenced by pointer `token`
+    fn enter(&mut self, token: &str) -> Option<()> {
+        self.skip_whitespace();
+        match self.peek()? {
+            b'{' => {
+                self.pos += 1;
+                loop {
+                    self.skip_whitespace();
+                    if self.peek()? == b'}' {
+                        return None;
+                    }
+                    let key = self.read_key()?;
+                    self.expect(b':')?;
+                    self.skip_whitespace();
+                    if key == token {
+                        return Some(());
+                    }
+                    self.skip_value()?;
+                    self.skip_whitespace();
+                    if self.peek()? == b',' {
+                        self.pos += 1;
+                    }
+                }
+            }
+            b'[' => {
+                let index: usize = token.parse().ok()?;
+                self.pos += 1;
+                for _ in 0..index {
+                    self.skip_value()?;
+                    self.expect(b',')?;
+                }
+                self.skip_whitespace();
+                if self.peek()? == b']' {
+                    return None;
+                }
+                Some(())
+            }
+            _ => None,
+        }
+    }
+
+    fn line_column(&self, pos: usize) -> (usize, usize) {
+        let pos = pos.min(self.text.len());
+        let before = &self.text[..pos];
+        let line = before.iter().filter(|&&byte| byte == b'\n').count() + 1;
+        let line_start = before.iter().rposition(|&byte| byte == b'\n').map_or(0, |i| i + 1);
+        (line, pos - line_start + 1)
+    }
+}
+
+/// Returns 1-based line and column of the element referenced by JSON `pointer`. If some
+/// part of the pointer is absent in `text`, position of the deepest present element is
+/// returned. Errors of the root element itself, like missing fields, are located at its end.
+pub(crate) fn element_position(text: &[u8], pointer: &str) -> (usize, usize) {
+    let mut scanner = Scanner { text, pos: 0 };
+    scanner.skip_whitespace();
+    if pointer.is_empty() {
+        let start = scanner.pos;
+        if scanner.skip_value().is_some() && scanner.pos > start {
+            return scanner.line_column(scanner.pos - 1);
+        }
+        return scanner.line_column(start);
+    }
+    let mut found = scanner.pos;
+    for token in pointer.split('/').skip(1) {
+        let token = token.replace("~1", "/").replace("~0", "~");
+        if scanner.enter(&token).is_none() {
+            break;
+        }
+        found = scanner.pos;
+    }
+    scanner.line_column(found)
+}
@@ -12,6 +12,7 @@
 */
 
 extern crate sha2;
+extern crate hmac;
 extern crate num_bigint;
 extern crate hex;
 extern crate ton_block;
@@ -21,27 +22,39 @@ extern crate serde_json;
 extern crate ed25519;
 extern crate ed25519_dalek;
 extern crate base64;
+#[cfg(feature = "gzip")]
+extern crate flate2;
 extern crate num_traits;
 
 pub mod contract;
 pub mod function;
 pub mod event;
+pub mod getter;
 pub mod int;
 pub mod param;
 pub mod param_type;
 pub mod token;
 pub mod json_abi;
 pub mod error;
+pub mod diff;
+pub mod validation;
+pub mod registry;
+pub mod summary;
+pub mod lint;
+pub mod typedefs;
+pub mod warnings;
 
+mod json_span;
 mod signature;
 
 pub use param_type::ParamType;
-pub use contract::{Contract, DataItem};
+pub use contract::{Contract, DataItem, LoadOptions};
 pub use token::{Token, MapKeyTokenValue, TokenValue};
 pub use function::Function;
 pub use event::Event;
+pub use getter::Getter;
 pub use json_abi::*;
-pub use param::Param;
+pub use param::{AttributedParam, Param, ParamAttributes};
 pub use int::{Int, Uint};
 pub use error::*;
 
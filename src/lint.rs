@@ -0,0 +1,194 @@
+/*
+* Copyright 2018-2020 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Contract ABI linting: non-fatal findings which don't prevent ABI usage.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use crate::contract::Contract;
+use crate::param::{Param, ParamAttributes};
+use crate::param_type::ParamType;
+
+/// Param name prefixes reserved for compiler generated names
+pub const RESERVED_PREFIXES: &[&str] = &["__"];
+
+/// Non-fatal problem found in contract ABI
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum LintFinding {
+    /// Param name starts with reserved prefix
+    ReservedPrefix { location: String, prefix: String },
+    /// Param has empty name
+    EmptyName { location: String },
+    /// Tuple has single component and can be replaced with it
+    SingleMemberTuple { location: String },
+    /// Fixed array has zero size
+    EmptyFixedArray { location: String },
+    /// Components declared in `structs` section are not used by any param
+    UnusedComponents { location: String },
+    /// Several functions have identical inputs and outputs types
+    IdenticalFunctionSignatures { signature: String, names: Vec<String> },
+    /// Several events have identical inputs types
+    IdenticalEventSignatures { signature: String, names: Vec<String> },
+}
+
+impl LintFinding {
+    /// Returns stable finding code suitable for machine processing
+    pub fn code(&self) -> &'static str {
+        match self {
+            LintFinding::ReservedPrefix { .. } => "reserved-prefix",
+            LintFinding::EmptyName { .. } => "empty-name",
+            LintFinding::SingleMemberTuple { .. } => "single-member-tuple",
+            LintFinding::EmptyFixedArray { .. } => "empty-fixed-array",
+            LintFinding::UnusedComponents { .. } => "unused-components",
+            LintFinding::IdenticalFunctionSignatures { .. } => "identical-function-signatures",
+            LintFinding::IdenticalEventSignatures { .. } => "identical-event-signatures",
+        }
+    }
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}] ", self.code())?;
+        match self {
+            LintFinding::ReservedPrefix { location, prefix } =>
+                write!(f, "{} name starts with reserved prefix {}", location, prefix),
+            LintFinding::EmptyName { location } =>
+                write!(f, "{} has empty name", location),
+            LintFinding::SingleMemberTuple { location } =>
+                write!(f, "Tuple {} has single component", location),
+            LintFinding::EmptyFixedArray { location } =>
+                write!(f, "Fixed array {} has zero size", location),
+            LintFinding::UnusedComponents { location } =>
+                write!(f, "Components of {} are not used", location),
+            LintFinding::IdenticalFunctionSignatures { signature, names } =>
+                write!(f, "Functions {} have identical signature {}", names.join(", "), signature),
+            LintFinding::IdenticalEventSignatures { signature, names } =>
+                write!(f, "Events {} have identical signature {}", names.join(", "), signature),
+        }
+    }
+}
+
+fn types(params: &[Param]) -> String {
+    params.iter().map(|param| param.kind.type_signature()).collect::<Vec<_>>().join(",")
+}
+
+fn lint_params(location: &str, params: &[Param], findings: &mut Vec<LintFinding>) {
+    for (i, param) in params.iter().enumerate() {
+        let location = if param.name.is_empty() {
+            format!("{} #{}", location, i)
+        } else {
+            format!("{} {}", location, param.name)
+        };
+        if param.name.is_empty() {
+            findings.push(LintFinding::EmptyName { location: location.clone() });
+        }
+        if let Some(prefix) = RESERVED_PREFIXES.iter().find(|prefix| param.name.starts_with(*prefix)) {
+            findings.push(LintFinding::ReservedPrefix { location: location.clone(), prefix: prefix.to_string() });
+        }
+        lint_type(&location, &param.kind, findings);
+    }
+}
+
+fn lint_type(location: &str, kind: &ParamType, findings: &mut Vec<LintFinding>) {
+    match kind {
+        ParamType::Tuple(components) => {
+            if components.len() == 1 {
+                findings.push(LintFinding::SingleMemberTuple { location: location.to_owned() });
+            }
+            lint_params(location, components, findings);
+        }
+        ParamType::FixedArray(kind, size) => {
+            if *size == 0 {
+                findings.push(LintFinding::EmptyFixedArray { location: location.to_owned() });
+            }
+            lint_type(location, kind, findings);
+        }
+        ParamType::Array(kind) | ParamType::Optional(kind) | ParamType::Ref(kind) =>
+            lint_type(location, kind, findings),
+        ParamType::Map(key, value) => {
+            lint_type(location, key, findings);
+            lint_type(location, value, findings);
+        }
+        _ => {}
+    }
+}
+
+fn collect_struct_names<'a>(attributes: &'a ParamAttributes, names: &mut HashSet<&'a str>) {
+    if let Some(name) = &attributes.struct_name {
+        names.insert(name);
+    }
+    for component in &attributes.components {
+        collect_struct_names(component, names);
+    }
+}
+
+impl Contract {
+    /// Checks ABI for non-fatal problems. Findings are listed in ABI declaration order.
+    pub fn lint(&self) -> Vec<LintFinding> {
+        let mut findings = vec![];
+
+        lint_params("header", &self.header, &mut findings);
+        let mut function_signatures = BTreeMap::<String, Vec<String>>::new();
+        for function in self.functions_in_order() {
+            lint_params(&format!("function {} inputs", function.name), &function.inputs, &mut findings);
+            lint_params(&format!("function {} outputs", function.name), &function.outputs, &mut findings);
+            function_signatures
+                .entry(format!("({})({})", types(&function.inputs), types(&function.outputs)))
+                .or_default()
+                .push(function.name.clone());
+        }
+        for getter in self.getters_in_order() {
+            lint_params(&format!("getter {} inputs", getter.name), &getter.inputs, &mut findings);
+            lint_params(&format!("getter {} outputs", getter.name), &getter.outputs, &mut findings);
+        }
+        let mut event_signatures = BTreeMap::<String, Vec<String>>::new();
+        for event in self.events_in_order() {
+            lint_params(&format!("event {}", event.name), &event.inputs, &mut findings);
+            event_signatures
+                .entry(format!("({})", types(&event.inputs)))
+                .or_default()
+                .push(event.name.clone());
+        }
+        let data = self.data_in_order().into_iter().map(|item| item.value.clone()).collect::<Vec<_>>();
+        lint_params("data", &data, &mut findings);
+        lint_params("fields", &self.fields, &mut findings);
+
+        let mut used_structs = HashSet::new();
+        for attributes in self.param_attributes.values() {
+            collect_struct_names(attributes, &mut used_structs);
+        }
+        for item in &self.structs {
+            if !used_structs.contains(item.name.as_str()) {
+                findings.push(LintFinding::UnusedComponents { location: format!("struct {}", item.name) });
+            }
+        }
+
+        for (signature, names) in function_signatures {
+            if names.len() > 1 {
+                findings.push(LintFinding::IdenticalFunctionSignatures { signature, names });
+            }
+        }
+        for (signature, names) in event_signatures {
+            if names.len() > 1 {
+                findings.push(LintFinding::IdenticalEventSignatures { signature, names });
+            }
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_lint.rs"]
+mod tests;
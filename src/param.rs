@@ -13,8 +13,12 @@
 
 //! Function param.
 use crate::param_type::{ParamType, read_type};
+use crate::typedefs::STRUCT_FIELD;
 use serde::de::{Deserializer, Error};
-use serde::Deserialize;
+use serde::ser::{SerializeMap, Serializer};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
 
 
 /// Function param.
@@ -26,6 +30,43 @@ pub struct Param {
     pub kind: ParamType,
 }
 
+/// Optional param attributes declared in ABI JSON which don't affect param encoding.
+/// Attributes are kept by `Contract` apart from params, see `Contract::param_attributes`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ParamAttributes {
+    /// Value used by tokenizer if param value is not provided.
+    pub default: Option<Value>,
+    /// Extra JSON attributes not used by ABI itself, e.g. `desc` or `units`.
+    pub metadata: BTreeMap<String, Value>,
+    /// Name of struct from `structs` section describing tuple components of the param.
+    pub struct_name: Option<String>,
+    /// Attributes of tuple components of the param.
+    pub components: Vec<ParamAttributes>,
+}
+
+pub(crate) static NO_ATTRIBUTES: ParamAttributes = ParamAttributes {
+    default: None,
+    metadata: BTreeMap::new(),
+    struct_name: None,
+    components: Vec::new(),
+};
+
+impl ParamAttributes {
+    /// Returns `true` if neither param nor its components have any attributes
+    pub fn is_empty(&self) -> bool {
+        self.default.is_none()
+            && self.metadata.is_empty()
+            && self.struct_name.is_none()
+            && self.components.iter().all(ParamAttributes::is_empty)
+    }
+
+    /// Returns attributes of tuple component with `index` or empty attributes if there are none
+    pub fn component(&self, index: usize) -> &ParamAttributes {
+        self.components.get(index).unwrap_or(&NO_ATTRIBUTES)
+    }
+}
+
 impl Param {
     pub fn new(name: &str, kind: ParamType) -> Self {
         Self {
@@ -34,6 +75,11 @@ impl Param {
         }
     }
 
+    /// Returns serializable view of param which writes provided attributes into ABI JSON
+    pub fn with_attributes<'a>(&'a self, attributes: &'a ParamAttributes) -> AttributedParam<'a> {
+        AttributedParam { param: self, attributes }
+    }
+
     pub(crate) fn from_serde(serde_param: SerdeParam) -> Result<Self, String> {
         let mut result = Self {
             name: serde_param.name,
@@ -44,6 +90,10 @@ impl Param {
             .kind
             .set_components(serde_param.components)
             .map_err(|err| err.to_string())?;
+        result
+            .kind
+            .set_variants(serde_param.variants)
+            .map_err(|err| err.to_string())?;
 
         Ok(result)
     }
@@ -59,6 +109,9 @@ pub(crate) struct SerdeParam {
     /// Tuple components
     #[serde(default)]
     pub components: Vec<Param>,
+    /// Enum variants
+    #[serde(default)]
+    pub variants: Vec<String>,
     /// `init` flag for fields section
     #[serde(default)]
     pub init: bool,
@@ -78,6 +131,7 @@ impl<'a> Deserialize<'a> for Param {
                     .map_err(D::Error::custom)?;
                 match param_type {
                     ParamType::Tuple(_) |
+                    ParamType::Enum(_) |
                     ParamType::Array(_) |
                     ParamType::FixedArray(_, _) |
                     ParamType::Map(_, _) =>
@@ -86,26 +140,93 @@ impl<'a> Deserialize<'a> for Param {
                                     type_str))),
                     _ => {}
                 }
-                Ok(Self {
-                    name: type_str,
-                    kind: param_type
-                })
+                Ok(Self::new(&type_str, param_type))
             }
             value => {
                 let serde_param: SerdeParam = serde_json::from_value(value).map_err(D::Error::custom)?;
+                Self::from_serde(serde_param).map_err(D::Error::custom)
+            }
+        }
+    }
+}
 
-                let mut result = Self {
-                    name: serde_param.name,
-                    kind: serde_param.kind,
-                };
+/// Param fields written into ABI JSON as is and not kept as metadata
+const PARAM_FIELDS: [&str; 5] = ["name", "type", "components", "variants", "init"];
 
-                result.kind
-                    .set_components(serde_param.components)
-                    .map_err(D::Error::custom)?;
+#[derive(serde::Deserialize)]
+struct SerdeAttributes {
+    #[serde(default)]
+    default: Option<Value>,
+    #[serde(default, rename = "struct")]
+    struct_name: Option<String>,
+    #[serde(default)]
+    components: Vec<ParamAttributes>,
+    #[serde(flatten)]
+    metadata: BTreeMap<String, Value>,
+}
 
-                Ok(result)
+impl<'a> Deserialize<'a> for ParamAttributes {
+    /// Reads attributes from param description in ABI JSON. Params written as type strings
+    /// have no attributes
+    fn deserialize<D>(deserializer: D) -> Result<ParamAttributes, D::Error> where D: Deserializer<'a> {
+        match Value::deserialize(deserializer)? {
+            value @ Value::Object(_) => {
+                let mut serde_attributes: SerdeAttributes = serde_json::from_value(value)
+                    .map_err(D::Error::custom)?;
+                serde_attributes.metadata.retain(|key, _| !PARAM_FIELDS.contains(&key.as_str()));
+                Ok(ParamAttributes {
+                    default: serde_attributes.default,
+                    metadata: serde_attributes.metadata,
+                    struct_name: serde_attributes.struct_name,
+                    components: serde_attributes.components,
+                })
             }
+            _ => Ok(ParamAttributes::default()),
+        }
+    }
+}
+
+impl Serialize for Param {
+    /// Serializes param into ABI JSON form. Tuple components and enum variants are written
+    /// into `components` and `variants` fields
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        self.with_attributes(&NO_ATTRIBUTES).serialize(serializer)
+    }
+}
+
+/// Param with attributes serialized into ABI JSON form, see `Param::with_attributes`
+#[derive(Clone, Copy, Debug)]
+pub struct AttributedParam<'a> {
+    param: &'a Param,
+    attributes: &'a ParamAttributes,
+}
+
+impl Serialize for AttributedParam<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("name", &self.param.name)?;
+        map.serialize_entry("type", &self.param.kind.abi_type_name())?;
+        if let Some(components) = self.param.kind.tuple_components() {
+            let components = components
+                .iter()
+                .enumerate()
+                .map(|(i, component)| component.with_attributes(self.attributes.component(i)))
+                .collect::<Vec<_>>();
+            map.serialize_entry("components", &components)?;
+        }
+        if let Some(struct_name) = &self.attributes.struct_name {
+            map.serialize_entry(STRUCT_FIELD, struct_name)?;
+        }
+        if let Some(variants) = self.param.kind.enum_variants() {
+            map.serialize_entry("variants", variants)?;
+        }
+        if let Some(default) = &self.attributes.default {
+            map.serialize_entry("default", default)?;
+        }
+        for (key, value) in &self.attributes.metadata {
+            map.serialize_entry(key, value)?;
         }
+        map.end()
     }
 }
 
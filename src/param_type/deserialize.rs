@@ -11,11 +11,12 @@
 * limitations under the License.
 */
 
-use crate::{error::AbiError, param_type::ParamType};
+use crate::{error::AbiError, param_type::{ParamType, MAX_FIXED_DECIMALS}};
 use std::fmt;
+use std::str::FromStr;
 use serde::{Deserialize, Deserializer};
 use serde::de::{Error as SerdeError, Visitor};
-use ton_types::{fail, Result};
+use ton_types::{fail, BuilderData, Result};
 
 impl<'a> Deserialize<'a> for ParamType {
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error> where D: Deserializer<'a> {
@@ -23,6 +24,16 @@ impl<'a> Deserialize<'a> for ParamType {
     }
 }
 
+impl FromStr for ParamType {
+    type Err = anyhow::Error;
+
+    /// Parses type string, e.g. `map(uint32,tuple)`. Tuple components are left empty and can be
+    /// filled with `ParamType::set_components`
+    fn from_str(s: &str) -> Result<Self> {
+        read_type(s)
+    }
+}
+
 struct ParamTypeVisitor;
 
 impl<'a> Visitor<'a> for ParamTypeVisitor {
@@ -41,6 +52,27 @@ impl<'a> Visitor<'a> for ParamTypeVisitor {
     }
 }
 
+/// Type aliases accepted by `read_type` in place of canonical type names. Besides these
+/// `bytesN` is accepted as alias of `fixedbytesN`. Contract specific aliases are declared
+/// in `types` section of ABI JSON or provided in `LoadOptions::type_aliases`.
+pub const TYPE_ALIASES: &[(&str, &str)] = &[
+    ("coins", "varuint16"),
+    ("gram", "token"),
+    ("bitstring", "slice"),
+];
+
+fn resolve_alias(name: &str) -> Option<String> {
+    if let Some((_, canonical)) = TYPE_ALIASES.iter().find(|(alias, _)| *alias == name) {
+        return Some(canonical.to_string());
+    }
+    match name.strip_prefix("bytes") {
+        Some(len) if !len.is_empty() && len.chars().all(|c| c.is_ascii_digit()) => {
+            Some(format!("fixedbytes{}", len))
+        }
+        _ => None,
+    }
+}
+
 /// Converts string to param type.
 pub fn read_type(name: &str) -> Result<ParamType> {
     // check if it is a fixed or dynamic array.
@@ -70,11 +102,19 @@ pub fn read_type(name: &str) -> Result<ParamType> {
         }
     }
 
+    if let Some(canonical) = resolve_alias(name) {
+        // report type name as it is written in ABI rather than its canonical form
+        return read_type(&canonical)
+            .map_err(|_| AbiError::InvalidName { name: name.to_owned() }.into());
+    }
+
     let result = match name {
         "bool" => ParamType::Bool,
         // a little trick - here we only recognize parameter as a tuple and fill it
         // with parameters in `Param` type deserialization
         "tuple" => ParamType::Tuple(Vec::new()),
+        // the same for enum variants
+        "enum" => ParamType::Enum(Vec::new().into()),
         s if s.starts_with("int") => {
             let len = usize::from_str_radix(&s[3..], 10)
                 .map_err(|_| AbiError::InvalidName { name: name.to_owned() } )?;
@@ -122,17 +162,46 @@ pub fn read_type(name: &str) -> Result<ParamType> {
         "address_std" => {
             ParamType::AddressStd
         }
+        "address_var" => {
+            ParamType::AddressVar
+        }
+        "address_ext" => {
+            ParamType::AddressExt
+        }
         "token" => {
             ParamType::Token
         }
         "bytes" => {
             ParamType::Bytes
         }
+        "slice" => {
+            ParamType::Slice
+        }
         s if s.starts_with("fixedbytes") => {
             let len = usize::from_str_radix(&s[10..], 10)
                 .map_err(|_| AbiError::InvalidName { name: name.to_owned() } )?;
             ParamType::FixedBytes(len)
         }
+        s if s.starts_with("bits") => {
+            let len = usize::from_str_radix(&s[4..], 10)
+                .map_err(|_| AbiError::InvalidName { name: name.to_owned() } )?;
+            if len == 0 || len > BuilderData::bits_capacity() {
+                fail!(AbiError::InvalidName { name: name.to_owned() } );
+            }
+            ParamType::Bits(len)
+        }
+        s if s.starts_with("fixed") => {
+            let (bits, decimals) = s[5..].split_once('x')
+                .and_then(|(bits, decimals)| Some((
+                    usize::from_str_radix(bits, 10).ok()?,
+                    usize::from_str_radix(decimals, 10).ok()?,
+                )))
+                .ok_or_else(|| AbiError::InvalidName { name: name.to_owned() } )?;
+            if decimals > MAX_FIXED_DECIMALS {
+                fail!(AbiError::InvalidName { name: name.to_owned() } );
+            }
+            ParamType::FixedPoint { bits, decimals }
+        }
         "time" => {
             ParamType::Time
         }
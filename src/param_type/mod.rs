@@ -16,8 +16,11 @@
 mod deserialize;
 mod param_type;
 
-pub use self::param_type::ParamType;
-pub use self::deserialize::read_type;
+pub use self::param_type::{
+    ParamType, ENUM_MAX_VARIANTS, INT_SIZES, MAX_FIXED_DECIMALS, MAX_SLICE_BITS, SLICE_LEN_BITS, UINT_SIZES,
+    VAR_INT_SIZES,
+};
+pub use self::deserialize::{read_type, TYPE_ALIASES};
 
 #[cfg(test)]
 mod tests;
\ No newline at end of file
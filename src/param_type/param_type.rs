@@ -14,11 +14,29 @@
 //! Function and event param types.
 
 use std::fmt;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
 
-use crate::{AbiError, Param, contract::ABI_VERSION_2_0};
+use crate::{AbiError, Param, TokenValue, contract::ABI_VERSION_2_0};
 use crate::contract::{ABI_VERSION_1_0, ABI_VERSION_2_1, AbiVersion, ABI_VERSION_2_4};
 
-use ton_types::{Result, error};
+use ton_types::{Result, error, fail};
+
+/// Maximum number of enum variants which fit into `uint8` variant index
+pub const ENUM_MAX_VARIANTS: usize = 256;
+
+/// Bit sizes of `uint<N>` allowed by ABI specification
+pub const UINT_SIZES: RangeInclusive<usize> = 1..=256;
+/// Bit sizes of `int<N>` and `fixed<N>x<M>` allowed by ABI specification (TVM integer is 257 bits)
+pub const INT_SIZES: RangeInclusive<usize> = 1..=257;
+/// Byte sizes of `varint<N>` and `varuint<N>` allowed by ABI specification
+pub const VAR_INT_SIZES: RangeInclusive<usize> = 4..=32;
+/// Maximum number of decimal places of `fixed<N>x<M>` (the same as in Solidity)
+pub const MAX_FIXED_DECIMALS: usize = 80;
+/// Bit size of `slice` length prefix
+pub const SLICE_LEN_BITS: usize = 10;
+/// Maximum number of bits in `slice` value, so that value with length prefix fits into one cell
+pub const MAX_SLICE_BITS: usize = 1023 - SLICE_LEN_BITS;
 
 /// Function and event param types.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -31,8 +49,12 @@ pub enum ParamType {
     VarUint(usize),
     /// varint<M>: variable length integer type of maximum M bytes.
     VarInt(usize),
+    /// fixed<M>x<N>: signed fixed-point decimal number of M bits with N decimal places.
+    FixedPoint { bits: usize, decimals: usize },
     /// bool: boolean value.
     Bool,
+    /// enum: named variants encoded as `uint8` variant index.
+    Enum(Arc<[String]>),
     /// Tuple: several values combined into tuple.
     Tuple(Vec<Param>),
     /// T[]: dynamic array of elements of the type T.
@@ -47,10 +69,18 @@ pub enum ParamType {
     Address,
     /// std address
     AddressStd,
+    /// var address
+    AddressVar,
+    /// external address
+    AddressExt,
     /// byte array
     Bytes,
     /// fixed size byte array
     FixedBytes(usize),
+    /// bits<N>: N raw bits placed inline
+    Bits(usize),
+    /// slice: up to `MAX_SLICE_BITS` raw bits placed inline after `SLICE_LEN_BITS` length prefix
+    Slice,
     /// UTF8 string
     String,
     /// Nanograms
@@ -81,7 +111,9 @@ impl ParamType {
             ParamType::Int(size) => format!("int{}", size),
             ParamType::VarUint(size) => format!("varuint{}", size),
             ParamType::VarInt(size) => format!("varint{}", size),
+            ParamType::FixedPoint { bits, decimals } => format!("fixed{}x{}", bits, decimals),
             ParamType::Bool => "bool".to_owned(),
+            ParamType::Enum(_) => "uint8".to_owned(),
             ParamType::Tuple(params) => {
                 let mut signature = "".to_owned();
                 for param in params {
@@ -99,8 +131,12 @@ impl ParamType {
                 format!("map({},{})", key_type.type_signature(), value_type.type_signature()),
             ParamType::Address => "address".to_owned(),
             ParamType::AddressStd => "address_std".to_owned(),
+            ParamType::AddressVar => "address_var".to_owned(),
+            ParamType::AddressExt => "address_ext".to_owned(),
             ParamType::Bytes => "bytes".to_owned(),
             ParamType::FixedBytes(size) => format!("fixedbytes{}", size),
+            ParamType::Bits(size) => format!("bits{}", size),
+            ParamType::Slice => "slice".to_owned(),
             ParamType::String => "string".to_owned(),
             ParamType::Token => "gram".to_owned(),
             ParamType::Time => "time".to_owned(),
@@ -111,38 +147,172 @@ impl ParamType {
         }
     }
 
-    pub fn set_components(&mut self, components: Vec<Param>) -> Result<()> {
+    /// Returns type name as it is written in ABI JSON: tuples and enums are named `tuple` and
+    /// `enum` with their components and variants declared in separate param fields
+    pub fn abi_type_name(&self) -> String {
         match self {
-            ParamType::Tuple(params) => {
-                if components.is_empty() {
-                    Err(error!(AbiError::EmptyComponents))
-                } else {
+            ParamType::Tuple(_) => "tuple".to_owned(),
+            ParamType::Enum(_) => "enum".to_owned(),
+            ParamType::Array(ref param_type) => format!("{}[]", param_type.abi_type_name()),
+            ParamType::FixedArray(ref param_type, size) =>
+                format!("{}[{}]", param_type.abi_type_name(), size),
+            ParamType::Map(key_type, value_type) =>
+                format!("map({},{})", key_type.abi_type_name(), value_type.abi_type_name()),
+            ParamType::Optional(ref param_type) => format!("optional({})", param_type.abi_type_name()),
+            ParamType::Ref(ref param_type) => format!("ref({})", param_type.abi_type_name()),
+            _ => self.type_signature(),
+        }
+    }
+
+    /// Set tuple components. Type should contain exactly one tuple (possibly nested into
+    /// arrays, maps, optionals and refs) which receives components, or no tuples at all
+    /// if components are empty
+    pub fn set_components(&mut self, components: Vec<Param>) -> Result<()> {
+        let mut paths = vec![];
+        self.collect_tuple_paths(&mut vec![], &mut paths);
+        match paths.len() {
+            0 if components.is_empty() => Ok(()),
+            0 => Err(error!(AbiError::UnusedComponents)),
+            1 if components.is_empty() => Err(error!(AbiError::EmptyComponents)),
+            1 => {
+                if let Some(params) = self.tuple_components_mut() {
                     *params = components;
-                    Ok(())
                 }
+                Ok(())
             }
-            ParamType::Array(array_type) => {
-                array_type.set_components(components)
-            }
-            ParamType::FixedArray(array_type, _) => {
-                array_type.set_components(components)
+            _ => Err(error!(AbiError::AmbiguousComponents { paths: paths.join(", ") })),
+        }
+    }
+
+    /// Collects paths to all tuples reachable through containers, e.g. `map key -> tuple`
+    fn collect_tuple_paths(&self, path: &mut Vec<&'static str>, paths: &mut Vec<String>) {
+        let mut visit = |step: &'static str, inner: &ParamType, paths: &mut Vec<String>| {
+            path.push(step);
+            inner.collect_tuple_paths(path, paths);
+            path.pop();
+        };
+        match self {
+            ParamType::Tuple(_) => {
+                paths.push(path.iter().chain(&["tuple"]).cloned().collect::<Vec<_>>().join(" -> "))
             }
-            ParamType::Map(_, value_type) => {
-                value_type.set_components(components)
+            ParamType::Array(inner) => visit("array item", inner, paths),
+            ParamType::FixedArray(inner, _) => visit("fixed array item", inner, paths),
+            ParamType::Map(key, value) => {
+                visit("map key", key, paths);
+                visit("map value", value, paths);
             }
-            ParamType::Optional(inner_type) => {
-                inner_type.set_components(components)
+            ParamType::Optional(inner) => visit("optional value", inner, paths),
+            ParamType::Ref(inner) => visit("ref value", inner, paths),
+            _ => {}
+        }
+    }
+
+    /// Returns components of the tuple contained in the type
+    pub(crate) fn tuple_components(&self) -> Option<&Vec<Param>> {
+        match self {
+            ParamType::Tuple(params) => Some(params),
+            ParamType::Array(inner)
+            | ParamType::FixedArray(inner, _)
+            | ParamType::Optional(inner)
+            | ParamType::Ref(inner) => inner.tuple_components(),
+            ParamType::Map(key, value) => key.tuple_components().or_else(|| value.tuple_components()),
+            _ => None,
+        }
+    }
+
+    /// Returns variants of the enum contained in the type
+    pub(crate) fn enum_variants(&self) -> Option<&[String]> {
+        match self {
+            ParamType::Enum(names) => Some(names),
+            ParamType::Array(inner)
+            | ParamType::FixedArray(inner, _)
+            | ParamType::Map(_, inner)
+            | ParamType::Optional(inner)
+            | ParamType::Ref(inner) => inner.enum_variants(),
+            _ => None,
+        }
+    }
+
+    fn tuple_components_mut(&mut self) -> Option<&mut Vec<Param>> {
+        match self {
+            ParamType::Tuple(params) => Some(params),
+            ParamType::Array(inner)
+            | ParamType::FixedArray(inner, _)
+            | ParamType::Optional(inner)
+            | ParamType::Ref(inner) => inner.tuple_components_mut(),
+            ParamType::Map(key, value) => {
+                key.tuple_components_mut().or_else(|| value.tuple_components_mut())
             }
-            ParamType::Ref(inner_type) => {
-                inner_type.set_components(components)
+            _ => None,
+        }
+    }
+
+    /// Set variants names for enum types. For arrays, maps and other containers it is applied
+    /// to the inner type
+    pub fn set_variants(&mut self, variants: Vec<String>) -> Result<()> {
+        match self {
+            ParamType::Enum(names) => {
+                if variants.is_empty() {
+                    fail!(AbiError::EmptyVariants);
+                }
+                if variants.len() > ENUM_MAX_VARIANTS {
+                    fail!(AbiError::InvalidData {
+                        msg: format!("Enum can't have more than {} variants", ENUM_MAX_VARIANTS)
+                    });
+                }
+                for (i, variant) in variants.iter().enumerate() {
+                    if variants[..i].contains(variant) {
+                        fail!(AbiError::InvalidData { msg: format!("Duplicate enum variant {}", variant) });
+                    }
+                }
+                *names = variants.into();
+                Ok(())
             }
+            ParamType::Array(inner_type)
+            | ParamType::FixedArray(inner_type, _)
+            | ParamType::Map(_, inner_type)
+            | ParamType::Optional(inner_type)
+            | ParamType::Ref(inner_type) => inner_type.set_variants(variants),
             _ => {
-                if !components.is_empty() {
-                    Err(error!(AbiError::UnusedComponents))
-                } else {
-                    Ok(())
+                if !variants.is_empty() {
+                    fail!(AbiError::UnusedVariants);
                 }
-            },
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns first (possibly nested) integer type with size out of range allowed by
+    /// ABI specification
+    pub fn find_invalid_size(&self) -> Option<&ParamType> {
+        match self {
+            ParamType::Uint(size) if !UINT_SIZES.contains(size) => Some(self),
+            ParamType::Int(size) | ParamType::FixedPoint { bits: size, .. }
+                if !INT_SIZES.contains(size) => Some(self),
+            ParamType::VarUint(size) | ParamType::VarInt(size)
+                if !VAR_INT_SIZES.contains(size) => Some(self),
+            ParamType::Tuple(params) => params.iter().find_map(|param| param.kind.find_invalid_size()),
+            ParamType::Array(inner)
+            | ParamType::FixedArray(inner, _)
+            | ParamType::Optional(inner)
+            | ParamType::Ref(inner) => inner.find_invalid_size(),
+            ParamType::Map(key, value) => key.find_invalid_size().or_else(|| value.find_invalid_size()),
+            _ => None,
+        }
+    }
+
+    /// Returns nesting depth of the type. Simple types have depth 1
+    pub fn nesting_depth(&self) -> usize {
+        match self {
+            ParamType::Tuple(params) => {
+                1 + params.iter().map(|param| param.kind.nesting_depth()).max().unwrap_or(0)
+            }
+            ParamType::Array(inner)
+            | ParamType::FixedArray(inner, _)
+            | ParamType::Optional(inner)
+            | ParamType::Ref(inner) => 1 + inner.nesting_depth(),
+            ParamType::Map(key, value) => 1 + std::cmp::max(key.nesting_depth(), value.nesting_depth()),
+            _ => 1,
         }
     }
 
@@ -155,9 +325,122 @@ impl ParamType {
             ParamType::String
             | ParamType::Optional(_)
             | ParamType::VarInt(_)
-            | ParamType::VarUint(_) => abi_version >= &ABI_VERSION_2_1,
+            | ParamType::VarUint(_)
+            | ParamType::FixedPoint { .. }
+            | ParamType::Bits(_)
+            | ParamType::Slice => abi_version >= &ABI_VERSION_2_1,
             ParamType::Ref(_) => abi_version >= &ABI_VERSION_2_4,
             _ => abi_version >= &ABI_VERSION_1_0,
         }
     }
+
+    /// Returns maximum number of bits which value of the type occupies in its own cell when
+    /// encoded with particular ABI version. Data stored in references is not counted.
+    pub fn max_bit_size(&self, abi_version: &AbiVersion) -> usize {
+        match self {
+            ParamType::Uint(size) => *size,
+            ParamType::Int(size) => *size,
+            ParamType::VarUint(size) => TokenValue::varint_size_len(*size) + (size - 1) * 8,
+            ParamType::VarInt(size) => TokenValue::varint_size_len(*size) + (size - 1) * 8,
+            ParamType::FixedPoint { bits, .. } => *bits,
+            ParamType::Enum(_) => 8,
+            ParamType::Bits(size) => *size,
+            ParamType::Slice => SLICE_LEN_BITS + MAX_SLICE_BITS,
+            ParamType::Bool => 1,
+            ParamType::Array(_) => 33,
+            ParamType::FixedArray(_, _) => 1,
+            ParamType::Cell => 0,
+            ParamType::Map(_, _) => 1,
+            ParamType::Address => 591,
+            ParamType::AddressStd => 2 + (1 + 5 + 30) + 8 + 256,
+            ParamType::AddressVar => 2 + (1 + 5 + 30) + 9 + 32 + 511,
+            ParamType::AddressExt => 2 + 9 + 511,
+            ParamType::FixedBytes(size) if TokenValue::is_inline_fixed_bytes(*size, abi_version) => size * 8,
+            ParamType::Bytes | ParamType::FixedBytes(_) => 0,
+            ParamType::String => 0,
+            ParamType::Token => 124,
+            ParamType::Time => 64,
+            ParamType::Expire => 32,
+            ParamType::PublicKey => 257,
+            ParamType::Ref(_) => 0,
+            ParamType::Tuple(params) => params.iter().fold(0, |acc, param| {
+                acc + param.kind.max_bit_size(abi_version)
+            }),
+            ParamType::Optional(param_type) => {
+                if TokenValue::is_large_optional(param_type, abi_version) {
+                    1
+                } else {
+                    1 + param_type.max_bit_size(abi_version)
+                }
+            }
+        }
+    }
+
+    /// Returns minimum number of bits which value of the type occupies in its own cell when
+    /// encoded with particular ABI version, e.g. `addr_none` for addresses, zero length for
+    /// `varuint<N>` and empty optional.
+    pub fn min_bit_size(&self, abi_version: &AbiVersion) -> usize {
+        match self {
+            ParamType::VarUint(size) | ParamType::VarInt(size) => TokenValue::varint_size_len(*size),
+            ParamType::Address
+            | ParamType::AddressStd
+            | ParamType::AddressVar
+            | ParamType::AddressExt => 2,
+            ParamType::Token => 4,
+            ParamType::PublicKey => 1,
+            ParamType::Optional(_) => 1,
+            ParamType::Slice => SLICE_LEN_BITS,
+            ParamType::Tuple(params) => params.iter().fold(0, |acc, param| {
+                acc + param.kind.min_bit_size(abi_version)
+            }),
+            _ => self.max_bit_size(abi_version),
+        }
+    }
+
+    /// Returns maximum number of references which value of the type occupies in its own cell
+    /// when encoded with particular ABI version.
+    pub fn max_refs_count(&self, abi_version: &AbiVersion) -> usize {
+        match self {
+            // in-cell serialized types
+            ParamType::Uint(_)
+            | ParamType::Int(_)
+            | ParamType::VarUint(_)
+            | ParamType::VarInt(_)
+            | ParamType::FixedPoint { .. }
+            | ParamType::Enum(_)
+            | ParamType::Bits(_)
+            | ParamType::Slice
+            | ParamType::Bool
+            | ParamType::Address
+            | ParamType::AddressStd
+            | ParamType::AddressVar
+            | ParamType::AddressExt
+            | ParamType::Token
+            | ParamType::Time
+            | ParamType::Expire
+            | ParamType::PublicKey => 0,
+            ParamType::FixedBytes(size) if TokenValue::is_inline_fixed_bytes(*size, abi_version) => 0,
+            // reference serialized types
+            ParamType::Array(_)
+            | ParamType::FixedArray(_, _)
+            | ParamType::Cell
+            | ParamType::String
+            | ParamType::Map(_, _)
+            | ParamType::Bytes
+            | ParamType::FixedBytes(_)
+            | ParamType::Ref(_) => 1,
+            // tuple refs is sum of inner types refs
+            ParamType::Tuple(params) => params.iter().fold(0, |acc, param| {
+                acc + param.kind.max_refs_count(abi_version)
+            }),
+            // large optional is serialized into reference
+            ParamType::Optional(param_type) => {
+                if TokenValue::is_large_optional(param_type, abi_version) {
+                    1
+                } else {
+                    param_type.max_refs_count(abi_version)
+                }
+            }
+        }
+    }
 }
@@ -15,6 +15,28 @@ mod param_type_tests {
     use crate::ParamType;
     use crate::Param;
 
+    #[test]
+    fn test_param_type_nesting_depth() {
+        assert_eq!(ParamType::Uint(8).nesting_depth(), 1);
+        assert_eq!(
+            ParamType::Map(
+                Box::new(ParamType::Uint(8)),
+                Box::new(ParamType::Array(Box::new(ParamType::Tuple(vec![
+                    Param::new("a", ParamType::Bool),
+                    Param::new("b", ParamType::Optional(Box::new(ParamType::Cell))),
+                ])))),
+            ).nesting_depth(),
+            5
+        );
+
+        let mut deep = ParamType::Bool;
+        for _ in 0..crate::token::MAX_DECODE_DEPTH {
+            deep = ParamType::Ref(Box::new(deep));
+        }
+        let params = [Param::new("deep", deep)];
+        assert!(crate::TokenValue::check_params_depth(&params).is_err());
+    }
+
     #[test]
     fn test_param_type_signature() {
         assert_eq!(ParamType::Uint(256).type_signature(), "uint256".to_owned());
@@ -34,6 +56,10 @@ mod param_type_tests {
                 .type_signature(),
             "bytes[][2]".to_owned());
 
+        assert_eq!(
+            ParamType::FixedPoint { bits: 128, decimals: 9 }.type_signature(),
+            "fixed128x9".to_owned());
+
         let mut tuple_params = vec![];
         tuple_params.push(Param {name: "a".to_owned(), kind: ParamType::Uint(123)});
         tuple_params.push(Param {name: "b".to_owned(), kind: ParamType::Int(8)});
@@ -71,18 +97,52 @@ mod param_type_tests {
             ParamType::Ref(Box::new(ParamType::Uint(123))).type_signature(),
             "ref(uint123)".to_owned());
     }
+
+    #[test]
+    fn test_param_type_sizes() {
+        use crate::contract::{ABI_VERSION_2_2, ABI_VERSION_2_4};
+
+        let tuple = ParamType::Tuple(vec![
+            Param::new("a", ParamType::Uint(32)),
+            Param::new("b", ParamType::VarUint(16)),
+            Param::new("c", ParamType::Address),
+            Param::new("d", ParamType::Cell),
+        ]);
+        assert_eq!(tuple.max_bit_size(&ABI_VERSION_2_2), 32 + 124 + 591);
+        assert_eq!(tuple.min_bit_size(&ABI_VERSION_2_2), 32 + 4 + 2);
+        assert_eq!(tuple.max_refs_count(&ABI_VERSION_2_2), 1);
+
+        let optional = ParamType::Optional(Box::new(ParamType::Uint(64)));
+        assert_eq!(optional.max_bit_size(&ABI_VERSION_2_2), 65);
+        assert_eq!(optional.min_bit_size(&ABI_VERSION_2_2), 1);
+        assert_eq!(optional.max_refs_count(&ABI_VERSION_2_2), 0);
+
+        let large_optional = ParamType::Optional(Box::new(ParamType::Tuple(vec![
+            Param::new("a", tuple.clone()),
+            Param::new("b", tuple),
+        ])));
+        assert_eq!(large_optional.max_bit_size(&ABI_VERSION_2_2), 1);
+        assert_eq!(large_optional.max_refs_count(&ABI_VERSION_2_2), 1);
+
+        assert_eq!(ParamType::FixedBytes(32).max_bit_size(&ABI_VERSION_2_2), 0);
+        assert_eq!(ParamType::FixedBytes(32).max_bit_size(&ABI_VERSION_2_4), 256);
+        assert_eq!(ParamType::FixedBytes(32).max_refs_count(&ABI_VERSION_2_4), 0);
+        assert_eq!(ParamType::FixedBytes(200).max_bit_size(&ABI_VERSION_2_4), 0);
+        assert_eq!(ParamType::FixedBytes(200).max_refs_count(&ABI_VERSION_2_4), 1);
+        assert_eq!(ParamType::Uint(8).min_bit_size(&ABI_VERSION_2_2), 8);
+    }
 }
 
 mod deserialize_tests {
     use serde_json;
-    use crate::ParamType;
+    use crate::{Param, ParamType};
 
     #[test]
     fn param_type_deserialization() {
         let s = r#"["uint256", "int64", "bool", "bool[]", "int33[2]", "bool[][2]",
             "tuple", "tuple[]", "tuple[4]", "cell", "map(int3,bool)", "map(uint1023,tuple[][5])",
             "address", "bytes", "fixedbytes32", "token", "time", "expire", "pubkey", "string",
-            "varuint16", "varint32", "optional(bytes)", "ref(bool)"]"#;
+            "varuint16", "varint32", "optional(bytes)", "ref(bool)", "fixed128x9"]"#;
         let deserialized: Vec<ParamType> = serde_json::from_str(s).unwrap();
         assert_eq!(deserialized, vec![
             ParamType::Uint(256),
@@ -114,6 +174,59 @@ mod deserialize_tests {
             ParamType::VarInt(32),
             ParamType::Optional(Box::new(ParamType::Bytes)),
             ParamType::Ref(Box::new(ParamType::Bool)),
+            ParamType::FixedPoint { bits: 128, decimals: 9 },
+        ]);
+
+        assert_eq!(serde_json::from_str::<ParamType>(r#""bits12""#).unwrap(), ParamType::Bits(12));
+        assert!(serde_json::from_str::<ParamType>(r#""bits0""#).is_err());
+        assert!(serde_json::from_str::<ParamType>(r#""bits1024""#).is_err());
+        assert_eq!(serde_json::from_str::<ParamType>(r#""slice""#).unwrap(), ParamType::Slice);
+        assert!(serde_json::from_str::<ParamType>(r#""fixed128""#).is_err());
+        assert!(serde_json::from_str::<ParamType>(r#""fixedx9""#).is_err());
+        assert_eq!(
+            serde_json::from_str::<ParamType>(r#""fixed256x80""#).unwrap(),
+            ParamType::FixedPoint { bits: 256, decimals: 80 }
+        );
+        assert!(serde_json::from_str::<ParamType>(r#""fixed256x81""#).is_err());
+        assert!(serde_json::from_str::<ParamType>(r#""fixed128x18446744073709551615""#).is_err());
+    }
+
+    #[test]
+    fn param_type_aliases_deserialization() {
+        let s = r#"["coins", "gram", "bitstring", "bytes32", "coins[]", "map(uint8,bytes4)",
+            "optional(coins)", "bytes"]"#;
+        let deserialized: Vec<ParamType> = serde_json::from_str(s).unwrap();
+        assert_eq!(deserialized, vec![
+            ParamType::VarUint(16),
+            ParamType::Token,
+            ParamType::Slice,
+            ParamType::FixedBytes(32),
+            ParamType::Array(Box::new(ParamType::VarUint(16))),
+            ParamType::Map(Box::new(ParamType::Uint(8)), Box::new(ParamType::FixedBytes(4))),
+            ParamType::Optional(Box::new(ParamType::VarUint(16))),
+            ParamType::Bytes,
         ]);
+
+        assert!(serde_json::from_str::<ParamType>(r#""bytesx""#).is_err());
+
+        let error = serde_json::from_str::<ParamType>(r#""bytes99999999999999999999""#)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("bytes99999999999999999999"));
+        assert!(!error.contains("fixedbytes"));
+    }
+
+    #[test]
+    fn param_type_from_str() {
+        let mut parsed: ParamType = "map(uint32,tuple)".parse().unwrap();
+        assert_eq!(
+            parsed,
+            ParamType::Map(Box::new(ParamType::Uint(32)), Box::new(ParamType::Tuple(vec![])))
+        );
+        parsed.set_components(vec![Param::new("a", ParamType::Bool)]).unwrap();
+        assert_eq!(parsed.type_signature(), "map(uint32,(bool))");
+
+        assert!("uint".parse::<ParamType>().is_err());
+        assert!("unknown".parse::<ParamType>().is_err());
     }
 }
@@ -0,0 +1,80 @@
+/*
+* Copyright 2018-2020 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Registry of known contract ABIs.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use ton_types::{Result, SliceData, UInt256};
+
+use crate::contract::{Contract, DecodedBody};
+
+/// Thread-safe map of contract code hashes to contract ABIs
+#[derive(Debug, Default)]
+pub struct AbiRegistry {
+    contracts: RwLock<HashMap<UInt256, Arc<Contract>>>,
+}
+
+impl AbiRegistry {
+    /// Creates empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn read(&self) -> RwLockReadGuard<HashMap<UInt256, Arc<Contract>>> {
+        self.contracts.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<HashMap<UInt256, Arc<Contract>>> {
+        self.contracts.write().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Registers contract ABI for provided code hash. Returns previously registered ABI
+    pub fn register(&self, code_hash: UInt256, contract: impl Into<Arc<Contract>>) -> Option<Arc<Contract>> {
+        self.write().insert(code_hash, contract.into())
+    }
+
+    /// Removes contract ABI registered for provided code hash
+    pub fn unregister(&self, code_hash: &UInt256) -> Option<Arc<Contract>> {
+        self.write().remove(code_hash)
+    }
+
+    /// Returns contract ABI registered for provided code hash
+    pub fn lookup(&self, code_hash: &UInt256) -> Option<Arc<Contract>> {
+        self.read().get(code_hash).cloned()
+    }
+
+    /// Returns number of registered ABIs
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    /// Checks if registry has no ABIs
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+
+    /// Decodes message body of contract with provided code hash as function call, function
+    /// response or event. Returns `None` if no ABI is registered for the code hash.
+    pub fn decode_any(&self, code_hash: &UInt256, body: SliceData, internal: bool) -> Result<Option<DecodedBody>> {
+        match self.lookup(code_hash) {
+            Some(contract) => contract.decode_any(body, internal).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_registry.rs"]
+mod tests;
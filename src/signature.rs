@@ -1,10 +1,199 @@
 use std::borrow::Cow;
 
-use ed25519::signature::Signer;
+use hmac::{Hmac, Mac, NewMac};
+use sha2::Sha512;
+use ton_types::{fail, Result};
+
+use crate::error::AbiError;
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+#[cfg(feature = "dalek-v2")]
+pub mod dalek_v2;
+
+/// Signs external inbound messages bodies
+pub trait Signer {
+    /// Returns public key corresponding to the signing key
+    fn public_key(&self) -> ed25519_dalek::PublicKey;
+
+    /// Signs provided data
+    fn sign(&self, data: &[u8]) -> Result<ed25519_dalek::Signature>;
+}
+
+impl Signer for ed25519_dalek::Keypair {
+    fn public_key(&self) -> ed25519_dalek::PublicKey {
+        self.public
+    }
+
+    fn sign(&self, data: &[u8]) -> Result<ed25519_dalek::Signature> {
+        Ok(ed25519::signature::Signer::sign(self, data))
+    }
+}
+
+/// Future returned by `AsyncSigner::sign`
+#[cfg(feature = "async")]
+pub type SignFuture<'a> = std::pin::Pin<
+    Box<dyn std::future::Future<Output = Result<ed25519_dalek::Signature>> + Send + 'a>
+>;
+
+/// Signs external inbound messages bodies asynchronously, e.g. by remote custody service or KMS.
+/// The trait is object safe, so signers can be used as `&dyn AsyncSigner`
+#[cfg(feature = "async")]
+pub trait AsyncSigner {
+    /// Returns public key corresponding to the signing key
+    fn public_key(&self) -> ed25519_dalek::PublicKey;
+
+    /// Signs provided data
+    fn sign<'a>(&'a self, data: &'a [u8]) -> SignFuture<'a>;
+}
+
+/// Signer using expanded secret key (scalar and nonce) when original seed is not available.
+/// The key is wiped on drop by ed25519-dalek
+pub struct ExpandedKeySigner {
+    secret: ed25519_dalek::ExpandedSecretKey,
+    public: ed25519_dalek::PublicKey,
+}
+
+impl ExpandedKeySigner {
+    /// Creates signer from 64 bytes of expanded secret key
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let secret = ed25519_dalek::ExpandedSecretKey::from_bytes(bytes)?;
+        let public = ed25519_dalek::PublicKey::from(&secret);
+        Ok(Self { secret, public })
+    }
+}
+
+impl Signer for ExpandedKeySigner {
+    fn public_key(&self) -> ed25519_dalek::PublicKey {
+        self.public
+    }
+
+    fn sign(&self, data: &[u8]) -> Result<ed25519_dalek::Signature> {
+        Ok(self.secret.sign(data, &self.public))
+    }
+}
+
+/// Creates keypair from 32 bytes secret key seed
+pub fn keypair_from_seed(seed: &[u8]) -> Result<ed25519_dalek::Keypair> {
+    let secret = ed25519_dalek::SecretKey::from_bytes(seed)?;
+    let public = ed25519_dalek::PublicKey::from(&secret);
+    Ok(ed25519_dalek::Keypair { secret, public })
+}
+
+/// Creates keypair from BIP39 seed (e.g. produced from mnemonic phrase) using SLIP-0010
+/// ed25519 derivation along hardened `path` like `m/44'/396'/0'/0'/0'`.
+/// Derived seed copy is wiped only with `zeroize` feature
+pub fn keypair_from_derived_seed(bip39_seed: &[u8], path: &str) -> Result<ed25519_dalek::Keypair> {
+    let mut seed = derive_ed25519_key(bip39_seed, path)?;
+    let result = keypair_from_seed(&seed);
+    // no-op without `zeroize` feature
+    wipe(&mut seed);
+    result
+}
+
+/// Derives ed25519 secret key seed from BIP39 seed according to SLIP-0010.
+/// Only hardened derivation (`44'` or `44h`) is defined for ed25519.
+/// Intermediate keys are wiped only with `zeroize` feature
+pub fn derive_ed25519_key(bip39_seed: &[u8], path: &str) -> Result<[u8; 32]> {
+    let mut steps = path.split('/');
+    if steps.next() != Some("m") {
+        fail!(AbiError::InvalidInputData { msg: format!("Derivation path {} should start with `m`", path) });
+    }
+    let indexes = steps
+        .map(|step| step.strip_suffix('\'')
+            .or_else(|| step.strip_suffix('h'))
+            .and_then(|index| index.parse::<u32>().ok())
+            .filter(|index| index & 0x8000_0000 == 0)
+            .ok_or_else(|| AbiError::InvalidInputData {
+                msg: format!("Invalid derivation path {}: only hardened indexes are supported", path)
+            }))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let mut key = hmac_sha512(b"ed25519 seed", bip39_seed)?;
+    let mut data = [0u8; 37];
+    for index in indexes {
+        data[1..33].copy_from_slice(&key[..32]);
+        data[33..].copy_from_slice(&(index | 0x8000_0000).to_be_bytes());
+        let mut next = hmac_sha512(&key[32..], &data)?;
+        key.copy_from_slice(&next);
+        // no-op without `zeroize` feature
+        wipe(&mut next);
+    }
+
+    let mut result = [0; 32];
+    result.copy_from_slice(&key[..32]);
+    // no-op without `zeroize` feature
+    wipe(&mut key);
+    wipe(&mut data);
+    Ok(result)
+}
+
+fn hmac_sha512(key: &[u8], data: &[u8]) -> Result<[u8; 64]> {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key)
+        .map_err(|_| AbiError::InvalidInputData { msg: "Invalid HMAC key length".to_owned() })?;
+    mac.update(data);
+    let mut output = mac.finalize().into_bytes();
+
+    let mut result = [0; 64];
+    result.copy_from_slice(&output);
+    // no-op without `zeroize` feature
+    wipe(&mut output[..]);
+    Ok(result)
+}
+
+/// Clears buffer holding secret material with `zeroize` feature
+#[cfg(feature = "zeroize")]
+pub(crate) fn wipe(buffer: &mut [u8]) {
+    zeroize::Zeroize::zeroize(buffer)
+}
+
+/// Does nothing: buffers are left as is without `zeroize` feature
+#[cfg(not(feature = "zeroize"))]
+pub(crate) fn wipe(_buffer: &mut [u8]) {}
+
+/// Compares byte strings (e.g. public keys or signatures) in time independent of their content
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b).fold(0u8, |acc, (x, y)| std::hint::black_box(acc | (x ^ y))) == 0
+}
+
+/// Global capability flag enabling signatures with id in network config (`CapSignatureWithId`)
+pub const CAP_SIGNATURE_WITH_ID: u64 = 0x0400_0000;
+
+/// Global ids of known networks
+pub const KNOWN_NETWORKS: &[(&str, i32)] = &[
+    ("everscale", 42),
+    ("venom", 1000),
+    ("ton", -239),
+    ("ton-testnet", -3),
+];
+
+/// Returns signature id for network with provided global id and global capabilities.
+/// Signature id is network global id if `CAP_SIGNATURE_WITH_ID` capability is enabled
+pub fn signature_id_from_global_id(global_id: i32, capabilities: u64) -> Option<i32> {
+    if capabilities & CAP_SIGNATURE_WITH_ID != 0 {
+        Some(global_id)
+    } else {
+        None
+    }
+}
+
+/// Returns global id of known network by its name
+pub fn network_global_id(network: &str) -> Option<i32> {
+    KNOWN_NETWORKS
+        .iter()
+        .find(|(name, _)| *name == network)
+        .map(|(_, global_id)| *global_id)
+}
 
 pub fn sign_with_signature_id(key: &ed25519_dalek::Keypair, data: &[u8], signature_id: Option<i32>) -> ed25519_dalek::Signature {
-    let data = extend_signature_with_id(data, signature_id);
-    key.sign(&data)
+    let mut data = extend_signature_with_id(data, signature_id);
+    let signature = ed25519::signature::Signer::sign(key, &data);
+    // extended copy of signed data is not left in freed memory (no-op without `zeroize` feature)
+    if let Cow::Owned(data) = &mut data {
+        wipe(data);
+    }
+    signature
 }
 
 pub fn extend_signature_with_id(data: &[u8], signature_id: Option<i32>) -> Cow<'_, [u8]> {
@@ -0,0 +1,58 @@
+/*
+* Copyright 2018-2020 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Interoperability with ed25519-dalek 2.x `SigningKey`/`VerifyingKey` types. `SigningKey`
+//! implements `Signer` so it can be used anywhere the crate accepts a signer, keys and
+//! signatures can be converted to and from the types used in `TokenValue::PublicKey`.
+
+pub use ed25519_dalek_v2::{Signature, SigningKey, VerifyingKey};
+
+use ton_types::Result;
+
+use super::Signer;
+use crate::TokenValue;
+
+impl Signer for SigningKey {
+    fn public_key(&self) -> ed25519_dalek::PublicKey {
+        to_public_key(&self.verifying_key()).expect("verifying key is a valid curve point")
+    }
+
+    fn sign(&self, data: &[u8]) -> Result<ed25519_dalek::Signature> {
+        to_signature(&ed25519_dalek_v2::Signer::sign(self, data))
+    }
+}
+
+/// Converts 2.x verifying key into public key type used by the crate
+pub fn to_public_key(key: &VerifyingKey) -> Result<ed25519_dalek::PublicKey> {
+    Ok(ed25519_dalek::PublicKey::from_bytes(key.as_bytes())?)
+}
+
+/// Converts public key used by the crate into 2.x verifying key
+pub fn to_verifying_key(key: &ed25519_dalek::PublicKey) -> Result<VerifyingKey> {
+    Ok(VerifyingKey::from_bytes(key.as_bytes())?)
+}
+
+/// Converts keypair used by the crate into 2.x signing key
+pub fn to_signing_key(keypair: &ed25519_dalek::Keypair) -> SigningKey {
+    SigningKey::from_bytes(keypair.secret.as_bytes())
+}
+
+/// Converts 2.x signature into signature type used by the crate
+pub fn to_signature(signature: &Signature) -> Result<ed25519_dalek::Signature> {
+    Ok(ed25519_dalek::Signature::from_bytes(&signature.to_bytes())?)
+}
+
+/// Creates `pubkey` header value from 2.x verifying key
+pub fn public_key_token(key: Option<&VerifyingKey>) -> Result<TokenValue> {
+    Ok(TokenValue::PublicKey(key.map(to_public_key).transpose()?))
+}
@@ -0,0 +1,51 @@
+/*
+* Copyright 2018-2020 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Deterministic keys and signers for reproducible (golden) tests of encoded messages.
+
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signature, SIGNATURE_LENGTH};
+use ton_types::Result;
+
+use super::Signer;
+
+/// Signature returned by `FixedSigner`
+pub const FIXED_SIGNATURE: [u8; SIGNATURE_LENGTH] = [0x0F; SIGNATURE_LENGTH];
+
+/// Returns keypair with secret key consisting of `seed` bytes. Since ed25519 signatures are
+/// deterministic, signing the same data with this keypair always gives the same signature
+pub fn keypair(seed: u8) -> Keypair {
+    let secret = SecretKey::from_bytes(&[seed; 32]).expect("secret key of valid length");
+    let public = PublicKey::from(&secret);
+    Keypair { secret, public }
+}
+
+/// Signer with public key of `keypair(seed)` returning `FIXED_SIGNATURE` for any data
+pub struct FixedSigner {
+    public: PublicKey,
+}
+
+impl FixedSigner {
+    pub fn new(seed: u8) -> Self {
+        Self { public: keypair(seed).public }
+    }
+}
+
+impl Signer for FixedSigner {
+    fn public_key(&self) -> PublicKey {
+        self.public
+    }
+
+    fn sign(&self, _data: &[u8]) -> Result<Signature> {
+        Ok(Signature::from_bytes(&FIXED_SIGNATURE)?)
+    }
+}
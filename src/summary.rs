@@ -0,0 +1,167 @@
+/*
+* Copyright 2018-2020 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Human readable contract ABI summary.
+
+use std::fmt;
+
+use crate::contract::{AbiVersion, Contract};
+use crate::param::Param;
+
+/// Summary of contract function
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FunctionSummary {
+    pub name: String,
+    /// ABI function signature used for id calculation
+    pub signature: String,
+    pub input_id: u32,
+    pub output_id: u32,
+    /// Inputs as `name: type` strings
+    pub inputs: Vec<String>,
+    /// Outputs as `name: type` strings
+    pub outputs: Vec<String>,
+}
+
+/// Summary of contract event
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct EventSummary {
+    pub name: String,
+    /// ABI event signature used for id calculation
+    pub signature: String,
+    pub id: u32,
+    /// Inputs as `name: type` strings
+    pub inputs: Vec<String>,
+}
+
+/// Summary of contract getter
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GetterSummary {
+    pub name: String,
+    pub method_id: u32,
+    /// Inputs as `name: type` strings
+    pub inputs: Vec<String>,
+    /// Outputs as `name: type` strings
+    pub outputs: Vec<String>,
+}
+
+/// Summary of contract ABI. All members are listed in ABI declaration order
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ContractSummary {
+    pub version: AbiVersion,
+    /// Header params as `name: type` strings
+    pub header: Vec<String>,
+    pub functions: Vec<FunctionSummary>,
+    pub events: Vec<EventSummary>,
+    pub getters: Vec<GetterSummary>,
+    /// Data items as `(key, "name: type")`
+    pub data: Vec<(u64, String)>,
+    /// Storage fields as `("name: type", init)`
+    pub fields: Vec<(String, bool)>,
+}
+
+fn describe_params(params: &[Param]) -> Vec<String> {
+    params.iter().map(|param| format!("{}: {}", param.name, param.kind)).collect()
+}
+
+impl Contract {
+    /// Returns structured summary of contract ABI
+    pub fn describe(&self) -> ContractSummary {
+        ContractSummary {
+            version: self.abi_version,
+            header: describe_params(&self.header),
+            functions: self.functions_in_order().into_iter().map(|function| FunctionSummary {
+                name: function.name.clone(),
+                signature: function.get_function_signature(),
+                input_id: function.get_input_id(),
+                output_id: function.get_output_id(),
+                inputs: describe_params(&function.inputs),
+                outputs: describe_params(&function.outputs),
+            }).collect(),
+            events: self.events_in_order().into_iter().map(|event| EventSummary {
+                name: event.name.clone(),
+                signature: event.get_function_signature(),
+                id: event.get_id(),
+                inputs: describe_params(&event.inputs),
+            }).collect(),
+            getters: self.getters_in_order().into_iter().map(|getter| GetterSummary {
+                name: getter.name.clone(),
+                method_id: getter.get_id(),
+                inputs: describe_params(&getter.inputs),
+                outputs: describe_params(&getter.outputs),
+            }).collect(),
+            data: self.data_in_order().into_iter()
+                .map(|item| (item.key, format!("{}: {}", item.value.name, item.value.kind)))
+                .collect(),
+            fields: self.fields.iter()
+                .map(|field| (format!("{}: {}", field.name, field.kind), self.init_fields.contains(&field.name)))
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for ContractSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "ABI version: {}", self.version)?;
+        writeln!(f, "Header: {}", self.header.join(", "))?;
+        if !self.functions.is_empty() {
+            writeln!(f, "Functions:")?;
+            for function in &self.functions {
+                writeln!(
+                    f,
+                    "  0x{:08X} / 0x{:08X}  {}({}) -> ({})",
+                    function.input_id,
+                    function.output_id,
+                    function.name,
+                    function.inputs.join(", "),
+                    function.outputs.join(", "),
+                )?;
+            }
+        }
+        if !self.events.is_empty() {
+            writeln!(f, "Events:")?;
+            for event in &self.events {
+                writeln!(f, "  0x{:08X}  {}({})", event.id, event.name, event.inputs.join(", "))?;
+            }
+        }
+        if !self.getters.is_empty() {
+            writeln!(f, "Getters:")?;
+            for getter in &self.getters {
+                writeln!(
+                    f,
+                    "  {}  {}({}) -> ({})",
+                    getter.method_id,
+                    getter.name,
+                    getter.inputs.join(", "),
+                    getter.outputs.join(", "),
+                )?;
+            }
+        }
+        if !self.data.is_empty() {
+            writeln!(f, "Data:")?;
+            for (key, item) in &self.data {
+                writeln!(f, "  {}  {}", key, item)?;
+            }
+        }
+        if !self.fields.is_empty() {
+            writeln!(f, "Fields:")?;
+            for (field, init) in &self.fields {
+                writeln!(f, "  {}{}", field, if *init { " (init)" } else { "" })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_summary.rs"]
+mod tests;
@@ -27,3 +27,16 @@ fn test_pubkey() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_error_codes() {
+    let err = Contract::load(r#"{"functions": []}"#.as_bytes()).unwrap_err();
+    assert_eq!(crate::AbiError::code_of(&err), Some(14));
+
+    let contract = Contract::load(r#"{"version": "2.2", "functions": []}"#.as_bytes()).unwrap();
+    let err = contract.function("unknown").unwrap_err();
+    assert_eq!(crate::AbiError::code_of(&err), Some(3));
+
+    assert_eq!(crate::AbiError::code_of(&ton_types::error!("other error")), None);
+    assert_eq!(crate::AbiError::MaxDepthExceeded { name: "a".to_owned(), depth: 2, max: 1 }.code(), 31);
+}
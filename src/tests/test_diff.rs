@@ -0,0 +1,98 @@
+/*
+* Copyright 2018-2020 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::Contract;
+use crate::diff::MemberChange;
+
+const OLD_ABI: &str = r#"{
+    "version": "2.2",
+    "header": ["time", "expire"],
+    "functions": [
+        {"name": "transfer", "inputs": [{"name": "to", "type": "address"}], "outputs": []},
+        {"name": "balance", "inputs": [], "outputs": [{"name": "value", "type": "uint128"}]},
+        {"name": "burn", "inputs": [{"name": "amount", "type": "uint128"}], "outputs": []}
+    ],
+    "events": [
+        {"name": "Transferred", "inputs": [{"name": "amount", "type": "uint128"}]}
+    ],
+    "fields": [
+        {"name": "owner", "type": "address"},
+        {"name": "total", "type": "uint128"}
+    ]
+}"#;
+
+const NEW_ABI: &str = r#"{
+    "version": "2.2",
+    "header": ["time", "expire"],
+    "functions": [
+        {"name": "transfer", "inputs": [{"name": "dest", "type": "address"}], "outputs": []},
+        {"name": "balance", "inputs": [], "outputs": [{"name": "value", "type": "uint256"}]},
+        {"name": "mint", "inputs": [{"name": "amount", "type": "uint128"}], "outputs": []}
+    ],
+    "events": [
+        {"name": "Transferred", "inputs": [{"name": "amount", "type": "uint128"}]},
+        {"name": "Minted", "inputs": [{"name": "amount", "type": "uint128"}]}
+    ],
+    "fields": [
+        {"name": "owner", "type": "address"},
+        {"name": "total", "type": "uint128"},
+        {"name": "paused", "type": "bool"}
+    ]
+}"#;
+
+#[test]
+fn test_diff() {
+    let old = Contract::load(OLD_ABI.as_bytes()).unwrap();
+    let new = Contract::load(NEW_ABI.as_bytes()).unwrap();
+
+    assert!(old.diff(&old).is_empty());
+
+    let diff = old.diff(&new);
+    assert_eq!(diff.version, None);
+    assert_eq!(diff.added_functions, vec!["mint".to_owned()]);
+    assert_eq!(diff.removed_functions, vec!["burn".to_owned()]);
+    assert_eq!(diff.changed_functions, vec![
+        MemberChange {
+            name: "balance".to_owned(),
+            id_changed: true,
+            layout_changed: true,
+            names_changed: false,
+        },
+        MemberChange {
+            name: "transfer".to_owned(),
+            id_changed: false,
+            layout_changed: false,
+            names_changed: true,
+        },
+    ]);
+    assert_eq!(diff.added_events, vec!["Minted".to_owned()]);
+    assert!(diff.removed_events.is_empty());
+    assert!(diff.changed_events.is_empty());
+    assert!(!diff.header_changed);
+    assert!(!diff.storage_layout_changed);
+    assert!(!diff.is_backward_compatible());
+
+    let diff = new.diff(&old);
+    assert!(diff.storage_layout_changed);
+}
+
+#[test]
+fn test_diff_compatible() {
+    let old = Contract::load(OLD_ABI.as_bytes()).unwrap();
+    let mut new = old.clone();
+    new.functions.get_mut("transfer").unwrap().inputs[0].name = "dest".to_owned();
+
+    let diff = old.diff(&new);
+    assert!(!diff.is_empty());
+    assert!(diff.is_backward_compatible());
+}
@@ -0,0 +1,83 @@
+/*
+* Copyright 2018-2020 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use serde_json::Value;
+use ton_types::SliceData;
+
+use crate::{Contract, Getter, Token, TokenValue, Uint};
+use crate::json_abi::{decode_getter_output, encode_getter_input};
+
+const ABI: &str = r#"{
+    "version": "2.7",
+    "header": ["time"],
+    "functions": [],
+    "getters": [
+        {"name": "seqno", "inputs": [], "outputs": [{"name": "value0", "type": "uint32"}]},
+        {
+            "name": "balanceOf",
+            "inputs": [{"name": "owner", "type": "uint256"}],
+            "outputs": [{"name": "balance", "type": "uint128"}, {"name": "frozen", "type": "bool"}]
+        },
+        {"name": "custom", "id": "0x1234", "inputs": [], "outputs": []}
+    ],
+    "events": []
+}"#;
+
+#[test]
+fn test_getter_method_id() {
+    assert_eq!(Getter::calc_method_id("seqno"), 85143);
+    assert_eq!(Getter::calc_method_id("get_public_key"), 78748);
+
+    let contract = Contract::load(ABI.as_bytes()).unwrap();
+    assert_eq!(contract.getter("seqno").unwrap().get_id(), 85143);
+    assert_eq!(contract.getter("custom").unwrap().get_id(), 0x1234);
+    assert!(contract.getter("unknown").is_err());
+    assert_eq!(contract.getter_by_id(85143).unwrap().name, "seqno");
+    assert_eq!(contract.getter_by_id(0x1234).unwrap().name, "custom");
+    assert!(contract.getter_by_id(1).is_err());
+    assert_eq!(
+        contract.getter("balanceOf").unwrap().get_function_signature(),
+        "balanceOf(uint256)(uint128,bool)v2"
+    );
+}
+
+#[test]
+fn test_getter_encode_decode() {
+    let contract = Contract::load(ABI.as_bytes()).unwrap();
+    let getter = contract.getter("balanceOf").unwrap();
+
+    let input = vec![Token::new("owner", TokenValue::Uint(Uint::new(123, 256)))];
+    let encoded = getter.encode_input(&input).unwrap();
+    assert_eq!(
+        getter.decode_input(SliceData::load_builder(encoded.clone()).unwrap(), false).unwrap(),
+        input
+    );
+    assert_eq!(encode_getter_input(ABI, "balanceOf", r#"{"owner": "123"}"#).unwrap(), encoded);
+    assert!(getter.encode_input(&[Token::new("owner", TokenValue::Bool(true))]).is_err());
+
+    let output = vec![
+        Token::new("balance", TokenValue::Uint(Uint::new(1000, 128))),
+        Token::new("frozen", TokenValue::Bool(true)),
+    ];
+    let data = TokenValue::pack_values_into_chain(&output, vec![], &getter.abi_version).unwrap();
+    let data = SliceData::load_builder(data).unwrap();
+    assert_eq!(getter.decode_output(data.clone(), false).unwrap(), output);
+
+    assert_eq!(contract.decode_getter_output("balanceOf", data.clone()).unwrap(), output);
+
+    let decoded = decode_getter_output(ABI, "balanceOf", data, false).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Value>(&decoded).unwrap(),
+        serde_json::json!({"balance": "1000", "frozen": true})
+    );
+}
@@ -0,0 +1,83 @@
+/*
+* Copyright 2018-2020 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::Contract;
+use crate::lint::LintFinding;
+
+const ABI: &str = r#"{
+    "version": "2.2",
+    "header": ["time"],
+    "functions": [
+        {
+            "name": "a",
+            "inputs": [
+                {"name": "__x", "type": "uint8"},
+                {"name": "t", "type": "tuple", "components": [{"name": "y", "type": "bool"}]}
+            ],
+            "outputs": []
+        },
+        {
+            "name": "b",
+            "inputs": [
+                {"name": "x", "type": "uint8"},
+                {"name": "t", "type": "tuple", "components": [{"name": "z", "type": "bool"}]}
+            ],
+            "outputs": []
+        },
+        {"name": "c", "inputs": [{"name": "arr", "type": "uint8[0]"}], "outputs": []}
+    ],
+    "structs": [
+        {"name": "Used", "components": [{"name": "inner", "type": "struct:Nested"}, {"name": "f", "type": "bool"}]},
+        {"name": "Nested", "components": [{"name": "g", "type": "bool"}, {"name": "h", "type": "bool"}]},
+        {"name": "Unused", "components": [{"name": "u", "type": "bool"}, {"name": "w", "type": "bool"}]}
+    ],
+    "fields": [
+        {"name": "s", "type": "struct:Used"}
+    ],
+    "events": [
+        {"name": "e1", "inputs": [{"name": "", "type": "bool"}]},
+        {"name": "e2", "inputs": [{"name": "v", "type": "bool"}]}
+    ]
+}"#;
+
+#[test]
+fn test_lint() {
+    let contract = Contract::load(ABI.as_bytes()).unwrap();
+    let findings = contract.lint();
+
+    assert_eq!(findings, vec![
+        LintFinding::ReservedPrefix { location: "function a inputs __x".to_owned(), prefix: "__".to_owned() },
+        LintFinding::SingleMemberTuple { location: "function a inputs t".to_owned() },
+        LintFinding::SingleMemberTuple { location: "function b inputs t".to_owned() },
+        LintFinding::EmptyFixedArray { location: "function c inputs arr".to_owned() },
+        LintFinding::EmptyName { location: "event e1 #0".to_owned() },
+        LintFinding::UnusedComponents { location: "struct Unused".to_owned() },
+        LintFinding::IdenticalFunctionSignatures {
+            signature: "(uint8,(bool))()".to_owned(),
+            names: vec!["a".to_owned(), "b".to_owned()],
+        },
+        LintFinding::IdenticalEventSignatures {
+            signature: "(bool)".to_owned(),
+            names: vec!["e1".to_owned(), "e2".to_owned()],
+        },
+    ]);
+    assert_eq!(findings[3].code(), "empty-fixed-array");
+    assert_eq!(
+        findings[1].to_string(),
+        "[single-member-tuple] Tuple function a inputs t has single component"
+    );
+    assert_eq!(findings[5].to_string(), "[unused-components] Components of struct Unused are not used");
+
+    let clean = Contract::load(r#"{"version":"2.2","functions":[],"events":[]}"#.as_bytes()).unwrap();
+    assert!(clean.lint().is_empty());
+}
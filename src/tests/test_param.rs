@@ -11,17 +11,18 @@
 * limitations under the License.
 */
 
-use crate::token::Detokenizer;
+use crate::token::{Detokenizer, Tokenizer};
 use crate::Int;
 
 use crate::Token;
-use crate::{Param, ParamType};
+use crate::{Param, ParamAttributes, ParamType};
 use crate::{TokenValue, Uint};
 use crate::Function;
-use ton_types::BuilderData;
+use ton_types::{BuilderData, SliceData};
 use ton_types::IBitstring;
 
 use crate::contract::ABI_VERSION_2_0;
+use std::sync::Arc;
 
 #[test]
 fn int_json_representation() {
@@ -234,6 +235,35 @@ fn test_empty_tuple_error() {
     )
 }
 
+#[test]
+fn test_components_placement() {
+    let load = |kind: &str, components: &str| serde_json::from_str::<Param>(&format!(
+        r#"{{"name": "a", "type": "{}", "components": {}}}"#, kind, components
+    ));
+    let components = r#"[{"name": "b", "type": "bool"}]"#;
+    let tuple = ParamType::Tuple(vec![Param::new("b", ParamType::Bool)]);
+
+    assert_eq!(
+        load("optional(tuple)[]", components).unwrap().kind,
+        ParamType::Array(Box::new(ParamType::Optional(Box::new(tuple.clone()))))
+    );
+    assert_eq!(
+        load("map(uint32,ref(tuple))", components).unwrap().kind,
+        ParamType::Map(Box::new(ParamType::Uint(32)), Box::new(ParamType::Ref(Box::new(tuple))))
+    );
+    assert_eq!(load("uint32[]", "[]").unwrap().kind, ParamType::Array(Box::new(ParamType::Uint(32))));
+
+    assert_eq!(
+        load("map(uint32,uint8)", components).unwrap_err().to_string(),
+        "Type description contains non empty `components` field but it is not a tuple"
+    );
+    assert_eq!(
+        load("map(tuple,tuple[])", components).unwrap_err().to_string(),
+        "Type description contains several tuples which can't share one `components` field: \
+            map key -> tuple, map value -> array item -> tuple"
+    );
+}
+
 #[test]
 fn test_optional_tuple_param_deserialization() {
     let s = r#"{
@@ -255,3 +285,152 @@ fn test_optional_tuple_param_deserialization() {
         ]))),
     });
 }
+
+#[test]
+fn test_enum_param_deserialization() {
+    let s = r#"{
+        "name": "a",
+        "type": "enum[]",
+        "variants": ["Idle", "Active", "Closed"]
+    }"#;
+
+    let deserialized: Param = serde_json::from_str(s).unwrap();
+    let variants = vec!["Idle".to_owned(), "Active".to_owned(), "Closed".to_owned()];
+
+    assert_eq!(deserialized, Param::new("a", ParamType::Array(Box::new(ParamType::Enum(variants.into())))));
+    assert_eq!(deserialized.kind.type_signature(), "uint8[]");
+
+    let result = serde_json::from_str::<Param>(r#"{"name": "a", "type": "enum"}"#).unwrap_err();
+    assert_eq!("Enum description should contain non empty `variants` field", format!("{}", result));
+
+    let result = serde_json::from_str::<Param>(r#"{"name": "a", "type": "uint8", "variants": ["A"]}"#).unwrap_err();
+    assert_eq!(
+        "Type description contains non empty `variants` field but it is not an enum",
+        format!("{}", result)
+    );
+
+    assert!(serde_json::from_str::<Param>(r#"{"name": "a", "type": "enum", "variants": ["A", "A"]}"#).is_err());
+}
+
+#[test]
+fn test_enum_tokenization() {
+    let kind = ParamType::Enum(vec!["Idle".to_owned(), "Active".to_owned()].into());
+    let params = vec![Param::new("a", kind.clone()), Param::new("b", kind.clone()), Param::new("c", kind.clone())];
+
+    let tokens = Tokenizer::tokenize_all_params(
+        &params,
+        &serde_json::json!({"a": "Active", "b": 0, "c": "1"}),
+    ).unwrap();
+    let variants: Arc<[String]> = vec!["Idle".to_owned(), "Active".to_owned()].into();
+    assert_eq!(tokens, vec![
+        Token::new("a", TokenValue::Enum(variants.clone(), 1)),
+        Token::new("b", TokenValue::Enum(variants.clone(), 0)),
+        Token::new("c", TokenValue::Enum(variants.clone(), 1)),
+    ]);
+    assert_eq!(
+        Detokenizer::detokenize_to_json_value(&tokens).unwrap(),
+        serde_json::json!({"a": "Active", "b": "Idle", "c": "Active"})
+    );
+
+    assert!(Tokenizer::tokenize_parameter(&kind, &serde_json::json!("Closed"), "a").is_err());
+    assert!(Tokenizer::tokenize_parameter(&kind, &serde_json::json!(2), "a").is_err());
+
+    let mut builder = BuilderData::new();
+    builder.append_u8(1).unwrap();
+    let encoded = TokenValue::pack_values_into_chain(&tokens[..1], vec![], &ABI_VERSION_2_0).unwrap();
+    assert_eq!(encoded, builder);
+    let decoded = TokenValue::decode_params(&params[..1], SliceData::load_builder(encoded).unwrap(), &ABI_VERSION_2_0, false).unwrap();
+    assert_eq!(decoded, tokens[..1].to_vec());
+    // decoded value shares variants names with param type
+    match (&kind, &decoded[0].value) {
+        (ParamType::Enum(names), TokenValue::Enum(variants, _)) => assert!(Arc::ptr_eq(names, variants)),
+        other => panic!("unexpected enum decoding: {:?}", other),
+    }
+
+    let mut builder = BuilderData::new();
+    builder.append_u8(2).unwrap();
+    assert!(TokenValue::decode_params(&params[..1], SliceData::load_builder(builder).unwrap(), &ABI_VERSION_2_0, false).is_err());
+}
+
+#[test]
+fn test_param_default_value() {
+    let s = r#"[
+        {"name": "a", "type": "uint8", "default": 7},
+        {"name": "b", "type": "tuple", "components": [{"name": "c", "type": "bool", "default": true}]}
+    ]"#;
+
+    let params: Vec<Param> = serde_json::from_str(s).unwrap();
+    let attributes: Vec<ParamAttributes> = serde_json::from_str(s).unwrap();
+    assert_eq!(attributes[0].default, Some(serde_json::json!(7)));
+    assert_eq!(attributes[1].component(0).default, Some(serde_json::json!(true)));
+
+    let values = serde_json::json!({"b": {}});
+    let tokens = Tokenizer::tokenize_all_params_with_defaults(&params, &attributes, &values).unwrap();
+    assert_eq!(tokens, vec![
+        Token::new("a", TokenValue::Uint(Uint::new(7, 8))),
+        Token::new("b", TokenValue::Tuple(vec![Token::new("c", TokenValue::Bool(true))])),
+    ]);
+
+    let values = serde_json::json!({"a": 3});
+    let tokens = Tokenizer::tokenize_all_params_with_defaults(&params[..1], &attributes, &values).unwrap();
+    assert_eq!(tokens, vec![Token::new("a", TokenValue::Uint(Uint::new(3, 8)))]);
+
+    // defaults are not applied without attributes
+    assert!(Tokenizer::tokenize_all_params(&params, &serde_json::json!({"b": {}})).is_err());
+}
+
+#[test]
+fn test_param_metadata() {
+    let s = r#"{
+        "name": "a",
+        "type": "tuple",
+        "desc": "Transfer",
+        "components": [
+            {"name": "value", "type": "uint128", "desc": "Amount", "units": "nanotokens"},
+            {"name": "flag", "type": "bool"}
+        ]
+    }"#;
+
+    let deserialized: Param = serde_json::from_str(s).unwrap();
+    let attributes: ParamAttributes = serde_json::from_str(s).unwrap();
+    assert_eq!(attributes.metadata.len(), 1);
+    assert_eq!(attributes.metadata["desc"], serde_json::json!("Transfer"));
+    assert_eq!(attributes.component(0).metadata.len(), 2);
+    assert_eq!(attributes.component(0).metadata["desc"], serde_json::json!("Amount"));
+    assert_eq!(attributes.component(0).metadata["units"], serde_json::json!("nanotokens"));
+    assert!(attributes.component(1).metadata.is_empty());
+
+    // metadata doesn't affect param itself
+    let plain = serde_json::json!({
+        "name": "a",
+        "type": "tuple",
+        "components": [{"name": "value", "type": "uint128"}, {"name": "flag", "type": "bool"}]
+    });
+    assert_eq!(serde_json::to_value(&deserialized).unwrap(), plain);
+    assert_eq!(serde_json::from_value::<Param>(plain).unwrap(), deserialized);
+
+    // metadata is written back when param is serialized with attributes
+    let serialized = serde_json::to_value(deserialized.with_attributes(&attributes)).unwrap();
+    assert_eq!(serialized, serde_json::from_str::<serde_json::Value>(s).unwrap());
+    assert_eq!(serde_json::from_value::<ParamAttributes>(serialized).unwrap(), attributes);
+}
+
+#[test]
+fn test_param_serialization() {
+    let s = r#"[
+        {"name": "a", "type": "uint8", "default": 7},
+        {"name": "b", "type": "map(address,tuple[])", "components": [
+            {"name": "c", "type": "optional(enum)", "variants": ["X", "Y"]},
+            {"name": "d", "type": "bytes", "desc": "Payload"}
+        ]},
+        {"name": "e", "type": "fixedbytes32"}
+    ]"#;
+
+    let params: Vec<Param> = serde_json::from_str(s).unwrap();
+    let attributes: Vec<ParamAttributes> = serde_json::from_str(s).unwrap();
+    let serialized = serde_json::to_value(
+        params.iter().zip(&attributes).map(|(param, attributes)| param.with_attributes(attributes)).collect::<Vec<_>>()
+    ).unwrap();
+    assert_eq!(serialized, serde_json::from_str::<serde_json::Value>(s).unwrap());
+    assert_eq!(serde_json::from_value::<Vec<Param>>(serialized).unwrap(), params);
+}
@@ -0,0 +1,62 @@
+/*
+* Copyright 2018-2020 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use std::sync::Arc;
+
+use ton_types::{BuilderData, IBitstring, SliceData, UInt256};
+
+use crate::{Contract, Token, TokenValue, Uint};
+use crate::contract::DecodedBody;
+use crate::registry::AbiRegistry;
+
+const ABI: &str = r#"{
+    "version": "2.2",
+    "header": ["time"],
+    "functions": [
+        {"name": "balance", "inputs": [], "outputs": [{"name": "value", "type": "uint128"}]}
+    ],
+    "events": []
+}"#;
+
+#[test]
+fn test_registry() {
+    let registry = AbiRegistry::new();
+    assert!(registry.is_empty());
+
+    let code_hash = UInt256::from_slice(&[1; 32]);
+    let contract = Contract::load(ABI.as_bytes()).unwrap();
+    assert!(registry.register(code_hash.clone(), contract.clone()).is_none());
+    assert_eq!(registry.len(), 1);
+    assert_eq!(registry.lookup(&code_hash).as_deref(), Some(&contract));
+    assert!(registry.lookup(&UInt256::from_slice(&[2; 32])).is_none());
+
+    let function = contract.function("balance").unwrap();
+    let mut body = BuilderData::new();
+    body.append_u32(function.get_output_id()).unwrap();
+    body.append_u128(7).unwrap();
+    let body = SliceData::load_builder(body).unwrap();
+
+    assert_eq!(
+        registry.decode_any(&code_hash, body.clone(), false).unwrap(),
+        Some(DecodedBody::FunctionOutput {
+            name: "balance".to_owned(),
+            tokens: vec![Token::new("value", TokenValue::Uint(Uint::new(7, 128)))],
+        })
+    );
+    assert_eq!(registry.decode_any(&UInt256::from_slice(&[2; 32]), body, false).unwrap(), None);
+
+    let previous = registry.register(code_hash.clone(), Arc::new(contract)).unwrap();
+    assert_eq!(previous.functions.len(), 1);
+    assert!(registry.unregister(&code_hash).is_some());
+    assert!(registry.is_empty());
+}
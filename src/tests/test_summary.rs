@@ -0,0 +1,61 @@
+/*
+* Copyright 2018-2020 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::Contract;
+
+const ABI: &str = r#"{
+    "version": "2.2",
+    "header": ["time", "expire"],
+    "functions": [
+        {"name": "transfer", "inputs": [{"name": "to", "type": "address"}], "outputs": []},
+        {"name": "balance", "id": "0x00000001", "inputs": [], "outputs": [{"name": "value", "type": "uint128"}]}
+    ],
+    "events": [
+        {"name": "Transferred", "id": "0x00000002", "inputs": [{"name": "amount", "type": "uint128"}]}
+    ],
+    "data": [
+        {"key": 1, "name": "nonce", "type": "uint32"}
+    ],
+    "fields": [
+        {"name": "_pubkey", "type": "uint256"},
+        {"name": "nonce", "type": "uint32", "init": true}
+    ]
+}"#;
+
+#[test]
+fn test_describe() {
+    let contract = Contract::load(ABI.as_bytes()).unwrap();
+    let summary = contract.describe();
+
+    assert_eq!(summary.header, vec!["time: time", "expire: expire"]);
+    assert_eq!(
+        summary.functions.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(),
+        vec!["transfer", "balance"]
+    );
+    let transfer = contract.function("transfer").unwrap();
+    assert_eq!(summary.functions[0].signature, transfer.get_function_signature());
+    assert_eq!(summary.functions[0].input_id, transfer.get_input_id());
+    assert_eq!(summary.events[0].inputs, vec!["amount: uint128"]);
+    assert_eq!(summary.data, vec![(1, "nonce: uint32".to_owned())]);
+    assert_eq!(
+        summary.fields,
+        vec![("_pubkey: uint256".to_owned(), false), ("nonce: uint32".to_owned(), true)]
+    );
+
+    let text = summary.to_string();
+    assert!(text.starts_with("ABI version: 2.2\nHeader: time: time, expire: expire\n"), "{}", text);
+    assert!(text.contains("  0x00000001 / 0x00000001  balance() -> (value: uint128)\n"), "{}", text);
+    assert!(text.contains("  0x00000002  Transferred(amount: uint128)\n"), "{}", text);
+    assert!(text.contains("  1  nonce: uint32\n"), "{}", text);
+    assert!(text.contains("  nonce: uint32 (init)\n"), "{}", text);
+}
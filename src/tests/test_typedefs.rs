@@ -0,0 +1,224 @@
+/*
+* Copyright 2018-2020 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::{AbiError, Contract, LoadOptions, Param, ParamAttributes, ParamType};
+
+const ABI: &str = r#"{
+    "version": "2.2",
+    "header": ["time"],
+    "types": {
+        "Amount": "uint128",
+        "Amounts": "Amount[]",
+        "Payload": {
+            "type": "tuple",
+            "components": [
+                {"name": "amount", "type": "Amount"},
+                {"name": "flag", "type": "bool"}
+            ]
+        }
+    },
+    "functions": [
+        {
+            "name": "transfer",
+            "inputs": [
+                {"name": "amount", "type": "Amount"},
+                {"name": "amounts", "type": "Amounts"},
+                {"name": "payloads", "type": "map(uint32,Payload)"}
+            ],
+            "outputs": [{"name": "payload", "type": "optional(Payload)"}]
+        }
+    ],
+    "events": [],
+    "fields": [{"name": "total", "type": "Amount"}]
+}"#;
+
+fn payload() -> ParamType {
+    ParamType::Tuple(vec![
+        Param::new("amount", ParamType::Uint(128)),
+        Param::new("flag", ParamType::Bool),
+    ])
+}
+
+#[test]
+fn test_type_aliases_expansion() {
+    let contract = Contract::load(ABI.as_bytes()).unwrap();
+    let function = contract.function("transfer").unwrap();
+
+    assert_eq!(function.inputs, vec![
+        Param::new("amount", ParamType::Uint(128)),
+        Param::new("amounts", ParamType::Array(Box::new(ParamType::Uint(128)))),
+        Param::new("payloads", ParamType::Map(Box::new(ParamType::Uint(32)), Box::new(payload()))),
+    ]);
+    assert_eq!(function.outputs, vec![Param::new("payload", ParamType::Optional(Box::new(payload())))]);
+    assert_eq!(contract.fields, vec![Param::new("total", ParamType::Uint(128))]);
+}
+
+#[test]
+fn test_type_aliases_from_load_options() {
+    let abi = r#"{
+        "version": "2.2",
+        "types": {"Amount": "uint64"},
+        "functions": [{
+            "name": "f",
+            "inputs": [{"name": "a", "type": "Amount"}, {"name": "b", "type": "Balance[]"}],
+            "outputs": []
+        }],
+        "events": []
+    }"#;
+    let load = |aliases: &[(&str, &str)]| Contract::load_with_options(abi.as_bytes(), LoadOptions {
+        type_aliases: aliases.iter().map(|(alias, kind)| (alias.to_string(), kind.to_string())).collect(),
+        ..Default::default()
+    });
+
+    // aliases declared in ABI take precedence
+    let contract = load(&[("Balance", "coins"), ("Amount", "uint128")]).unwrap();
+    assert_eq!(contract.function("f").unwrap().inputs, vec![
+        Param::new("a", ParamType::Uint(64)),
+        Param::new("b", ParamType::Array(Box::new(ParamType::VarUint(16)))),
+    ]);
+
+    // aliases are scoped to the load they are provided to
+    assert!(Contract::load(abi.as_bytes()).is_err());
+    assert!(load(&[("Balance", "unknown")]).is_err());
+    assert!(load(&[("uint8", "uint16")]).is_err());
+}
+
+#[test]
+fn test_type_aliases_errors() {
+    let load = |types: &str, kind: &str| Contract::load(format!(r#"{{
+        "version": "2.2",
+        "types": {},
+        "functions": [{{"name": "f", "inputs": [{{"name": "a", "type": "{}"}}], "outputs": []}}]
+    }}"#, types, kind).as_bytes());
+
+    assert!(load(r#"{"A": "B", "B": "A[]"}"#, "A").is_err());
+    assert!(load(r#"{"uint8": "uint16"}"#, "uint8").is_err());
+    assert!(load(r#"{"map": "uint16"}"#, "uint8").is_err());
+    assert!(load(r#"["uint16"]"#, "uint8").is_err());
+    assert!(load(
+        r#"{"T": {"type": "tuple", "components": [{"name": "a", "type": "bool"}]}}"#,
+        "map(uint8,T)[]"
+    ).is_ok());
+    let err = load(
+        r#"{"T": {"type": "tuple", "components": [{"name": "a", "type": "T[]"}]}}"#,
+        "T"
+    ).unwrap_err();
+    assert!(err.to_string().contains("Type alias T is recursive"), "{}", err);
+    assert!(load(
+        r#"{"T": {"type": "tuple", "components": [{"name": "a", "type": "bool"}]}}"#,
+        "map(T,T)"
+    ).is_err());
+}
+
+#[test]
+fn test_type_aliases_error_location() {
+    let load_error = |abi: &str| match Contract::load(abi.as_bytes()).unwrap_err().downcast::<AbiError>() {
+        Ok(AbiError::InvalidAbiJson { pointer, line, column, .. }) => (pointer, line, column),
+        other => panic!("unexpected error {:?}", other),
+    };
+
+    let abi = r#"{
+        "version": "2.2",
+        "types": {"A": "uint8", "B": 5},
+        "functions": [{"name": "f", "inputs": [{"name": "a", "type": "B"}], "outputs": []}]
+    }"#;
+    assert_eq!(load_error(abi), ("/types/B".to_owned(), 3, 38));
+
+    let abi = r#"{
+        "version": "2.2",
+        "structs": [{"name": "S", "components": [{"name": "x", "type": "uint7x"}]}],
+        "functions": [
+            {"name": "f", "inputs": [
+                {"name": "a", "type": "struct:Unknown"}
+            ], "outputs": []}
+        ]
+    }"#;
+    assert_eq!(load_error(abi), ("/functions/0/inputs/0".to_owned(), 6, 17));
+
+    let abi = abi.replace("struct:Unknown", "struct:S");
+    assert_eq!(load_error(&abi), ("/structs/0/components".to_owned(), 3, 49));
+}
+
+#[test]
+fn test_structs() {
+    let abi = r#"{
+        "version": "2.2",
+        "types": {"Amount": "uint128"},
+        "structs": [
+            {
+                "name": "TransferDetails",
+                "components": [
+                    {"name": "amount", "type": "Amount"},
+                    {"name": "meta", "type": "struct:Meta"}
+                ]
+            },
+            {"name": "Meta", "components": [{"name": "flag", "type": "bool"}]}
+        ],
+        "functions": [
+            {
+                "name": "transfer",
+                "inputs": [{"name": "details", "type": "struct:TransferDetails[]"}],
+                "outputs": []
+            }
+        ],
+        "events": []
+    }"#;
+    let contract = Contract::load(abi.as_bytes()).unwrap();
+
+    let meta = vec![Param::new("flag", ParamType::Bool)];
+    let details = vec![
+        Param::new("amount", ParamType::Uint(128)),
+        Param::new("meta", ParamType::Tuple(meta.clone())),
+    ];
+    let inputs = &contract.function("transfer").unwrap().inputs;
+    assert_eq!(
+        inputs,
+        &vec![Param::new("details", ParamType::Array(Box::new(ParamType::Tuple(details.clone()))))]
+    );
+    let attributes = contract.param_attributes("function transfer inputs details").unwrap();
+    assert_eq!(attributes.struct_name.as_deref(), Some("TransferDetails"));
+    assert_eq!(attributes.component(0).struct_name, None);
+    assert_eq!(attributes.component(1).struct_name.as_deref(), Some("Meta"));
+
+    assert_eq!(
+        contract.structs.iter().map(|item| item.name.as_str()).collect::<Vec<_>>(),
+        vec!["TransferDetails", "Meta"]
+    );
+    assert_eq!(contract.struct_def("Meta").unwrap().components, meta);
+
+    // tuples with the same layout declared without struct reference are not named
+    let plain = abi.replace("\"struct:Meta\"", "\"tuple\", \"components\": [{\"name\": \"flag\", \"type\": \"bool\"}]");
+    let contract = Contract::load(plain.as_bytes()).unwrap();
+    let details = contract.struct_def("TransferDetails").unwrap();
+    assert_eq!(details.components[1].kind, ParamType::Tuple(meta));
+    let plain_attributes = contract.param_attributes("function transfer inputs details").unwrap();
+    assert_eq!(plain_attributes.component(1).struct_name, None);
+
+    // struct name is written back on serialization
+    let serialized = serde_json::to_value(inputs[0].with_attributes(attributes)).unwrap();
+    assert_eq!(serialized["struct"], "TransferDetails");
+    assert_eq!(serde_json::from_value::<Param>(serialized.clone()).unwrap(), inputs[0]);
+    assert_eq!(&serde_json::from_value::<ParamAttributes>(serialized).unwrap(), attributes);
+
+    assert!(Contract::load(abi.replace("struct:Meta\"", "struct:Unknown\"").as_bytes()).is_err());
+    assert!(Contract::load(abi.replace("\"Meta\", \"components\"", "\"TransferDetails\", \"components\"").as_bytes()).is_err());
+
+    let recursive = abi.replace("\"type\": \"bool\"}]}", "\"type\": \"optional(struct:TransferDetails)\"}]}");
+    match Contract::load(recursive.as_bytes()).unwrap_err().downcast::<AbiError>() {
+        Ok(AbiError::InvalidAbiJson { pointer, msg, .. }) => {
+            assert_eq!(pointer, "/structs/0");
+            assert!(msg.contains("Struct TransferDetails is recursive"), "{}", msg);
+        }
+        other => panic!("unexpected error {:?}", other),
+    }
+}
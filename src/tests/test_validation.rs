@@ -0,0 +1,220 @@
+/*
+* Copyright 2018-2020 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::{AbiError, Contract, DataItem, LoadOptions, Param, ParamType};
+use crate::contract::AbiVersion;
+use crate::validation::ValidationIssue;
+
+const ABI: &str = r#"{
+    "version": "2.1",
+    "header": ["time", "expire"],
+    "functions": [
+        {"name": "a", "id": "0x00000001", "inputs": [], "outputs": []},
+        {"name": "b", "id": "0x00000001", "inputs": [], "outputs": []}
+    ],
+    "events": [
+        {"name": "e", "id": "0x00000001", "inputs": []}
+    ],
+    "data": [
+        {"key": 1, "name": "x", "type": "uint8"},
+        {"key": 1, "name": "y", "type": "uint8"}
+    ]
+}"#;
+
+#[test]
+fn test_validate() {
+    let mut contract = Contract::load(ABI.as_bytes()).unwrap();
+    contract.header.push(Param::new("time2", ParamType::Time));
+    contract.fields.push(Param::new("r", ParamType::Ref(Box::new(ParamType::Uint(8)))));
+    contract.fields.push(Param::new("t", ParamType::Array(Box::new(ParamType::Tuple(vec![])))));
+
+    let issues = contract.validate();
+    assert!(issues.contains(&ValidationIssue::DuplicateFunctionId {
+        id: 1,
+        names: vec!["a".to_owned(), "b".to_owned()],
+    }));
+    assert!(issues.contains(&ValidationIssue::EventFunctionIdCollision {
+        id: 1,
+        event: "e".to_owned(),
+        function: "a".to_owned(),
+    }));
+    assert!(issues.contains(&ValidationIssue::InvalidHeader {
+        msg: "duplicate param of type time".to_owned(),
+    }));
+    assert!(issues.contains(&ValidationIssue::UnsupportedType {
+        location: "fields r".to_owned(),
+        kind: "ref(uint8)".to_owned(),
+    }));
+    assert!(issues.contains(&ValidationIssue::EmptyTuple { location: "fields t".to_owned() }));
+    assert!(issues.contains(&ValidationIssue::DuplicateDataKey {
+        key: 1,
+        names: vec!["x".to_owned(), "y".to_owned()],
+    }));
+}
+
+#[test]
+fn test_validate_valid() {
+    let mut contract = Contract::load(ABI.as_bytes()).unwrap();
+    contract.functions.remove("b");
+    contract.events.clear();
+    contract.data.clear();
+    contract.data.insert("x".to_owned(), DataItem { key: 1, value: Param::new("x", ParamType::Uint(8)) });
+
+    assert_eq!(contract.validate(), vec![]);
+}
+
+#[test]
+fn test_load_id_collisions() {
+    let load = |abi: &str| Contract::load_with_options(abi.as_bytes(), LoadOptions {
+        deny_duplicates: true,
+        ..Default::default()
+    });
+    assert!(Contract::load(ABI.as_bytes()).is_ok());
+    assert!(load(ABI).is_err());
+
+    let abi = ABI.replace(r#""name": "b", "id": "0x00000001""#, r#""name": "b", "id": "0x00000002""#);
+    let err = load(&abi).unwrap_err().to_string();
+    assert!(err.contains("Event e id 0x00000001 is equal to function a output id"), "{}", err);
+
+    let abi = abi.replace(r#""name": "e", "id": "0x00000001""#, r#""name": "e", "id": "0x00000003""#);
+    assert!(load(&abi).is_ok());
+}
+
+#[test]
+fn test_validate_defaults() {
+    let abi = r#"{
+        "version": "2.1",
+        "functions": [
+            {
+                "name": "f",
+                "inputs": [
+                    {"name": "a", "type": "uint8", "default": 10},
+                    {"name": "b", "type": "uint8", "default": 1000},
+                    {"name": "c", "type": "bool", "default": "yes"}
+                ],
+                "outputs": []
+            }
+        ],
+        "events": []
+    }"#;
+    let contract = Contract::load(abi.as_bytes()).unwrap();
+    let attributes = contract.param_attributes("function f inputs a").unwrap();
+    assert_eq!(attributes.default, Some(serde_json::json!(10)));
+
+    let issues = contract.validate();
+    assert_eq!(issues.len(), 2, "{:?}", issues);
+    assert!(matches!(&issues[0], ValidationIssue::InvalidDefault { location, .. } if location == "function f inputs b"));
+    assert!(matches!(&issues[1], ValidationIssue::InvalidDefault { location, .. } if location == "function f inputs c"));
+}
+
+#[test]
+fn test_load_type_sizes() {
+    let load = |kind: &str, options: LoadOptions| Contract::load_with_options(format!(r#"{{
+        "version": "2.1",
+        "functions": [{{"name": "f", "inputs": [{{"name": "a", "type": "{}"}}], "outputs": []}}],
+        "events": []
+    }}"#, kind).as_bytes(), options);
+
+    let strict = || LoadOptions { deny_experimental_sizes: true, ..Default::default() };
+    for kind in ["uint1", "uint256", "int257", "varuint4", "varint32", "map(uint8,int257[])"] {
+        assert!(load(kind, strict()).is_ok(), "{}", kind);
+    }
+    for kind in ["uint0", "uint257", "int258", "varuint2", "varint33", "optional(uint300)", "map(int0,bool)"] {
+        let err = load(kind, strict()).unwrap_err();
+        assert!(
+            matches!(err.downcast_ref::<AbiError>(), Some(AbiError::InvalidTypeSize { location, .. }) if location == "function f inputs a"),
+            "{}: {}", kind, err
+        );
+        assert!(load(kind, LoadOptions::default()).is_ok(), "{}", kind);
+    }
+}
+
+#[test]
+fn test_load_abi_version_override() {
+    let abi = r#"{
+        "version": "2.1",
+        "header": ["time"],
+        "functions": [{"name": "f", "inputs": [{"name": "a", "type": "uint8"}], "outputs": []}],
+        "events": [],
+        "fields": [{"name": "b", "type": "string"}]
+    }"#;
+    let load = |version: &str| Contract::load_with_options(abi.as_bytes(), LoadOptions {
+        abi_version: Some(AbiVersion::parse(version).unwrap()),
+        ..Default::default()
+    });
+
+    let contract = load("2.3").unwrap();
+    assert_eq!(contract.abi_version, AbiVersion::parse("2.3").unwrap());
+    assert_eq!(contract.function("f").unwrap().abi_version, AbiVersion::parse("2.3").unwrap());
+    assert!(load("2.0").is_err());
+}
+
+#[test]
+fn test_load_json_error_location() {
+    let load_error = |abi: &str| match Contract::load(abi.as_bytes()).unwrap_err().downcast::<AbiError>() {
+        Ok(AbiError::InvalidAbiJson { pointer, line, column, msg }) => (pointer, line, column, msg),
+        other => panic!("unexpected error {:?}", other),
+    };
+
+    let (pointer, line, column, _) = load_error("{\n  \"version\": \"2.2\",\n  \"functions\": [\n}");
+    assert_eq!((pointer.as_str(), line, column), ("", 4, 1));
+
+    let abi = r#"{
+        "version": "2.2",
+        "functions": [
+            {"name": "f", "inputs": [], "outputs": []},
+            {"name": "g", "outputs": [], "inputs": [
+                {"name": "a", "type": "tuple", "components": [{"name": "x", "type": "bool"}, {"name": "y", "type": "uint7x"}]}
+            ]}
+        ]
+    }"#;
+    let (pointer, line, _, msg) = load_error(abi);
+    assert_eq!(pointer, "/functions/1/inputs/0/components/1");
+    assert_eq!(line, 6);
+    assert!(msg.contains("uint7x"), "{}", msg);
+
+    let abi = r#"{
+        "version": "2.2",
+        "functions": [],
+        "events": [{"inputs": []}]
+    }"#;
+    let (pointer, line, _, msg) = load_error(abi);
+    assert_eq!(pointer, "/events/0");
+    assert_eq!(line, 4);
+    assert!(msg.contains("name"), "{}", msg);
+
+    let abi = r#"{
+        "version": "2.2",
+        "types": {"Amount": "uint128"},
+        "functions": [{"name": "f", "inputs": [{"name": "a", "type": "Amount"}],
+            "outputs": [{"type": "bool"}]}]
+    }"#;
+    let (pointer, line, column, _) = load_error(abi);
+    assert_eq!(pointer, "/functions/0/outputs/0");
+    assert_eq!((line, column), (5, 25));
+
+    // element of expanded alias is located at param referencing it
+    let abi = r#"{
+        "version": "2.2",
+        "types": {"S": {"type": "tuple", "components": [{"name": "x", "type": "uint7x"}]}},
+        "functions": [{"name": "f", "outputs": [],
+            "inputs": [{"name": "a", "type": "S"}]}]
+    }"#;
+    let (pointer, line, column, _) = load_error(abi);
+    assert_eq!(pointer, "/functions/0/inputs/0/components/0");
+    assert_eq!((line, column), (5, 24));
+
+    // errors of the root object are located at its end
+    let (pointer, line, column, _) = load_error("{\n  \"version\": \"2.4\"\n}");
+    assert_eq!((pointer.as_str(), line, column), ("", 3, 1));
+}
@@ -185,7 +185,7 @@ fn test_abi_parse() {
             key: 100
         });
 
-    let expected_contract = Contract {
+    let mut expected_contract = Contract {
         abi_version: 1.into(),
         header,
         functions,
@@ -194,7 +194,14 @@ fn test_abi_parse() {
         fields: vec![],
         init_fields: Default::default(),
         getters: Default::default(),
+        function_input_ids: Default::default(),
+        function_output_ids: Default::default(),
+        event_ids: Default::default(),
+        order: Default::default(),
+        structs: vec![],
+        param_attributes: Default::default(),
     };
+    expected_contract.build_id_indexes();
 
     assert_eq!(parsed_contract, expected_contract);
 }
@@ -20,6 +20,8 @@ use smallvec::smallvec;
 use crate::contract::ABI_VERSION_2_2;
 use crate::{Function, Param, ParamType, Token, Uint};
 use crate::json_abi::*;
+use crate::error::IntoAbiResult;
+use crate::warnings::{Warning, Warnings};
 
 const WALLET_ABI: &str = r#"{
     "ABI version": 2,
@@ -283,6 +285,168 @@ fn test_signed_call() {
     assert_eq!(response.function_name, "createArbitraryLimit");
 }
 
+#[test]
+fn test_decode_function_response_partial() {
+    let mut response = BuilderData::new();
+    response.append_u32(0xA238B58A).unwrap();   // function id
+    response.append_u64(5).unwrap();            // value0
+    response.append_u32(0xFFFF).unwrap();       // data added by newer contract version
+    let response = SliceData::load_builder(response).unwrap();
+
+    assert!(decode_function_response(WALLET_ABI, "createArbitraryLimit", response.clone(), false).is_err());
+    assert_eq!(
+        decode_function_response_partial(WALLET_ABI, "createArbitraryLimit", response, false).unwrap(),
+        r#"{"value0":"5"}"#
+    );
+}
+
+#[test]
+fn test_warnings() {
+    let mut warnings = Warnings::new();
+    encode_function_call_with_options(
+        WALLET_ABI,
+        "createArbitraryLimit",
+        r#"{"value":"1","period":"2","comment":"extra"}"#,
+        CallOptions::new().header(r#"{"expire":123}"#).warnings(&mut warnings),
+    ).unwrap();
+    assert_eq!(warnings.into_vec(), vec![
+        Warning::IgnoredField { path: "comment".to_owned() },
+        Warning::DefaultedHeaderValue { name: "pubkey".to_owned(), kind: "pubkey".to_owned() },
+    ]);
+
+    // generated `expire` is not reported as defaulted
+    let mut warnings = Warnings::new();
+    encode_function_call_with_options(
+        WALLET_ABI,
+        "createArbitraryLimit",
+        r#"{"value":"1","period":"2"}"#,
+        CallOptions::new().warnings(&mut warnings),
+    ).unwrap();
+    assert_eq!(warnings.into_vec(), vec![
+        Warning::DefaultedHeaderValue { name: "pubkey".to_owned(), kind: "pubkey".to_owned() },
+    ]);
+
+    let mut response = BuilderData::new();
+    response.append_u32(0xA238B58A).unwrap();   // function id
+    response.append_u64(5).unwrap();            // value0
+    response.append_u32(0xFFFF).unwrap();       // data added by newer contract version
+    let response = SliceData::load_builder(response).unwrap();
+
+    let mut warnings = Warnings::new();
+    decode_function_response_with_warnings(
+        WALLET_ABI, "createArbitraryLimit", response, false, true, &mut warnings
+    ).unwrap();
+    assert_eq!(warnings.into_vec(), vec![Warning::TrailingDataIgnored { bits: 32, refs: 0 }]);
+
+    let abi = r#"{
+        "version": "2.2",
+        "functions": [{
+            "name": "pay",
+            "inputs": [{"name":"order","type":"tuple","components":[
+                {"name":"amount","type":"gram"},
+                {"name":"memo","type":"string"}
+            ]}],
+            "outputs": []
+        }]
+    }"#;
+    let mut warnings = Warnings::new();
+    encode_function_call_with_options(
+        abi,
+        "pay",
+        r#"{"order":{"amount":"1","memo":"","note":""}}"#,
+        CallOptions::new().internal(true).warnings(&mut warnings),
+    ).unwrap();
+    assert_eq!(warnings.into_vec(), vec![
+        Warning::IgnoredField { path: "order.note".to_owned() },
+        Warning::DeprecatedType {
+            location: "function pay input order amount".to_owned(),
+            kind: "gram".to_owned(),
+            replacement: "varuint16".to_owned(),
+        },
+    ]);
+
+    // warnings are not collected unless requested
+    assert!(encode_function_call_with_options(abi, "pay", r#"{"order":{"amount":"1","memo":""}}"#,
+        CallOptions::new().internal(true)).is_ok());
+}
+
+#[test]
+fn test_typed_errors() {
+    let err = decode_function_response(WALLET_ABI, "unknown", SliceData::default(), false).unwrap_err();
+    assert!(matches!(&err, AbiError::InvalidName { name } if name == "unknown"));
+    assert_eq!(err.code(), 3);
+
+    let abi = r#"{
+        "version": "2.2",
+        "functions": [],
+        "fields": [{"name":"values","type":"uint32[]"}]
+    }"#;
+    let tokens = vec![Token::new("values", TokenValue::Array(ParamType::Uint(8), vec![
+        TokenValue::Uint(Uint::new(1, 8)),
+    ]))];
+    let data = TokenValue::pack_values_into_chain(&tokens, vec![], &ABI_VERSION_2_2).unwrap();
+    let err = decode_storage_fields(abi, SliceData::load_builder(data).unwrap(), false).unwrap_err();
+    assert!(matches!(&err, AbiError::InParam { path, .. } if path == "values[0]"), "{}", err);
+    assert_eq!(err.code(), err.unwrap_param().code());
+
+    // typed errors can still be propagated in code using `ton_types::Result`
+    let compat = || -> ton_types::Result<String> {
+        Ok(decode_function_response(WALLET_ABI, "unknown", SliceData::default(), false)?)
+    };
+    assert_eq!(AbiError::code_of(&compat().unwrap_err()), Some(3));
+
+    // functions returning `ton_types::Result` are kept for one release cycle
+    #[allow(deprecated)]
+    let err = compat::decode_function_response(WALLET_ABI, "unknown", SliceData::default(), false).unwrap_err();
+    assert_eq!(AbiError::code_of(&err), Some(3));
+
+    // errors of other crates don't expose `anyhow` in typed error
+    let err = AbiError::from(ton_types::error!("cell overflow"));
+    assert!(matches!(&err, AbiError::Other(_)));
+    assert_eq!(err.code(), 32);
+    assert_eq!(err.to_string(), "cell overflow");
+
+    let err: AbiError = Contract::load(r#"{"functions": []}"#.as_bytes()).into_abi_result().unwrap_err();
+    assert!(matches!(err, AbiError::InvalidVersion(_)));
+}
+
+#[test]
+fn test_decode_tokens_variants() {
+    let mut response = BuilderData::new();
+    response.append_u32(0xA238B58A).unwrap();
+    response.append_u64(5).unwrap();
+    let response = SliceData::load_builder(response).unwrap();
+
+    assert_eq!(
+        decode_function_response_tokens(WALLET_ABI, "createArbitraryLimit", response, false, false).unwrap(),
+        vec![Token::new("value0", TokenValue::Uint(Uint::new(5, 64)))]
+    );
+
+    let mut storage = BuilderData::new();
+    storage.append_u32(7).unwrap();
+    storage.append_bit_one().unwrap();
+    let storage = SliceData::load_builder(storage).unwrap();
+    let abi = r#"{
+        "version": "2.1",
+        "functions": [],
+        "fields": [{"name":"a","type":"uint32"},{"name":"b","type":"bool"}]
+    }"#;
+
+    let tokens = decode_storage_fields_tokens(abi, storage.clone(), false).unwrap();
+    assert_eq!(tokens, vec![
+        Token::new("a", TokenValue::Uint(Uint::new(7, 32))),
+        Token::new("b", TokenValue::Bool(true)),
+    ]);
+    assert_eq!(
+        decode_storage_fields(abi, storage.clone(), false).unwrap(),
+        Detokenizer::detokenize(&tokens).unwrap()
+    );
+    assert_eq!(
+        decode_selected_storage_fields_tokens(abi, storage, &["b"]).unwrap(),
+        vec![Token::new("b", TokenValue::Bool(true))]
+    );
+}
+
 #[test]
 fn test_not_signed_call() {
     let params = r#"{
@@ -317,6 +481,149 @@ fn test_not_signed_call() {
     assert_eq!(test_tree, expected_tree);
 }
 
+#[test]
+fn test_decode_call_with_header() {
+    let header = r#"{
+        "pubkey": "11c0a428b6768562df09db05326595337dbb5f8dde0e128224d4df48df760f17",
+        "expire": 123
+    }"#;
+    let body = encode_function_call(
+        WALLET_ABI, "getLimit", Some(header), r#"{"limitId": "0x2"}"#, false, None, None
+    ).unwrap();
+    let body = SliceData::load_builder(body).unwrap();
+
+    let expected_header = serde_json::json!({
+        "expire": 123,
+        "pubkey": "11c0a428b6768562df09db05326595337dbb5f8dde0e128224d4df48df760f17",
+    });
+    let check = |decoded: DecodedMessage| {
+        assert_eq!(decoded.function_name, "getLimit");
+        assert_eq!(decoded.params, r#"{"limitId":"2"}"#);
+        assert_eq!(
+            serde_json::from_str::<Value>(&decoded.header.unwrap()).unwrap(),
+            expected_header
+        );
+    };
+    check(decode_unknown_function_call_with_header(WALLET_ABI, body.clone(), false, false).unwrap());
+    check(decode_function_call_with_header(WALLET_ABI, "getLimit", body.clone(), false, false).unwrap());
+
+    assert!(decode_unknown_function_call(WALLET_ABI, body, false, false).unwrap().header.is_none());
+}
+
+#[test]
+fn test_decode_header() {
+    let header = r#"{
+        "pubkey": "11c0a428b6768562df09db05326595337dbb5f8dde0e128224d4df48df760f17",
+        "expire": 123
+    }"#;
+    let body = encode_function_call(
+        WALLET_ABI, "getLimit", Some(header), r#"{"limitId": "0x2"}"#, false, None, None
+    ).unwrap();
+    let body = SliceData::load_builder(body).unwrap();
+
+    let id = Contract::load(WALLET_ABI.as_bytes()).unwrap().function("getLimit").unwrap().get_input_id();
+    let expected = serde_json::json!({
+        "header": {
+            "expire": 123,
+            "pubkey": "11c0a428b6768562df09db05326595337dbb5f8dde0e128224d4df48df760f17",
+        },
+        "function_id": format!("0x{:08x}", id),
+        "function_name": "getLimit",
+    });
+
+    assert_eq!(decode_header_to_json_value(WALLET_ABI, body.clone(), false).unwrap(), expected);
+    assert_eq!(
+        serde_json::from_str::<Value>(&decode_header(WALLET_ABI, body, false).unwrap()).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn test_boc_helpers() {
+    let header = r#"{"expire": 123}"#;
+    let params = r#"{"limitId":"2"}"#;
+    let boc = encode_function_call_to_boc(WALLET_ABI, "getLimit", Some(header), params, false, None, None).unwrap();
+
+    let body = encode_function_call(WALLET_ABI, "getLimit", Some(header), params, false, None, None).unwrap();
+    let cell = deserialize_tree_of_cells(&mut base64::decode(&boc).unwrap().as_slice()).unwrap();
+    assert_eq!(cell, body.into_cell().unwrap());
+
+    let decoded = decode_function_call_from_boc(WALLET_ABI, &boc, false, false).unwrap();
+    assert_eq!(decoded.function_name, "getLimit");
+    assert_eq!(decoded.params, params);
+
+    assert!(decode_function_call_from_boc(WALLET_ABI, "not base64!", false, false).is_err());
+}
+
+#[test]
+fn test_decode_call_or_event() {
+    use crate::contract::MessageBodyKind;
+
+    let body = encode_function_call(WALLET_ABI, "getLimit", None, r#"{"limitId":"2"}"#, true, None, None).unwrap();
+    let (kind, decoded) = decode_unknown_function_call_or_event(
+        WALLET_ABI, SliceData::load_builder(body).unwrap(), true, false
+    ).unwrap();
+    assert_eq!(kind, MessageBodyKind::FunctionInput);
+    assert_eq!(decoded.function_name, "getLimit");
+
+    let body = SliceData::load_builder(
+        BuilderData::with_bitstring(smallvec![0x0C, 0xAF, 0x24, 0xBE, 0xFF, 0x80]).unwrap()
+    ).unwrap();
+    assert!(decode_unknown_function_call(WALLET_ABI, body.clone(), true, false).is_err());
+    let (kind, decoded) = decode_unknown_function_call_or_event(WALLET_ABI, body, true, false).unwrap();
+    assert_eq!(kind, MessageBodyKind::Event);
+    assert_eq!(decoded.function_name, "event");
+    assert_eq!(decoded.params, r#"{"param":"255"}"#);
+}
+
+#[test]
+fn test_encode_function_call_with_options() {
+    let pair = crate::signature::test_utils::keypair(3);
+    let header = r#"{"expire": 123}"#;
+    let params = r#"{"limitId":"2"}"#;
+
+    let body = encode_function_call_with_options(
+        WALLET_ABI,
+        "getLimit",
+        params,
+        CallOptions::new().header(header).signer(&pair).signature_id(7),
+    ).unwrap();
+    let expected = encode_function_call(
+        WALLET_ABI, "getLimit", Some(header), params, false, Some((&pair, Some(7))), None
+    ).unwrap();
+    assert_eq!(body, expected);
+
+    let body = encode_function_call_with_options(
+        WALLET_ABI, "getLimit", params, CallOptions::new().internal(true)
+    ).unwrap();
+    let expected = encode_function_call(WALLET_ABI, "getLimit", None, params, true, None, None).unwrap();
+    assert_eq!(body, expected);
+}
+
+#[test]
+fn test_encode_function_call_abi_version_override() {
+    let header = r#"{"expire": 123}"#;
+    let params = r#"{"limitId":"2"}"#;
+
+    let body = encode_function_call_with_options(
+        WALLET_ABI, "getLimit", params, CallOptions::new().header(header).abi_version("2.3")
+    ).unwrap();
+    let abi = WALLET_ABI.replacen(r#""ABI version": 2,"#, r#""ABI version": 2, "version": "2.3","#, 1);
+    let expected = encode_function_call(&abi, "getLimit", Some(header), params, false, None, None).unwrap();
+    assert_eq!(body, expected);
+
+    let abi = r#"{
+        "version": "2.4",
+        "functions": [{"name": "f", "inputs": [{"name": "a", "type": "optional(uint8)"}], "outputs": []}]
+    }"#;
+    let encode = |version| encode_function_call_with_options(
+        abi, "f", r#"{"a": null}"#, CallOptions::new().internal(true).abi_version(version)
+    );
+    assert!(encode("2.2").is_ok());
+    assert!(encode("2.0").is_err());
+    assert!(encode("3.0").is_err());
+}
+
 #[test]
 fn test_add_signature_full() {
     let params = r#"{"limitId":"2"}"#;
@@ -350,6 +657,40 @@ fn test_add_signature_full() {
     assert_eq!(decoded.params, params);
 }
 
+#[test]
+fn test_verify_function_call() {
+    let params = r#"{"limitId":"2"}"#;
+    let pair = Keypair::generate(&mut rand::thread_rng());
+    let other_pair = Keypair::generate(&mut rand::thread_rng());
+    let address = "0:1111111111111111111111111111111111111111111111111111111111111111";
+
+    let body = encode_function_call(
+        WALLET_ABI, "getLimit", None, params, false, Some((&pair, Some(7))), None
+    ).unwrap();
+    let body = SliceData::load_builder(body).unwrap();
+
+    let pubkey = pair.public.to_bytes();
+    assert!(verify_function_call(WALLET_ABI, body.clone(), &pubkey, None, Some(7)).unwrap());
+    assert!(!verify_function_call(WALLET_ABI, body.clone(), &pubkey, None, None).unwrap());
+    assert!(!verify_function_call(WALLET_ABI, body, &other_pair.public.to_bytes(), None, Some(7)).unwrap());
+
+    // since ABI 2.3 destination address is a part of signed data
+    let abi = WALLET_ABI.replace(r#""ABI version": 2,"#, r#""version": "2.3","#);
+    let body = encode_function_call(
+        &abi, "getLimit", None, params, false, Some((&pair, None)), Some(address.to_owned())
+    ).unwrap();
+    let body = SliceData::load_builder(body).unwrap();
+
+    assert!(verify_function_call(&abi, body.clone(), &pubkey, Some(address.to_owned()), None).unwrap());
+    assert!(!verify_function_call(&abi, body.clone(), &pubkey, Some(address.replace('1', "2")), None).unwrap());
+    assert!(verify_function_call(&abi, body, &pubkey, None, None).is_err());
+
+    let unsigned = encode_function_call(WALLET_ABI, "getLimit", None, params, false, None, None).unwrap();
+    assert!(!verify_function_call(
+        WALLET_ABI, SliceData::load_builder(unsigned).unwrap(), &pubkey, None, None
+    ).unwrap());
+}
+
 #[test]
 fn test_find_event() {
     let event_tree = SliceData::load_builder(
@@ -363,6 +704,256 @@ fn test_find_event() {
     assert_eq!(decoded.params, r#"{"param":"255"}"#);
 }
 
+#[test]
+fn test_try_decode_unknown_response() {
+    let event_tree = SliceData::load_builder(
+        BuilderData::with_bitstring(smallvec![0x0C, 0xAF, 0x24, 0xBE, 0xFF, 0x80]).unwrap()
+    ).unwrap();
+
+    match try_decode_unknown_function_response(WALLET_ABI, event_tree, false).unwrap() {
+        DecodedResponse::Decoded(decoded) => {
+            assert_eq!(decoded.function_name, "event");
+            assert_eq!(decoded.params, r#"{"param":"255"}"#);
+        }
+        DecodedResponse::Unknown { .. } => panic!("event is not decoded"),
+    }
+
+    let unknown_tree = SliceData::load_builder(
+        BuilderData::with_bitstring(smallvec![0x12, 0x34, 0x56, 0x78, 0xFF, 0x80]).unwrap()
+    ).unwrap();
+    assert!(decode_unknown_function_response(WALLET_ABI, unknown_tree.clone(), false).is_err());
+    match try_decode_unknown_function_response(WALLET_ABI, unknown_tree.clone(), false).unwrap() {
+        DecodedResponse::Unknown { id, body } => {
+            assert_eq!(id, 0x12345678);
+            assert_eq!(body, unknown_tree);
+        }
+        DecodedResponse::Decoded(_) => panic!("unknown body is decoded"),
+    }
+}
+
+#[test]
+fn test_encode_event_body() {
+    let contract = Contract::load(WALLET_ABI.as_bytes()).unwrap();
+    let event = contract.events.get("event").unwrap();
+
+    let inputs = vec![Token::new("param", TokenValue::Uint(Uint::new(255, 8)))];
+    let body = event.encode_message_body(&inputs).unwrap();
+
+    let expected = BuilderData::with_bitstring(smallvec![0x0C, 0xAF, 0x24, 0xBE, 0xFF, 0x80]).unwrap();
+    assert_eq!(body, expected);
+    assert_eq!(event.decode_input(SliceData::load_builder(body).unwrap()).unwrap(), inputs);
+
+    assert!(event.encode_message_body(&[]).is_err());
+}
+
+#[test]
+fn test_encode_external_message() {
+    let contract = Contract::load(WALLET_ABI.as_bytes()).unwrap();
+    let function = contract.function("createArbitraryLimit").unwrap();
+    let pair = Keypair::generate(&mut rand::thread_rng());
+    let dst = MsgAddressInt::with_standart(None, 0, [0x11; 32].into()).unwrap();
+
+    let inputs = vec![
+        Token::new("value", TokenValue::Uint(Uint::new(12, 128))),
+        Token::new("period", TokenValue::Uint(Uint::new(30, 32))),
+    ];
+
+    let message = function.encode_external_message(
+        dst.clone(),
+        &HashMap::new(),
+        &inputs,
+        Some((&pair, None)),
+        None,
+    ).unwrap();
+
+    assert_eq!(message.dst(), Some(dst));
+    let tokens = function.decode_input(message.body().unwrap(), false, false).unwrap();
+    assert_eq!(tokens, inputs);
+}
+
+#[test]
+fn test_encode_internal_message() {
+    let contract = Contract::load(WALLET_ABI.as_bytes()).unwrap();
+    let function = contract.function("createArbitraryLimit").unwrap();
+    let dst = MsgAddressInt::with_standart(None, 0, [0x11; 32].into()).unwrap();
+
+    let inputs = vec![
+        Token::new("value", TokenValue::Uint(Uint::new(12, 128))),
+        Token::new("period", TokenValue::Uint(Uint::new(30, 32))),
+    ];
+
+    let message = function.encode_internal_message(dst.clone(), 1_000_000_000, true, true, &inputs)
+        .unwrap();
+
+    assert_eq!(message.dst(), Some(dst));
+    assert_eq!(message.get_value().unwrap().grams, 1_000_000_000u64.into());
+    let tokens = function.decode_input(message.body().unwrap(), true, false).unwrap();
+    assert_eq!(tokens, inputs);
+}
+
+#[test]
+fn test_id_helpers() {
+    let contract = Contract::load(WALLET_ABI.as_bytes()).unwrap();
+    let function = contract.function("getLimit").unwrap();
+
+    assert_eq!(get_function_id(WALLET_ABI, "getLimit").unwrap(), function.get_input_id());
+    assert_eq!(get_function_signature(WALLET_ABI, "getLimit").unwrap(), function.get_function_signature());
+    assert_eq!(get_event_id(WALLET_ABI, "event").unwrap(), contract.event("event").unwrap().get_id());
+
+    assert!(get_function_id(WALLET_ABI, "event").is_err());
+    assert!(get_event_id(WALLET_ABI, "getLimit").is_err());
+    assert!(get_function_signature(WALLET_ABI, "unknown").is_err());
+}
+
+#[test]
+fn test_encode_internal_message_boc() {
+    let dst = MsgAddressInt::with_standart(None, 0, [0x11; 32].into()).unwrap();
+    let params = r#"{"value":"12","period":"30"}"#;
+
+    let boc = encode_internal_message(
+        WALLET_ABI, "createArbitraryLimit", params, 1_000_000_000, true, &dst.to_string()
+    ).unwrap();
+    let message = ton_block::Message::construct_from_base64(&boc).unwrap();
+
+    assert_eq!(message.dst(), Some(dst.clone()));
+    assert_eq!(message.get_value().unwrap().grams, 1_000_000_000u64.into());
+    let decoded = decode_function_call_with_header(
+        WALLET_ABI, "createArbitraryLimit", message.body().unwrap(), true, false
+    ).unwrap();
+    assert_eq!(decoded.params, params);
+
+    assert!(encode_internal_message(WALLET_ABI, "createArbitraryLimit", params, 1, true, "not address").is_err());
+}
+
+#[test]
+fn test_decode_candidates() {
+    let event_tree = SliceData::load_builder(
+        BuilderData::with_bitstring(
+            smallvec![0x0C, 0xAF, 0x24, 0xBE, 0xFF, 0x80])
+        .unwrap()).unwrap();
+
+    let contracts = vec![
+        Contract::load(ABI_WITH_FIELDS.as_bytes()).unwrap(),
+        Contract::load(WALLET_ABI.as_bytes()).unwrap(),
+    ];
+
+    let candidates = Contract::decode_candidates(&contracts, event_tree, false);
+
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].contract_index, 1);
+    assert_eq!(candidates[0].kind, crate::contract::MessageBodyKind::Event);
+    assert_eq!(candidates[0].name, "event");
+    assert!(candidates[0].complete);
+}
+
+#[test]
+fn test_decode_any() {
+    use crate::contract::DecodedBody;
+
+    let contract = Contract::load(WALLET_ABI.as_bytes()).unwrap();
+    let function = contract.function("createArbitraryLimit").unwrap();
+
+    let inputs = vec![
+        Token::new("value", TokenValue::Uint(Uint::new(12, 128))),
+        Token::new("period", TokenValue::Uint(Uint::new(30, 32))),
+    ];
+    let mut header = HashMap::new();
+    header.insert("expire".to_owned(), TokenValue::Expire(123));
+    let body = function.encode_input(&header, &inputs, false, None, None).unwrap();
+
+    assert_eq!(
+        contract.decode_any(SliceData::load_builder(body).unwrap(), false).unwrap(),
+        DecodedBody::FunctionInput {
+            name: "createArbitraryLimit".to_owned(),
+            header: vec![
+                Token::new("expire", TokenValue::Expire(123)),
+                Token::new("pubkey", TokenValue::PublicKey(None)),
+            ],
+            tokens: inputs,
+        }
+    );
+
+    let mut response = BuilderData::new();
+    response.append_u32(function.get_output_id()).unwrap();
+    response.append_u64(5).unwrap();
+    assert_eq!(
+        contract.decode_any(SliceData::load_builder(response).unwrap(), false).unwrap(),
+        DecodedBody::FunctionOutput {
+            name: "createArbitraryLimit".to_owned(),
+            tokens: vec![Token::new("value0", TokenValue::Uint(Uint::new(5, 64)))],
+        }
+    );
+
+    let event_tree = SliceData::load_builder(
+        BuilderData::with_bitstring(smallvec![0x0C, 0xAF, 0x24, 0xBE, 0xFF, 0x80]).unwrap()
+    ).unwrap();
+    assert_eq!(
+        contract.decode_any(event_tree, false).unwrap(),
+        DecodedBody::Event {
+            name: "event".to_owned(),
+            tokens: vec![Token::new("param", TokenValue::Uint(Uint::new(255, 8)))],
+        }
+    );
+
+    let mut unknown = BuilderData::new();
+    unknown.append_u32(0x12345678).unwrap();
+    unknown.append_u8(0xAB).unwrap();
+    match contract.decode_any(SliceData::load_builder(unknown).unwrap(), true).unwrap() {
+        DecodedBody::Unknown { id, mut remainder } => {
+            assert_eq!(id, 0x12345678);
+            assert_eq!(remainder.get_next_byte().unwrap(), 0xAB);
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn test_decode_event_filtered() {
+    use crate::contract::EventFilter;
+    use std::collections::HashSet;
+
+    let contract = Contract::load(WALLET_ABI.as_bytes()).unwrap();
+    let event_tree = SliceData::load_builder(
+        BuilderData::with_bitstring(smallvec![0x0C, 0xAF, 0x24, 0xBE, 0xFF, 0x80]).unwrap()
+    ).unwrap();
+    let event_id = 0x0CAF24BE;
+
+    let decoded = contract
+        .decode_event_filtered(event_tree.clone(), &HashSet::from([event_id]))
+        .unwrap()
+        .unwrap();
+    assert_eq!(decoded.function_name, "event");
+    assert!(contract.decode_event_filtered(event_tree.clone(), &HashSet::from([1])).unwrap().is_none());
+
+    let filter = EventFilter::with_names(&contract, &["event"]).unwrap();
+    assert!(filter.contains(event_id));
+    assert_eq!(
+        filter.decode(event_tree.clone()).unwrap().unwrap().tokens,
+        vec![Token::new("param", TokenValue::Uint(Uint::new(255, 8)))]
+    );
+    assert!(EventFilter::with_names(&contract, &["unknown"]).is_err());
+
+    let filter = EventFilter::new(&contract, &HashSet::from([1]));
+    assert!(filter.decode(event_tree).unwrap().is_none());
+}
+
+#[test]
+fn test_decode_event_partial() {
+    let contract = Contract::load(WALLET_ABI.as_bytes()).unwrap();
+    let event = contract.events.get("event").unwrap();
+
+    let mut body = BuilderData::new();
+    body.append_u32(event.get_id()).unwrap();
+    body.append_u8(255).unwrap();
+    body.append_u8(0x42).unwrap();
+    let body = SliceData::load_builder(body).unwrap();
+
+    assert!(event.decode_input(body.clone()).is_err());
+    let (tokens, mut cursor) = event.decode_input_partial(body).unwrap();
+    assert_eq!(tokens, vec![Token::new("param", TokenValue::Uint(Uint::new(255, 8)))]);
+    assert_eq!(cursor.slice.get_next_byte().unwrap(), 0x42);
+}
+
 #[test]
 fn test_store_pubkey() {
     let mut test_map = HashmapE::with_bit_len(Contract::DATA_MAP_KEYLEN);
@@ -390,6 +981,7 @@ fn test_store_pubkey() {
 
 #[test]
 fn decode_params() {
+    use crate::contract::DecodedBody;
 
     let abi = r#"{"ABI version":2,"data":[{"key":1,"name":"_randomNonce","type":"uint256"}],"events":[{"inputs":[{"name":"previousOwner","type":"uint256"},{"name":"newOwner","type":"uint256"}],"name":"OwnershipTransferred","outputs":[]}],"fields":[{"name":"_pubkey","type":"uint256"},{"name":"_timestamp","type":"uint64"},{"name":"_constructorFlag","type":"bool"},{"name":"owner","type":"uint256"},{"name":"_randomNonce","type":"uint256"}],"functions":[{"inputs":[{"name":"dest","type":"address"},{"name":"value","type":"uint128"},{"name":"bounce","type":"bool"},{"name":"flags","type":"uint8"},{"name":"payload","type":"cell"}],"name":"sendTransaction","outputs":[]},{"inputs":[{"name":"newOwner","type":"uint256"}],"name":"transferOwnership","outputs":[]},{"inputs":[],"name":"constructor","outputs":[]},{"inputs":[],"name":"owner","outputs":[{"name":"owner","type":"uint256"}]},{"inputs":[],"name":"_randomNonce","outputs":[{"name":"_randomNonce","type":"uint256"}]}],"header":["time"],"version":"2.2"}"#;
     let abi = Contract::load(abi.as_bytes()).unwrap();
@@ -397,7 +989,7 @@ fn decode_params() {
 
     let tx = "te6ccgECCwEAAm8AA7V++NnCdgsS7iubg2YKljkWMK+Nl4rodhkGdA6ME3X1l2AAAVf+pN2AHEqw3VLPrqWO4rwpNsyQj5WeGXAg+bV8rOllzOC4e0FwAAFX/qPpXBYglxvwADRw9u7oBQQBAg8MQEYbHIJEQAMCAG/JiqxsTBx2WAAAAAAAAgAAAAAAAh+1bvDWnLCgRmTLFrApyvKnoCvN5oGbiFWPDRqpjy0EQJAfZACdQy+jE4gAAAAAAAAAACSAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAIACCcj7pfRHC7sHIea84Hs/hukiSefxdzPwsH4Ne5GCMU2vSK9sdrhKJoUW6Md9cS31zzC4xsPaU5ULRh/WiRm/kBvoCAeAIBgEB3wcBsWgB3xs4TsFiXcVzcGzBUscixhXxsvFdDsMgzoHRgm6+su0ADXqbt4aynxEK1cyy5QQ+J7v5F3aeGaWPbVJjYUSyrwFR3IDsoAYcdoQAACr/1JuwBMQS437ACgHdiAHfGzhOwWJdxXNwbMFSxyLGFfGy8V0OwyDOgdGCbr6y7AV48xwh0kVSa89wWq/VOK3fnIvDljptTJeqcB10t2RV0KCx4Vq1k5kzaY0RhHBKxqT0tiyp/nC0e+1/jx19p/AwAAAF+9ORPq0zuZGyCQFlgAa9TdvDWU+IhWrmWXKCHxPd/Iu7TwzSx7apMbCiWVeAoAAAAAAAAAAAAAAADuaygBAICgBLDgTSnoAdQT3MtmUbh60b5nBYLKOECUJ+GUmxfw3N+6y3zjrx6BA=";
     let tx = Transaction::construct_from_base64(tx).unwrap();
-    let message = tx.in_msg.unwrap().read_struct().context("Failed reading in msg").unwrap();
+    let message = tx.in_msg.as_ref().unwrap().read_struct().context("Failed reading in msg").unwrap();
     let body = message.body().unwrap();
 
     let (_, _, body) =
@@ -431,6 +1023,81 @@ fn decode_params() {
     ];
 
     assert_eq!(tokens, result_tokens);
+
+    let decoded = abi.decode_transaction(&tx).unwrap();
+    match decoded.in_msg.unwrap() {
+        DecodedBody::FunctionInput { name, header, tokens } => {
+            assert_eq!(name, "sendTransaction");
+            assert_eq!(header.len(), 1);
+            assert_eq!(tokens, result_tokens);
+        }
+        other => panic!("unexpected in message decoding: {:?}", other),
+    }
+    assert!(decoded.failed.is_empty());
+    assert_eq!(decoded.out_msgs.len() + decoded.unmatched.len(), tx.outmsg_cnt as usize);
+
+    assert!(matches!(
+        abi.decode_message(&message).unwrap(),
+        DecodedBody::FunctionInput { name, .. } if name == "sendTransaction"
+    ));
+
+    let event = abi.events.get("OwnershipTransferred").unwrap();
+    let event_body = event.encode_message_body(&[
+        Token::new("previousOwner", TokenValue::Uint(Uint::new(1, 256))),
+        Token::new("newOwner", TokenValue::Uint(Uint::new(2, 256))),
+    ]).unwrap();
+    let mut event_message = ton_block::Message::with_ext_out_header(Default::default());
+    event_message.set_body(SliceData::load_builder(event_body).unwrap());
+    assert!(matches!(
+        abi.decode_message(&event_message).unwrap(),
+        DecodedBody::Event { name, .. } if name == "OwnershipTransferred"
+    ));
+
+    let owner = abi.function("owner").unwrap();
+    let owner_tokens = vec![Token::new("owner", TokenValue::Uint(Uint::new(3, 256)))];
+    let response_body = owner.encode_internal_output(owner.get_output_id(), &owner_tokens).unwrap();
+    let mut response_message = ton_block::Message::with_ext_out_header(Default::default());
+    response_message.set_body(SliceData::load_builder(response_body).unwrap());
+
+    let mut unknown_body = BuilderData::new();
+    unknown_body.append_u32(0xDEADBEEF).unwrap();
+    let mut unknown_message = ton_block::Message::with_ext_out_header(Default::default());
+    unknown_message.set_body(SliceData::load_builder(unknown_body).unwrap());
+
+    // event id followed by truncated data
+    let mut malformed_body = BuilderData::new();
+    malformed_body.append_u32(event.get_id()).unwrap();
+    malformed_body.append_u64(1).unwrap();
+    let mut malformed_message = ton_block::Message::with_ext_out_header(Default::default());
+    malformed_message.set_body(SliceData::load_builder(malformed_body).unwrap());
+
+    let empty_message = ton_block::Message::with_ext_out_header(Default::default());
+
+    let mut tx = Transaction::default();
+    tx.write_in_msg(Some(&message)).unwrap();
+    for message in [&event_message, &response_message, &unknown_message, &malformed_message, &empty_message] {
+        tx.add_out_message(message).unwrap();
+    }
+
+    let decoded = abi.decode_transaction(&tx).unwrap();
+    assert!(matches!(
+        decoded.in_msg,
+        Some(DecodedBody::FunctionInput { ref name, ref tokens, .. })
+            if name == "sendTransaction" && tokens == &result_tokens
+    ));
+    assert_eq!(decoded.out_msgs, vec![
+        DecodedBody::Event {
+            name: "OwnershipTransferred".to_owned(),
+            tokens: vec![
+                Token::new("previousOwner", TokenValue::Uint(Uint::new(1, 256))),
+                Token::new("newOwner", TokenValue::Uint(Uint::new(2, 256))),
+            ],
+        },
+        DecodedBody::FunctionOutput { name: "owner".to_owned(), tokens: owner_tokens },
+    ]);
+    assert_eq!(decoded.unmatched, vec![unknown_message, empty_message]);
+    assert_eq!(decoded.failed.len(), 1);
+    assert_eq!(decoded.failed[0].0, malformed_message);
 }
 
 #[test]
@@ -490,6 +1157,167 @@ fn test_update_decode_contract_data() {
     );
 }
 
+#[test]
+fn test_merge_contract_data() {
+    let params = r#"{
+        "subscription": "0:1111111111111111111111111111111111111111111111111111111111111111",
+        "owner": "0x22"
+    }"#;
+    let data = update_contract_data(WALLET_ABI, params, SliceData::default()).unwrap();
+
+    let data = merge_contract_data(WALLET_ABI, r#"{"owner": "0x33"}"#, data).unwrap();
+    let owner = get_contract_data_item(WALLET_ABI, data.clone(), "owner").unwrap().unwrap();
+    assert_eq!(
+        serde_json::from_str::<Value>(&owner).unwrap(),
+        serde_json::json!({ "owner": "51" })
+    );
+    assert!(get_contract_data_item(WALLET_ABI, data.clone(), "subscription").unwrap().is_some());
+
+    let data = merge_contract_data(WALLET_ABI, r#"{"subscription": null}"#, data).unwrap();
+    assert!(get_contract_data_item(WALLET_ABI, data.clone(), "subscription").unwrap().is_none());
+    assert!(get_contract_data_item(WALLET_ABI, data.clone(), "owner").unwrap().is_some());
+
+    assert!(merge_contract_data(WALLET_ABI, r#"{"unknown": 1}"#, data.clone()).is_err());
+    assert!(merge_contract_data(WALLET_ABI, "[]", data).is_err());
+}
+
+#[test]
+fn test_contract_pubkey_layouts() {
+    let data = SliceData::load_cell(encode_contract_data(WALLET_ABI, "{}", Some(&[0x33; 32])).unwrap()).unwrap();
+    assert_eq!(get_contract_pubkey(WALLET_ABI, data.clone()).unwrap(), Some([0x33; 32]));
+    let data = set_contract_pubkey(WALLET_ABI, data, &[0x44; 32]).unwrap();
+    assert_eq!(get_contract_pubkey(WALLET_ABI, data).unwrap(), Some([0x44; 32]));
+
+    let abi = r#"{
+        "version": "2.4",
+        "functions": [],
+        "fields": [
+            {"name":"_pubkey","type":"uint256"},
+            {"name":"_timestamp","type":"uint64"},
+            {"name":"value","type":"uint32","init":true}
+        ]
+    }"#;
+    let data = SliceData::load_builder(encode_storage_fields(abi, Some(r#"{"value": 7}"#)).unwrap()).unwrap();
+    assert_eq!(get_contract_pubkey(abi, data.clone()).unwrap(), Some([0; 32]));
+
+    let data = set_contract_pubkey(abi, data, &[0x55; 32]).unwrap();
+    assert_eq!(get_contract_pubkey(abi, data.clone()).unwrap(), Some([0x55; 32]));
+    assert_eq!(
+        serde_json::from_str::<Value>(&decode_selected_storage_fields(abi, data, &["value"]).unwrap()).unwrap(),
+        serde_json::json!({"value": "7"})
+    );
+
+    let abi = abi.replace("_pubkey", "owner");
+    let data = SliceData::load_builder(encode_storage_fields(&abi, Some(r#"{"value": 7}"#)).unwrap()).unwrap();
+    assert_eq!(get_contract_pubkey(&abi, data.clone()).unwrap(), None);
+    assert!(set_contract_pubkey(&abi, data, &[0x55; 32]).is_err());
+}
+
+#[test]
+fn test_encode_contract_data() {
+    let params = r#"{
+        "subscription": "0:1111111111111111111111111111111111111111111111111111111111111111",
+        "owner": "8738"
+    }"#;
+
+    let data = encode_contract_data(WALLET_ABI, params, Some(&[0x33; 32])).unwrap();
+    let data = SliceData::load_cell(data).unwrap();
+    assert_eq!(Contract::get_pubkey(&data).unwrap(), Some([0x33; 32]));
+
+    let decoded = decode_contract_data(WALLET_ABI, data).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Value>(params).unwrap(),
+        serde_json::from_str::<Value>(&decoded).unwrap()
+    );
+
+    let data = encode_contract_data(WALLET_ABI, params, None).unwrap();
+    assert_eq!(Contract::get_pubkey(&SliceData::load_cell(data).unwrap()).unwrap(), None);
+    assert!(encode_contract_data(WALLET_ABI, r#"{"owner": "1"}"#, None).is_err());
+}
+
+#[test]
+fn test_get_set_contract_data_item() {
+    let data = Contract::insert_pubkey(SliceData::default(), &[0u8; 32]).unwrap();
+
+    assert_eq!(get_contract_data_item(WALLET_ABI, data.clone(), "owner").unwrap(), None);
+    assert!(get_contract_data_item(WALLET_ABI, data.clone(), "unknown").is_err());
+    assert!(set_contract_data_item(WALLET_ABI, data.clone(), "owner", r#""not a number""#).is_err());
+
+    let data = set_contract_data_item(WALLET_ABI, data, "owner", r#""0x2222""#).unwrap();
+    let owner = get_contract_data_item(WALLET_ABI, data.clone(), "owner").unwrap().unwrap();
+    assert_eq!(
+        serde_json::from_str::<Value>(&owner).unwrap(),
+        serde_json::json!({ "owner": "8738" })
+    );
+    assert_eq!(Contract::get_pubkey(&data).unwrap(), Some([0u8; 32]));
+
+    let contract = Contract::load(WALLET_ABI.as_bytes()).unwrap();
+    let detailed = contract.decode_init_data_detailed(data.clone()).unwrap();
+    let status = |name: &str| detailed.iter().find(|value| value.name == name).unwrap().status;
+    assert_eq!(status("owner"), crate::contract::InitValueStatus::Present);
+    assert_eq!(status("subscription"), crate::contract::InitValueStatus::Defaulted);
+
+    assert!(contract
+        .set_data_item(data, "owner", TokenValue::Bool(true))
+        .is_err());
+}
+
+#[test]
+fn test_decode_from_cell() {
+    let contract = Contract::load(WALLET_ABI.as_bytes()).unwrap();
+    let function = contract.function("createArbitraryLimit").unwrap();
+
+    let mut output = BuilderData::new();
+    output.append_u32(function.get_output_id()).unwrap();
+    output.append_u64(5).unwrap();
+    let output = output.into_cell().unwrap();
+    let expected = vec![Token::new("value0", TokenValue::Uint(Uint::new(5, 64)))];
+    assert_eq!(function.decode_output_from_cell(&output, false).unwrap(), expected);
+    assert_eq!(contract.decode_output_from_cell(&output, false).unwrap().tokens, expected);
+    let mut params = SliceData::load_cell(output.clone()).unwrap();
+    params.get_next_u32().unwrap();
+    assert_eq!(
+        TokenValue::decode_params_from_cell(
+            function.output_params(), &params.into_cell(), &function.abi_version, false
+        ).unwrap(),
+        expected
+    );
+
+    let mut input = BuilderData::new();
+    input.append_u32(function.get_input_id()).unwrap();
+    input.append_raw(&[0; 16], 128).unwrap();
+    input.append_u32(7).unwrap();
+    let input = input.into_cell().unwrap();
+    let expected = vec![
+        Token::new("value", TokenValue::Uint(Uint::new(0, 128))),
+        Token::new("period", TokenValue::Uint(Uint::new(7, 32))),
+    ];
+    assert_eq!(function.decode_input_from_cell(&input, true, false).unwrap(), expected);
+    assert_eq!(contract.decode_input_from_cell(&input, true, false).unwrap().tokens, expected);
+
+    let event = contract.event("event").unwrap();
+    let mut body = BuilderData::new();
+    body.append_u32(event.get_id()).unwrap();
+    body.append_u8(3).unwrap();
+    let body = body.into_cell().unwrap();
+    assert_eq!(
+        event.decode_input_from_cell(&body).unwrap(),
+        vec![Token::new("param", TokenValue::Uint(Uint::new(3, 8)))]
+    );
+
+    let mut storage = BuilderData::new();
+    storage.append_raw(&[0x55; 32], 256).unwrap();
+    storage.append_u64(123).unwrap();
+    storage.append_bit_one().unwrap();
+    storage.append_u32(456).unwrap();
+    let storage = storage.into_cell().unwrap();
+    let fields = Contract::load(ABI_WITH_FIELDS.as_bytes()).unwrap();
+    assert_eq!(
+        fields.decode_storage_fields_from_cell(&storage, false).unwrap(),
+        fields.decode_storage_fields(SliceData::load_cell(storage).unwrap(), false).unwrap()
+    );
+}
+
 const ABI_WITH_FIELDS: &str = r#"{
     "version": "2.1",
     "functions": [],
@@ -510,9 +1338,14 @@ fn test_decode_storage_fields() {
     storage.append_u64(123).unwrap();
     storage.append_bit_one().unwrap();
     storage.append_u32(456).unwrap();
-    let storage = SliceData::load_builder(storage).unwrap();
+    let storage_cell = storage.into_cell().unwrap();
+    let storage = SliceData::load_cell(storage_cell.clone()).unwrap();
 
     let decoded = decode_storage_fields(ABI_WITH_FIELDS, storage, false).unwrap();
+    assert_eq!(
+        decoded,
+        decode_storage_fields_from_cell(ABI_WITH_FIELDS, &storage_cell, false).unwrap()
+    );
 
     assert_eq!(
         decoded,
@@ -526,7 +1359,98 @@ fn test_decode_storage_fields() {
     );
 }
 
-fn value_helper(abi_type: &str, value: &str) -> Result<BuilderData> {
+#[test]
+fn test_decode_storage_fields_with_defaults() {
+    let mut storage = BuilderData::new();
+    storage
+        .append_bitstring(&[vec![0x55; 32], vec![0x80]].join(&[][..]))
+        .unwrap();
+    storage.append_u64(123).unwrap();
+    let storage = SliceData::load_builder(storage).unwrap();
+
+    let contract = Contract::load(ABI_WITH_FIELDS.as_bytes()).unwrap();
+
+    assert!(contract.decode_storage_fields(storage.clone(), false).is_err());
+
+    let (tokens, defaulted) = contract
+        .decode_storage_fields_with_defaults(storage, false)
+        .unwrap();
+
+    assert_eq!(defaulted, vec!["ok".to_owned(), "value".to_owned()]);
+    assert_eq!(tokens[1], Token::new("__timestamp", TokenValue::Uint(Uint::new(123, 64))));
+    assert_eq!(tokens[2], Token::new("ok", TokenValue::Bool(false)));
+    assert_eq!(tokens[3], Token::new("value", TokenValue::Uint(Uint::new(0, 32))));
+
+    // trailing data is rejected unless partial decoding is allowed
+    let mut storage = BuilderData::new();
+    storage.append_u8(1).unwrap();
+    let storage = SliceData::load_builder(storage).unwrap();
+    assert!(TokenValue::decode_params_with_defaults(&[], storage.clone(), &contract.abi_version, false).is_err());
+    let (tokens, defaulted) =
+        TokenValue::decode_params_with_defaults(&[], storage, &contract.abi_version, true).unwrap();
+    assert!(tokens.is_empty() && defaulted.is_empty());
+}
+
+#[test]
+fn test_skip_storage_fields() {
+    let mut storage = BuilderData::new();
+    storage
+        .append_bitstring(&[vec![0x55; 32], vec![0x80]].join(&[][..]))
+        .unwrap();
+    storage.append_u64(123).unwrap();
+    storage.append_bit_one().unwrap();
+    storage.append_u32(456).unwrap();
+    let storage = SliceData::load_builder(storage).unwrap();
+
+    let contract = Contract::load(ABI_WITH_FIELDS.as_bytes()).unwrap();
+
+    let cursor = TokenValue::skip(&contract.fields[..3], storage.into(), &contract.abi_version).unwrap();
+    let (tokens, _) = TokenValue::decode_params_with_cursor(
+        &contract.fields[3..],
+        cursor,
+        &contract.abi_version,
+        false,
+        true,
+    ).unwrap();
+
+    assert_eq!(tokens, vec![Token::new("value", TokenValue::Uint(Uint::new(456, 32)))]);
+}
+
+#[test]
+fn test_decode_selected_storage_fields() {
+    let mut storage = BuilderData::new();
+    storage
+        .append_bitstring(&[vec![0x55; 32], vec![0x80]].join(&[][..]))
+        .unwrap();
+    storage.append_u64(123).unwrap();
+    storage.append_bit_one().unwrap();
+    storage.append_u32(456).unwrap();
+    let storage = SliceData::load_builder(storage).unwrap();
+
+    let contract = Contract::load(ABI_WITH_FIELDS.as_bytes()).unwrap();
+
+    assert_eq!(
+        contract.decode_selected_storage_fields(storage.clone(), &["value", "__timestamp"]).unwrap(),
+        vec![
+            Token::new("__timestamp", TokenValue::Uint(Uint::new(123, 64))),
+            Token::new("value", TokenValue::Uint(Uint::new(456, 32))),
+        ]
+    );
+    assert_eq!(
+        contract.decode_selected_storage_fields(storage.clone(), &["ok"]).unwrap(),
+        vec![Token::new("ok", TokenValue::Bool(true))]
+    );
+    assert!(contract.decode_selected_storage_fields(storage.clone(), &[]).unwrap().is_empty());
+    assert!(contract.decode_selected_storage_fields(storage.clone(), &["unknown"]).is_err());
+
+    let decoded = decode_selected_storage_fields(ABI_WITH_FIELDS, storage, &["__timestamp"]).unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&decoded).unwrap(),
+        serde_json::json!({"__timestamp": "123"})
+    );
+}
+
+fn value_helper(abi_type: &str, value: &str) -> AbiResult<BuilderData> {
     let abi = serde_json::json!({
         "ABI version": 2,
         "version": "2.3",
@@ -11,11 +11,12 @@
 * limitations under the License.
 */
 
-use crate::{Contract, Function, Event, Param, ParamType, DataItem};
+use crate::{Contract, Function, Event, LoadOptions, Param, ParamType, DataItem, Token, TokenValue, Uint};
 use std::collections::HashMap;
-use ton_block::{Deserializable, StateInit};
-use ton_types::SliceData;
-use crate::contract::ABI_VERSION_2_4;
+use ton_block::{Deserializable, MsgAddressInt, Serializable, StateInit};
+use ton_types::{BuilderData, IBitstring, SliceData};
+use crate::contract::{ABI_VERSION_2_4, InitValue, InitValueStatus};
+use crate::signature::Signer;
 
 const TEST_ABI: &str = r#"
 {
@@ -259,7 +260,7 @@ fn test_abi_parse() {
 
     let init_fields = vec!["b".to_owned()].into_iter().collect();
 
-    let expected_contract = Contract {
+    let mut expected_contract = Contract {
         abi_version,
         header,
         functions,
@@ -268,7 +269,14 @@ fn test_abi_parse() {
         fields,
         init_fields,
         getters: Default::default(),
+        function_input_ids: Default::default(),
+        function_output_ids: Default::default(),
+        event_ids: Default::default(),
+        order: Default::default(),
+        structs: vec![],
+        param_attributes: Default::default(),
     };
+    expected_contract.build_id_indexes();
 
     assert_eq!(parsed_contract, expected_contract);
 }
@@ -336,11 +344,1367 @@ fn decode_27_init_data() {
 
     let state_init = StateInit::construct_from_bytes(&base64::decode(state_init).unwrap_or_default()).unwrap();
 
-    let data = state_init.data.unwrap();
+    let data = state_init.data.clone().unwrap();
 
     let contract = Contract::load(abi.as_bytes()).unwrap();
 
-    let x = contract.decode_init_data(SliceData::load_cell(data).unwrap()).unwrap();
+    let x = contract.decode_init_data(SliceData::load_cell(data.clone()).unwrap()).unwrap();
     assert_eq!(x.len(), 3);
+
+    let detailed = contract.decode_init_data_detailed(SliceData::load_cell(data).unwrap()).unwrap();
+    assert_eq!(detailed.len(), 5);
+    assert!(detailed.iter().all(|value| value.status == InitValueStatus::Present));
+    assert_eq!(
+        detailed.iter().filter(|value| value.init).map(|value| value.name.as_str()).collect::<Vec<_>>(),
+        vec!["_pubkey", "root", "owner"]
+    );
+
+    let pubkey: [u8; 32] = match &x.iter().find(|token| token.name == "_pubkey").unwrap().value {
+        TokenValue::FixedBytes(bytes) => bytes.as_slice().try_into().unwrap(),
+        _ => panic!("wrong _pubkey type"),
+    };
+    let init = x.into_iter()
+        .filter(|token| token.name != "_pubkey")
+        .map(|token| (token.name, token.value))
+        .collect::<HashMap<_, _>>();
+    let encoded = contract
+        .encode_state_init(state_init.code.clone().unwrap(), init.clone(), Some(&pubkey))
+        .unwrap();
+    assert_eq!(encoded.data, state_init.data);
+
+    let new_code = BuilderData::with_raw(vec![0x55], 8).unwrap().into_cell().unwrap();
+    let replaced = contract.replace_code(&state_init, new_code.clone(), None).unwrap();
+    assert_eq!(replaced.code, Some(new_code.clone()));
+    assert_eq!(replaced.data, state_init.data);
+    let migrated = contract.replace_code(&state_init, new_code, Some(&contract)).unwrap();
+    assert_eq!(migrated.data, state_init.data);
+    let address = contract
+        .compute_address(state_init.code.clone().unwrap(), init, 0, Some(&pubkey))
+        .unwrap();
+    assert_eq!(
+        address,
+        MsgAddressInt::with_standart(None, 0, state_init.serialize().unwrap().repr_hash().into()).unwrap()
+    );
 }
 
+#[test]
+fn test_decode_init_data_detailed_data_map() {
+    let abi = r#"{
+        "version": "2.3",
+        "functions": [],
+        "events": [],
+        "data": [
+            {"key": 1, "name": "x", "type": "uint8"},
+            {"key": 2, "name": "y", "type": "uint8"}
+        ]
+    }"#;
+    let contract = Contract::load(abi.as_bytes()).unwrap();
+    let init = HashMap::from([("x".to_owned(), TokenValue::Uint(Uint::new(5, 8)))]);
+    let data = contract.encode_initial_data(init, None).unwrap();
+
+    assert_eq!(
+        contract.decode_init_data_detailed(SliceData::load_cell(data).unwrap()).unwrap(),
+        vec![
+            InitValue {
+                name: "x".to_owned(),
+                init: false,
+                status: InitValueStatus::Present,
+                value: Some(TokenValue::Uint(Uint::new(5, 8))),
+            },
+            InitValue {
+                name: "y".to_owned(),
+                init: false,
+                status: InitValueStatus::Defaulted,
+                value: Some(TokenValue::Uint(Uint::new(0, 8))),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_decode_init_data_detailed_fields() {
+    let abi = r#"{
+        "version": "2.4",
+        "functions": [],
+        "events": [],
+        "fields": [
+            {"init": true, "name": "a", "type": "uint8"},
+            {"init": false, "name": "b", "type": "uint8"},
+            {"init": true, "name": "c", "type": "uint8"}
+        ]
+    }"#;
+    let contract = Contract::load(abi.as_bytes()).unwrap();
+    let data = BuilderData::with_raw(vec![5], 8).unwrap().into_cell().unwrap();
+
+    assert_eq!(
+        contract.decode_init_data_detailed(SliceData::load_cell(data).unwrap()).unwrap(),
+        vec![
+            InitValue {
+                name: "a".to_owned(),
+                init: true,
+                status: InitValueStatus::Present,
+                value: Some(TokenValue::Uint(Uint::new(5, 8))),
+            },
+            InitValue {
+                name: "b".to_owned(),
+                init: false,
+                status: InitValueStatus::Defaulted,
+                value: Some(TokenValue::Uint(Uint::new(0, 8))),
+            },
+            InitValue {
+                name: "c".to_owned(),
+                init: true,
+                status: InitValueStatus::Missing,
+                value: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_compute_address_with_data_map() {
+    let abi = r#"{
+        "version": "2.3",
+        "functions": [],
+        "data": [
+            {"key": 1, "name": "a", "type": "uint64"},
+            {"key": 2, "name": "b", "type": "address"}
+        ]
+    }"#;
+    let contract = Contract::load(abi.as_bytes()).unwrap();
+    let code = BuilderData::with_raw(vec![0x55], 8).unwrap().into_cell().unwrap();
+    let pubkey = [0x22; 32];
+    let mut init = HashMap::new();
+    init.insert("a".to_owned(), TokenValue::Uint(Uint::new(5, 64)));
+
+    let address = contract.compute_address(code.clone(), init, -1, Some(&pubkey)).unwrap();
+
+    let data = contract
+        .encode_data(&[Token::new("a", TokenValue::Uint(Uint::new(5, 64)))], Some(&pubkey))
+        .unwrap();
+    let expected = StateInit { code: Some(code.clone()), data: Some(data.clone()), ..Default::default() };
+    assert_eq!(
+        address,
+        MsgAddressInt::with_standart(None, -1, expected.serialize().unwrap().repr_hash().into()).unwrap()
+    );
+    let data = SliceData::load_cell(data).unwrap();
+    assert_eq!(Contract::get_pubkey(&data).unwrap(), Some(pubkey));
+    #[allow(deprecated)]
+    let tokens = contract.decode_data(data, false).unwrap();
+    assert_eq!(tokens, vec![Token::new("a", TokenValue::Uint(Uint::new(5, 64)))]);
+
+    // zero public key is stored if it is not provided
+    let unsigned = contract.encode_state_init(code, HashMap::new(), None).unwrap();
+    let data = SliceData::load_cell(unsigned.data.unwrap()).unwrap();
+    assert_eq!(Contract::get_pubkey(&data).unwrap(), Some([0; 32]));
+}
+
+#[test]
+fn test_replace_code_migrate_fields() {
+    let old = Contract::load(r#"{
+        "version": "2.4",
+        "functions": [],
+        "fields": [
+            {"name": "_pubkey", "type": "uint256", "init": true},
+            {"name": "a", "type": "uint64", "init": true},
+            {"name": "b", "type": "bool", "init": true},
+            {"name": "old_name", "type": "uint32", "init": true}
+        ]
+    }"#.as_bytes()).unwrap();
+    let new_abi = r#"{
+        "version": "2.4",
+        "functions": [],
+        "fields": [
+            {"name": "_pubkey", "type": "uint256"},
+            {"name": "a", "type": "uint64"},
+            {"name": "new_name", "type": "uint32"},
+            {"name": "c", "type": "bool"}
+        ]
+    }"#;
+    let new = Contract::load(new_abi.as_bytes()).unwrap();
+
+    let pubkey = Uint::new(0x22, 256);
+    let mut init = HashMap::new();
+    init.insert("_pubkey".to_owned(), TokenValue::Uint(pubkey.clone()));
+    init.insert("a".to_owned(), TokenValue::Uint(Uint::new(7, 64)));
+    init.insert("b".to_owned(), TokenValue::Bool(true));
+    init.insert("old_name".to_owned(), TokenValue::Uint(Uint::new(9, 32)));
+    let data = old.encode_storage_fields(init).unwrap().into_cell().unwrap();
+    let state_init = StateInit { data: Some(data), ..Default::default() };
+    let code = BuilderData::with_raw(vec![0x55], 8).unwrap().into_cell().unwrap();
+
+    // removed field is dropped, renamed and added fields get default values
+    let migrated = new.replace_code(&state_init, code.clone(), Some(&old)).unwrap();
+    assert_eq!(migrated.code, Some(code.clone()));
+    let tokens = new.decode_storage_fields_from_cell(&migrated.data.unwrap(), false).unwrap();
+    assert_eq!(tokens, vec![
+        Token::new("_pubkey", TokenValue::Uint(pubkey)),
+        Token::new("a", TokenValue::Uint(Uint::new(7, 64))),
+        Token::new("new_name", TokenValue::Uint(Uint::new(0, 32))),
+        Token::new("c", TokenValue::Bool(false)),
+    ]);
+
+    // renamed field marked as `init` can't be migrated
+    let new = Contract::load(
+        new_abi.replace(r#""type": "uint32"}"#, r#""type": "uint32", "init": true}"#).as_bytes()
+    ).unwrap();
+    let err = new.replace_code(&state_init, code, Some(&old)).unwrap_err();
+    assert!(err.to_string().contains("new_name"), "{}", err);
+}
+
+#[test]
+fn test_replace_code_migrate_data_map_to_fields() {
+    let old = Contract::load(r#"{
+        "version": "2.3",
+        "functions": [],
+        "data": [
+            {"key": 1, "name": "a", "type": "uint64"},
+            {"key": 2, "name": "b", "type": "bool"}
+        ]
+    }"#.as_bytes()).unwrap();
+    let new = Contract::load(r#"{
+        "version": "2.4",
+        "functions": [],
+        "fields": [
+            {"name": "_pubkey", "type": "fixedbytes32"},
+            {"name": "a", "type": "uint64"},
+            {"name": "c", "type": "bool"}
+        ]
+    }"#.as_bytes()).unwrap();
+
+    let pubkey = [0x33; 32];
+    let data = old
+        .encode_data(
+            &[
+                Token::new("a", TokenValue::Uint(Uint::new(7, 64))),
+                Token::new("b", TokenValue::Bool(true)),
+            ],
+            Some(&pubkey),
+        )
+        .unwrap();
+    let state_init = StateInit { data: Some(data), ..Default::default() };
+    let code = BuilderData::with_raw(vec![0x55], 8).unwrap().into_cell().unwrap();
+
+    // data map items and public key are moved into storage fields
+    let migrated = new.replace_code(&state_init, code, Some(&old)).unwrap();
+    let tokens = new.decode_storage_fields_from_cell(&migrated.data.unwrap(), false).unwrap();
+    assert_eq!(tokens, vec![
+        Token::new("_pubkey", TokenValue::FixedBytes(pubkey.to_vec())),
+        Token::new("a", TokenValue::Uint(Uint::new(7, 64))),
+        Token::new("c", TokenValue::Bool(false)),
+    ]);
+}
+
+
+#[test]
+fn test_unsigned_call_late_address() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("no_output").unwrap();
+
+    let mut header = HashMap::new();
+    header.insert("time".to_owned(), TokenValue::Time(123));
+    header.insert("expire".to_owned(), TokenValue::Expire(456));
+    header.insert("a".to_owned(), TokenValue::Uint(Uint::new(789, 64)));
+    let input = vec![Token::new("a", TokenValue::Uint(Uint::new(1, 15)))];
+    let address = MsgAddressInt::with_standart(None, 0, [0x11; 32].into()).unwrap();
+
+    let call = function.prepare_unsigned_call(&header, &input, false, true).unwrap();
+    assert!(call.hash(None).is_err());
+
+    let (builder, hash) = function
+        .create_unsigned_call(&header, &input, false, true, Some(address.clone()))
+        .unwrap();
+    assert_eq!(call.builder, builder);
+    assert_eq!(call.hash(Some(address.clone())).unwrap(), hash);
+
+    let pair = ed25519_dalek::Keypair::generate(&mut rand::thread_rng());
+    let signed = call.sign(&pair, Some(address.clone()), None).unwrap();
+    let expected = function
+        .encode_input(&header, &input, false, Some((&pair, None)), Some(address))
+        .unwrap();
+    assert_eq!(signed, expected);
+}
+
+#[test]
+fn test_decode_input_with_header() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("no_output").unwrap();
+
+    let mut header = HashMap::new();
+    header.insert("time".to_owned(), TokenValue::Time(123));
+    header.insert("expire".to_owned(), TokenValue::Expire(456));
+    header.insert("a".to_owned(), TokenValue::Uint(Uint::new(789, 64)));
+    let input = vec![Token::new("a", TokenValue::Uint(Uint::new(1, 15)))];
+
+    let body = function.encode_input(&header, &input, false, None, None).unwrap();
+    let (header_tokens, input_tokens) = function
+        .decode_input_with_header(SliceData::load_builder(body).unwrap(), false, false)
+        .unwrap();
+
+    assert_eq!(header_tokens, vec![
+        Token::new("time", TokenValue::Time(123)),
+        Token::new("expire", TokenValue::Expire(456)),
+        Token::new("pubkey", TokenValue::PublicKey(None)),
+        Token::new("a", TokenValue::Uint(Uint::new(789, 64))),
+    ]);
+    assert_eq!(input_tokens, input);
+}
+
+#[test]
+fn test_verify_signature() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("no_output").unwrap();
+
+    let mut header = HashMap::new();
+    header.insert("a".to_owned(), TokenValue::Uint(Uint::new(789, 64)));
+    let input = vec![Token::new("a", TokenValue::Uint(Uint::new(1, 15)))];
+    let address = MsgAddressInt::with_standart(None, 0, [0x11; 32].into()).unwrap();
+    let other_address = MsgAddressInt::with_standart(None, 0, [0x22; 32].into()).unwrap();
+
+    let pair = ed25519_dalek::Keypair::generate(&mut rand::thread_rng());
+    let other_pair = ed25519_dalek::Keypair::generate(&mut rand::thread_rng());
+
+    let body = function
+        .encode_input(&header, &input, false, Some((&pair, Some(42))), Some(address.clone()))
+        .unwrap();
+    let body = SliceData::load_builder(body).unwrap();
+
+    assert!(function.verify_signature(body.clone(), &pair.public, Some(address.clone()), Some(42)).unwrap());
+    assert!(!function.verify_signature(body.clone(), &pair.public, Some(address.clone()), None).unwrap());
+    assert!(!function.verify_signature(body.clone(), &pair.public, Some(other_address), Some(42)).unwrap());
+    assert!(!function.verify_signature(body.clone(), &other_pair.public, Some(address), Some(42)).unwrap());
+    assert!(function.verify_signature(body, &pair.public, None, Some(42)).is_err());
+
+    let unsigned = function.encode_input(&header, &input, false, None, None).unwrap();
+    assert!(!function
+        .verify_signature(SliceData::load_builder(unsigned).unwrap(), &pair.public, None, None)
+        .unwrap());
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_encode_input_with_async_signer() {
+    use crate::signature::{AsyncSigner, SignFuture};
+    use std::future::Future;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    struct RemoteSigner(ed25519_dalek::Keypair);
+
+    impl AsyncSigner for RemoteSigner {
+        fn public_key(&self) -> ed25519_dalek::PublicKey {
+            self.0.public
+        }
+
+        fn sign<'a>(&'a self, data: &'a [u8]) -> SignFuture<'a> {
+            Box::pin(async move { Signer::sign(&self.0, data) })
+        }
+    }
+
+    struct NoopWake;
+
+    impl Wake for NoopWake {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Waker::from(Arc::new(NoopWake));
+        let mut context = Context::from_waker(&waker);
+        let mut future = std::pin::pin!(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("no_output").unwrap();
+
+    let mut header = HashMap::new();
+    header.insert("a".to_owned(), TokenValue::Uint(Uint::new(789, 64)));
+    let input = vec![Token::new("a", TokenValue::Uint(Uint::new(1, 15)))];
+    let address = MsgAddressInt::with_standart(None, 0, [0x11; 32].into()).unwrap();
+    let signer = RemoteSigner(ed25519_dalek::Keypair::generate(&mut rand::thread_rng()));
+
+    let body = block_on(function.encode_input_with_async_signer(
+        &header, &input, &signer, Some(42), Some(address.clone())
+    )).unwrap();
+    let expected = function
+        .encode_input(&header, &input, false, Some((&signer.0, Some(42))), Some(address.clone()))
+        .unwrap();
+    assert_eq!(body, expected);
+
+    let dyn_signer: &dyn AsyncSigner = &signer;
+    let dyn_body = block_on(function.encode_input_with_async_signer(
+        &header, &input, dyn_signer, Some(42), Some(address.clone())
+    )).unwrap();
+    assert_eq!(dyn_body, expected);
+
+    let body = SliceData::load_builder(body).unwrap();
+    assert!(function.verify_signature(body, &signer.0.public, Some(address), Some(42)).unwrap());
+}
+
+#[test]
+fn test_deterministic_signers() {
+    use crate::signature::test_utils::{keypair, FixedSigner, FIXED_SIGNATURE};
+
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("no_output").unwrap();
+
+    let mut header = HashMap::new();
+    header.insert("time".to_owned(), TokenValue::Time(1));
+    header.insert("expire".to_owned(), TokenValue::Expire(2));
+    header.insert("a".to_owned(), TokenValue::Uint(Uint::new(789, 64)));
+    let input = vec![Token::new("a", TokenValue::Uint(Uint::new(1, 15)))];
+    let address = MsgAddressInt::with_standart(None, 0, [0x11; 32].into()).unwrap();
+
+    assert_eq!(keypair(1).to_bytes(), keypair(1).to_bytes());
+    assert_ne!(keypair(1).public, keypair(2).public);
+
+    let encode = |signer: &dyn Signer| function
+        .encode_input_with_signer(&header, &input, false, Some((signer, None)), Some(address.clone()))
+        .unwrap();
+    assert_eq!(encode(&keypair(1)), encode(&keypair(1)));
+
+    let mut body = SliceData::load_builder(encode(&FixedSigner::new(1))).unwrap();
+    assert!(body.get_next_bit().unwrap());
+    assert_eq!(body.get_next_bytes(FIXED_SIGNATURE.len()).unwrap(), FIXED_SIGNATURE.to_vec());
+}
+
+#[test]
+fn test_keypair_constructors() {
+    use crate::signature::{derive_ed25519_key, keypair_from_derived_seed, keypair_from_seed, ExpandedKeySigner};
+
+    let pair = keypair_from_seed(&[7; 32]).unwrap();
+    assert_eq!(pair.secret.to_bytes(), [7; 32]);
+    assert!(keypair_from_seed(&[7; 31]).is_err());
+
+    let expanded = ed25519_dalek::ExpandedSecretKey::from(&pair.secret);
+    let signer = ExpandedKeySigner::from_bytes(&expanded.to_bytes()).unwrap();
+    assert_eq!(signer.public_key(), pair.public);
+    assert_eq!(signer.sign(b"data").unwrap(), Signer::sign(&pair, b"data").unwrap());
+
+    // SLIP-0010 test vector 1 for ed25519
+    let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+    assert_eq!(
+        hex::encode(derive_ed25519_key(&seed, "m").unwrap()),
+        "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7"
+    );
+    assert_eq!(
+        hex::encode(derive_ed25519_key(&seed, "m/0'").unwrap()),
+        "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"
+    );
+    assert_eq!(
+        keypair_from_derived_seed(&seed, "m/0h").unwrap().public,
+        keypair_from_seed(&derive_ed25519_key(&seed, "m/0'").unwrap()).unwrap().public
+    );
+    assert!(derive_ed25519_key(&seed, "m/0").is_err());
+    assert!(derive_ed25519_key(&seed, "0'").is_err());
+}
+
+#[test]
+fn test_constant_time_eq() {
+    use crate::signature::constant_time_eq;
+
+    assert!(constant_time_eq(&[1, 2, 3], &[1, 2, 3]));
+    assert!(!constant_time_eq(&[1, 2, 3], &[1, 2, 4]));
+    assert!(!constant_time_eq(&[1, 2, 3], &[1, 2]));
+    assert!(constant_time_eq(&[], &[]));
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn test_wipe_secrets() {
+    use crate::signature::{derive_ed25519_key, sign_with_signature_id, wipe};
+
+    let mut buffer = [0x5A; 64];
+    wipe(&mut buffer);
+    assert_eq!(buffer, [0; 64]);
+
+    // wiping temporary buffers doesn't change results
+    let key = derive_ed25519_key(&[1; 64], "m/44'/396'/0'/0'/0'").unwrap();
+    assert_eq!(key, derive_ed25519_key(&[1; 64], "m/44'/396'/0'/0'/0'").unwrap());
+    assert_ne!(key, [0; 32]);
+
+    let pair = crate::signature::keypair_from_seed(&key).unwrap();
+    let signature = sign_with_signature_id(&pair, b"data", Some(42));
+    assert_eq!(signature, Signer::sign(&pair, &[&42i32.to_be_bytes()[..], b"data"].concat()).unwrap());
+}
+
+#[cfg(feature = "dalek-v2")]
+#[test]
+fn test_dalek_v2_signing_key() {
+    use crate::signature::dalek_v2::{public_key_token, to_signing_key, to_verifying_key, SigningKey};
+
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("no_output").unwrap();
+
+    let signing_key = SigningKey::from_bytes(&[5; 32]);
+    let verifying_key = signing_key.verifying_key();
+
+    let mut header = HashMap::new();
+    header.insert("a".to_owned(), TokenValue::Uint(Uint::new(789, 64)));
+    header.insert("pubkey".to_owned(), public_key_token(Some(&verifying_key)).unwrap());
+    let input = vec![Token::new("a", TokenValue::Uint(Uint::new(1, 15)))];
+    let address = MsgAddressInt::with_standart(None, 0, [0x11; 32].into()).unwrap();
+
+    let body = function
+        .encode_input_with_signer(&header, &input, false, Some((&signing_key, None)), Some(address.clone()))
+        .unwrap();
+    let public_key = Signer::public_key(&signing_key);
+    assert_eq!(to_verifying_key(&public_key).unwrap(), verifying_key);
+    assert!(function
+        .verify_signature(SliceData::load_builder(body).unwrap(), &public_key, Some(address), None)
+        .unwrap());
+
+    // crate API keeps using ed25519-dalek 1.x types
+    let keypair = crate::signature::keypair_from_seed(&[5; 32]).unwrap();
+    assert_eq!(keypair.public, public_key);
+    assert_eq!(to_signing_key(&keypair).to_bytes(), signing_key.to_bytes());
+}
+
+#[test]
+fn test_signature_id_derivation() {
+    use crate::signature::{network_global_id, signature_id_from_global_id, CAP_SIGNATURE_WITH_ID};
+
+    assert_eq!(network_global_id("everscale"), Some(42));
+    assert_eq!(network_global_id("unknown"), None);
+    assert_eq!(signature_id_from_global_id(42, CAP_SIGNATURE_WITH_ID | 1), Some(42));
+    assert_eq!(signature_id_from_global_id(42, 1), None);
+}
+
+#[test]
+fn test_check_header() {
+    let now_ms = 1_000_000_000;
+    let header = vec![
+        Token::new("time", TokenValue::Time(now_ms)),
+        Token::new("expire", TokenValue::Expire(1_000_060)),
+    ];
+
+    assert!(Function::check_header(&header, now_ms, now_ms - 1, 60).is_ok());
+    assert!(Function::check_header(&header, now_ms, now_ms, 60).is_err());
+    assert!(Function::check_header(&header, now_ms, now_ms - 1, 59).is_err());
+    assert!(Function::check_header(&header, now_ms + 60_000, 0, 60).is_err());
+    assert!(Function::check_header(&header, now_ms - crate::function::MAX_TIME_DRIFT_MS - 1, 0, 1000).is_err());
+}
+
+#[test]
+fn test_encode_input_batch() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("no_output").unwrap();
+
+    let mut header = HashMap::new();
+    header.insert("time".to_owned(), TokenValue::Time(123));
+    header.insert("a".to_owned(), TokenValue::Uint(Uint::new(789, 64)));
+    let inputs: Vec<Vec<Token>> = (0..3)
+        .map(|i| vec![Token::new("a", TokenValue::Uint(Uint::new(i, 15)))])
+        .collect();
+    let address = MsgAddressInt::with_standart(None, 0, [0x11; 32].into()).unwrap();
+    let pair = ed25519_dalek::Keypair::generate(&mut rand::thread_rng());
+
+    let batch = function
+        .encode_input_batch(
+            &header, &inputs, false, Some((&pair as &dyn Signer, None)), Some(address.clone()))
+        .unwrap();
+    assert_eq!(batch.len(), inputs.len());
+    for (body, input) in batch.into_iter().zip(inputs.iter()) {
+        let expected = function
+            .encode_input(&header, input, false, Some((&pair, None)), Some(address.clone()))
+            .unwrap();
+        assert_eq!(body, expected);
+    }
+
+    let batch = function.encode_input_batch(&header, &inputs, false, None, None).unwrap();
+    for (body, input) in batch.into_iter().zip(inputs.iter()) {
+        assert_eq!(body, function.encode_input(&header, input, false, None, None).unwrap());
+    }
+}
+
+#[test]
+fn test_responsible_function() {
+    let abi = r#"{
+        "version": "2.4",
+        "functions": [{
+            "name": "balance",
+            "inputs": [{"name": "answerId", "type": "uint32"}, {"name": "b", "type": "uint8"}],
+            "outputs": [{"name": "value", "type": "uint128"}]
+        }],
+        "events": []
+    }"#;
+    let contract = Contract::load(abi.as_bytes()).unwrap();
+    let function = contract.function("balance").unwrap();
+    assert!(function.is_responsible());
+    assert!(!Contract::load(TEST_ABI.as_bytes()).unwrap().function("no_output").unwrap().is_responsible());
+
+    let input = vec![Token::new("b", TokenValue::Uint(Uint::new(5, 8)))];
+    let body = function.encode_internal_input_with_answer_id(0x1234, &input).unwrap();
+    let full_input = vec![
+        Token::new("answerId", TokenValue::Uint(Uint::new(0x1234, 32))),
+        Token::new("b", TokenValue::Uint(Uint::new(5, 8))),
+    ];
+    assert_eq!(body, function.encode_internal_input(&full_input).unwrap());
+    assert_eq!(body, function.encode_internal_input_with_answer_id(0x1234, &full_input).unwrap());
+
+    let mut callback = BuilderData::new();
+    callback.append_u32(0x1234).unwrap();
+    callback.append_raw(&[0; 15], 15 * 8).unwrap();
+    callback.append_u8(7).unwrap();
+    let callback = SliceData::load_builder(callback).unwrap();
+
+    assert_eq!(
+        function.decode_responsible_output(callback.clone(), 0x1234).unwrap(),
+        vec![Token::new("value", TokenValue::Uint(Uint::new(7, 128)))]
+    );
+    assert!(function.decode_responsible_output(callback, 0x4321).is_err());
+}
+
+#[test]
+fn test_encode_input_with_id() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("no_output").unwrap();
+
+    let mut header = HashMap::new();
+    header.insert("a".to_owned(), TokenValue::Uint(Uint::new(789, 64)));
+    let input = vec![Token::new("a", TokenValue::Uint(Uint::new(1, 15)))];
+
+    let body = function.encode_input_with_id(0x01234567, &header, &input, false, None, None).unwrap();
+    let id = Function::decode_input_id(
+        &function.abi_version, SliceData::load_builder(body).unwrap(), &function.header, false,
+    ).unwrap();
+    assert_eq!(id, 0x01234567);
+    assert_ne!(function.get_input_id(), 0x01234567);
+
+    let body = function.encode_internal_input_with_id(0x01234567, &input).unwrap();
+    let id = Function::decode_input_id(
+        &function.abi_version, SliceData::load_builder(body).unwrap(), &function.header, true,
+    ).unwrap();
+    assert_eq!(id, 0x01234567);
+}
+
+#[test]
+fn test_decode_output_partial_remainder() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("no_input").unwrap();
+
+    let mut body = BuilderData::new();
+    body.append_u32(function.get_output_id()).unwrap();
+    body.append_u8(5).unwrap();
+    body.append_u16(0xabcd).unwrap();
+    let body = SliceData::load_builder(body).unwrap();
+
+    assert!(function.decode_output(body.clone(), false).is_err());
+
+    let tokens = function.decode_output_partial(body.clone(), false).unwrap();
+    assert_eq!(tokens, vec![Token::new("a", TokenValue::Uint(Uint::new(5, 8)))]);
+
+    let (tokens, mut cursor) = function.decode_output_with_cursor(body, false).unwrap();
+    assert_eq!(tokens, vec![Token::new("a", TokenValue::Uint(Uint::new(5, 8)))]);
+    assert_eq!(cursor.slice.remaining_bits(), 16);
+    assert_eq!(cursor.slice.get_next_u16().unwrap(), 0xabcd);
+}
+
+#[test]
+fn test_function_signature_for_version() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("no_output").unwrap();
+
+    assert_eq!(function.get_function_signature_for(&ABI_VERSION_2_4), function.get_function_signature());
+    assert_eq!(function.get_function_signature_for(&crate::contract::ABI_VERSION_2_0), "no_output(uint15)()v2");
+    assert_eq!(
+        function.get_function_signature_for(&crate::contract::ABI_VERSION_1_0),
+        "no_output(time,expire,pubkey,uint64,uint15)()v1"
+    );
+    assert_eq!(
+        function.get_function_id_for(&crate::contract::ABI_VERSION_1_0),
+        Function::calc_function_id("no_output(time,expire,pubkey,uint64,uint15)()v1")
+    );
+    assert_eq!(function.get_function_id_for(&ABI_VERSION_2_4) & 0x7FFFFFFF, function.get_input_id());
+}
+
+#[test]
+fn test_function_param_lookup() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("input_and_output").unwrap();
+
+    assert_eq!(function.input_param("c"), Some(&Param::new("c", ParamType::Bytes)));
+    assert_eq!(function.input_param_index("c"), Some(2));
+    assert_eq!(function.input_param("d"), None);
+    assert_eq!(function.output_param("b"), Some(&Param::new("b", ParamType::Uint(8))));
+    assert_eq!(function.output_param_index("a"), Some(0));
+    assert_eq!(function.header_param("pubkey"), Some(&Param::new("pubkey", ParamType::PublicKey)));
+    assert_eq!(function.header_param_index("a"), Some(3));
+    assert_eq!(function.header_param_index("b"), None);
+}
+
+#[test]
+fn test_encode_run_local_input_with_header() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("no_output").unwrap();
+    let input = vec![Token::new("a", TokenValue::Uint(Uint::new(1, 15)))];
+
+    assert!(function.encode_run_local_input(123, &input).is_err());
+
+    let pubkey = ed25519_dalek::PublicKey::from_bytes(&[0xcc; 32]).unwrap();
+    let mut header = HashMap::new();
+    header.insert("pubkey".to_owned(), TokenValue::PublicKey(Some(pubkey)));
+    header.insert("a".to_owned(), TokenValue::Uint(Uint::new(789, 64)));
+
+    let body = function.encode_run_local_input_with_header(123, &header, &input).unwrap();
+    let (header_tokens, input_tokens) = function
+        .decode_input_with_header(SliceData::load_builder(body).unwrap(), false, false)
+        .unwrap();
+    assert_eq!(header_tokens, vec![
+        Token::new("time", TokenValue::Time(123)),
+        Token::new("expire", TokenValue::Expire(u32::MAX)),
+        Token::new("pubkey", TokenValue::PublicKey(Some(pubkey))),
+        Token::new("a", TokenValue::Uint(Uint::new(789, 64))),
+    ]);
+    assert_eq!(input_tokens, input);
+}
+
+#[test]
+fn test_function_display() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+
+    assert_eq!(
+        contract.function("input_and_output").unwrap().to_string(),
+        format!(
+            "input_and_output(a: uint64, b: uint8[], c: bytes) -> (a: int16, b: uint8) [in 0x{:08X} / out 0x{:08X}]",
+            contract.function("input_and_output").unwrap().get_input_id(),
+            contract.function("input_and_output").unwrap().get_output_id(),
+        )
+    );
+    assert_eq!(
+        contract.function("has_id").unwrap().to_string(),
+        "has_id() -> () [in 0x01234567 / out 0x01234567]"
+    );
+}
+
+#[test]
+fn test_estimate_call_size() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("input_and_output").unwrap();
+    let input = vec![
+        Token::new("a", TokenValue::Uint(Uint::new(1, 64))),
+        Token::new("b", TokenValue::Array(ParamType::Uint(8), vec![TokenValue::Uint(Uint::new(2, 8))])),
+        Token::new("c", TokenValue::Bytes(vec![0x55; 200])),
+    ];
+
+    let mut header = HashMap::new();
+    header.insert("a".to_owned(), TokenValue::Uint(Uint::new(789, 64)));
+    let address = MsgAddressInt::with_standart(None, 0, [0x11; 32].into()).unwrap();
+    let pair = ed25519_dalek::Keypair::generate(&mut rand::thread_rng());
+    header.insert("pubkey".to_owned(), TokenValue::PublicKey(Some(pair.public)));
+
+    let body = function
+        .encode_input(&header, &input, false, Some((&pair, None)), Some(address))
+        .unwrap()
+        .into_cell()
+        .unwrap();
+    fn count(cell: &ton_types::Cell) -> (usize, usize, usize) {
+        (0..cell.references_count()).fold(
+            (cell.bit_length(), cell.references_count(), 1),
+            |(bits, refs, cells), i| {
+                let (r_bits, r_refs, r_cells) = count(&cell.reference(i).unwrap());
+                (bits + r_bits, refs + r_refs, cells + r_cells)
+            })
+    }
+
+    let size = function.estimate_call_size(&input, true).unwrap();
+    assert_eq!((size.bits, size.refs, size.cells), count(&body));
+
+    let unsigned = function.estimate_call_size(&input, false).unwrap();
+    assert_eq!(
+        size.bits - unsigned.bits,
+        (ed25519_dalek::SIGNATURE_LENGTH + ed25519_dalek::PUBLIC_KEY_LENGTH) * 8
+    );
+}
+
+#[test]
+fn test_call_encoder() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("no_output").unwrap();
+    let pair = ed25519_dalek::Keypair::generate(&mut rand::thread_rng());
+    let address = MsgAddressInt::with_standart(None, 0, [0x11; 32].into()).unwrap();
+
+    let mut header = HashMap::new();
+    header.insert("time".to_owned(), TokenValue::Time(123));
+    header.insert("expire".to_owned(), TokenValue::Expire(456));
+    header.insert("pubkey".to_owned(), TokenValue::PublicKey(Some(pair.public)));
+    header.insert("a".to_owned(), TokenValue::Uint(Uint::new(789, 64)));
+
+    let mut encoder = crate::function::CallEncoder::new();
+    for i in 0..3 {
+        let input = vec![Token::new("a", TokenValue::Uint(Uint::new(i, 15)))];
+        let expected = function
+            .encode_input(&header, &input, false, Some((&pair, None)), Some(address.clone()))
+            .unwrap();
+        let body = encoder
+            .encode_input(function, &header, &input, false, Some((&pair, None)), Some(address.clone()))
+            .unwrap();
+        assert_eq!(body, expected);
+    }
+
+    header.insert("time".to_owned(), TokenValue::Time(124));
+    let input = vec![Token::new("a", TokenValue::Uint(Uint::new(1, 15)))];
+    assert_eq!(
+        encoder.encode_input(function, &header, &input, false, None, None).unwrap(),
+        function.encode_input(&header, &input, false, None, None).unwrap()
+    );
+
+    // same function encoded by rules of other ABI version doesn't reuse cached header
+    let mut other = function.clone();
+    other.abi_version = crate::contract::ABI_VERSION_2_2;
+    assert_ne!(other.abi_version, function.abi_version);
+    assert_eq!(
+        encoder.encode_input(&other, &header, &input, false, None, None).unwrap(),
+        other.encode_input(&header, &input, false, None, None).unwrap()
+    );
+}
+
+#[test]
+fn test_signature_in_reference() {
+    use crate::function::SignaturePlacement;
+    use ed25519::signature::Verifier;
+
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("no_output").unwrap();
+
+    let mut header = HashMap::new();
+    header.insert("a".to_owned(), TokenValue::Uint(Uint::new(789, 64)));
+    let input = vec![Token::new("a", TokenValue::Uint(Uint::new(1, 15)))];
+    let address = MsgAddressInt::with_standart(None, 0, [0x11; 32].into()).unwrap();
+    let pair = ed25519_dalek::Keypair::generate(&mut rand::thread_rng());
+
+    let call = function
+        .prepare_unsigned_call_with_placement(&header, &input, false, true, SignaturePlacement::InReference)
+        .unwrap();
+    let hash = call.hash(Some(address.clone())).unwrap();
+    let unsigned_refs = call.builder.references_used();
+    let body = call.sign(&pair, Some(address), None).unwrap();
+    assert_eq!(body.references_used(), unsigned_refs + 1);
+
+    let mut body = SliceData::load_builder(body).unwrap();
+    assert!(body.get_next_bit().unwrap());
+    let signature = SliceData::load_cell(body.checked_drain_reference().unwrap())
+        .unwrap()
+        .get_next_bytes(ed25519_dalek::SIGNATURE_LENGTH)
+        .unwrap();
+    let signature = ed25519_dalek::Signature::from_bytes(&signature).unwrap();
+    pair.public.verify(hash.as_slice(), &signature).unwrap();
+
+    let body = SliceData::load_builder(
+        function
+            .prepare_unsigned_call_with_placement(&header, &input, false, true, SignaturePlacement::InReference)
+            .unwrap()
+            .sign(&pair, Some(address.clone()), None)
+            .unwrap()
+    ).unwrap();
+    assert!(function.verify_signature_with_placement(
+        body.clone(), &pair.public, Some(address.clone()), None, SignaturePlacement::InReference).unwrap());
+    assert!(!function.verify_signature_with_placement(
+        body, &pair.public, Some(address), None, SignaturePlacement::InCell).unwrap_or(false));
+
+    let call = function
+        .prepare_unsigned_call_with_placement(&header, &input, false, false, SignaturePlacement::InReference)
+        .unwrap();
+    let unsigned_refs = call.builder.references_used();
+    let body = call.fill_sign(None, None).unwrap();
+    assert_eq!(body.references_used(), unsigned_refs);
+    assert!(!SliceData::load_builder(body).unwrap().get_next_bit().unwrap());
+}
+
+#[test]
+fn test_signature_in_reference_full_root() {
+    use crate::function::SignaturePlacement;
+
+    let abi = r#"{
+        "version": "2.4",
+        "functions": [{
+            "name": "full",
+            "inputs": [
+                {"name": "a","type": "uint256"},
+                {"name": "b","type": "uint256"},
+                {"name": "c","type": "uint256"},
+                {"name": "d","type": "uint128"}
+            ],
+            "outputs": []
+        }]
+    }"#;
+    let contract = Contract::load(abi.as_bytes()).unwrap();
+    let function = contract.function("full").unwrap();
+
+    let input = vec![
+        Token::new("a", TokenValue::Uint(Uint::new(1, 256))),
+        Token::new("b", TokenValue::Uint(Uint::new(2, 256))),
+        Token::new("c", TokenValue::Uint(Uint::new(3, 256))),
+        Token::new("d", TokenValue::Uint(Uint::new(4, 128))),
+    ];
+    let address = MsgAddressInt::with_standart(None, 0, [0x11; 32].into()).unwrap();
+    let pair = ed25519_dalek::Keypair::generate(&mut rand::thread_rng());
+
+    for placement in [SignaturePlacement::InCell, SignaturePlacement::InReference] {
+        let call = function
+            .prepare_unsigned_call_with_placement(&HashMap::new(), &input, false, true, placement)
+            .unwrap();
+        // address is prepended to the call body while computing hash, so it should fit
+        call.hash(Some(address.clone())).unwrap();
+        let body = call.sign(&pair, Some(address.clone()), None).unwrap();
+        assert!(function.verify_signature_with_placement(
+            SliceData::load_builder(body).unwrap(), &pair.public, Some(address.clone()), None, placement
+        ).unwrap());
+    }
+}
+
+#[test]
+fn test_multi_sign() {
+    use crate::function::SignaturePlacement;
+    use ed25519::signature::Verifier;
+
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("no_output").unwrap();
+
+    let mut header = HashMap::new();
+    header.insert("a".to_owned(), TokenValue::Uint(Uint::new(789, 64)));
+    let input = vec![Token::new("a", TokenValue::Uint(Uint::new(1, 15)))];
+    let address = MsgAddressInt::with_standart(None, 0, [0x11; 32].into()).unwrap();
+    let pairs = (0..3)
+        .map(|_| ed25519_dalek::Keypair::generate(&mut rand::thread_rng()))
+        .collect::<Vec<_>>();
+
+    let call = function
+        .prepare_unsigned_call_with_placement(&header, &input, false, true, SignaturePlacement::InReference)
+        .unwrap();
+    let data = call.data_to_sign(Some(address), None).unwrap();
+    let unsigned = call.builder.clone();
+    let signatures = pairs[..2]
+        .iter()
+        .map(|pair| (Signer::sign(pair, &data).unwrap(), pair.public))
+        .collect::<Vec<_>>();
+
+    let body = call.fill_multi_sign(&signatures).unwrap();
+    let (extracted, rest) = Function::extract_signatures(&ABI_VERSION_2_4, SliceData::load_builder(body.clone()).unwrap()).unwrap();
+    assert_eq!(extracted, signatures);
+    assert_eq!(BuilderData::from_slice(&rest), unsigned);
+    for (signature, public_key) in &extracted {
+        public_key.verify(&data, signature).unwrap();
+    }
+
+    // chain entries beyond signatures count are rejected
+    let list = SliceData::load_builder(body).unwrap().reference(0).unwrap();
+    let mut short_list = BuilderData::new();
+    short_list.append_u8(1).unwrap();
+    short_list.checked_append_reference(list.reference(0).unwrap()).unwrap();
+    let mut tampered = unsigned.clone();
+    tampered.checked_prepend_reference(short_list.into_cell().unwrap()).unwrap();
+    let mut prefix = BuilderData::new();
+    prefix.append_bit_one().unwrap();
+    tampered.prepend_builder(&prefix).unwrap();
+    let err = Function::extract_signatures(&ABI_VERSION_2_4, SliceData::load_builder(tampered).unwrap())
+        .unwrap_err();
+    assert!(err.to_string().contains("beyond 1 signatures"), "{}", err);
+
+    // partial signing
+    let body = Function::fill_multi_sign(&ABI_VERSION_2_4, &[], unsigned.clone()).unwrap();
+    assert!(Function::extract_signatures(&ABI_VERSION_2_4, SliceData::load_builder(body.clone()).unwrap()).unwrap().0.is_empty());
+    let mut body = SliceData::load_builder(body).unwrap();
+    for pair in &pairs {
+        let signature = Signer::sign(pair, &data).unwrap();
+        body = SliceData::load_builder(
+            Function::append_signature(&ABI_VERSION_2_4, signature, pair.public, body).unwrap()
+        ).unwrap();
+    }
+    let (extracted, rest) = Function::extract_signatures(&ABI_VERSION_2_4, body.clone()).unwrap();
+    assert_eq!(extracted.iter().map(|(_, key)| *key).collect::<Vec<_>>(), pairs.iter().map(|pair| pair.public).collect::<Vec<_>>());
+    assert_eq!(BuilderData::from_slice(&rest), unsigned);
+
+    let signature = Signer::sign(&pairs[0], &data).unwrap();
+    assert!(Function::append_signature(&ABI_VERSION_2_4, signature, pairs[0].public, body).is_err());
+
+    let call = function.prepare_unsigned_call(&header, &input, false, true).unwrap();
+    assert!(call.fill_multi_sign(&signatures).is_err());
+}
+
+#[test]
+fn test_unpack_signature() {
+    use ed25519::signature::Verifier;
+
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("no_output").unwrap();
+
+    let mut header = HashMap::new();
+    header.insert("a".to_owned(), TokenValue::Uint(Uint::new(789, 64)));
+    let input = vec![Token::new("a", TokenValue::Uint(Uint::new(1, 15)))];
+    let address = MsgAddressInt::with_standart(None, 0, [0x11; 32].into()).unwrap();
+    let pair = ed25519_dalek::Keypair::generate(&mut rand::thread_rng());
+
+    let (unsigned, hash) = function
+        .create_unsigned_call(&header, &input, false, true, Some(address.clone()))
+        .unwrap();
+    let body = function
+        .encode_input(&header, &input, false, Some((&pair, None)), Some(address))
+        .unwrap();
+
+    let (signature, public_key, rest) =
+        Function::unpack_signature(&function.abi_version, SliceData::load_builder(body).unwrap()).unwrap();
+    assert!(public_key.is_none());
+    assert_eq!(rest, SliceData::load_builder(unsigned).unwrap());
+    pair.public.verify(hash.as_slice(), &signature.unwrap()).unwrap();
+
+    let body = function.encode_input(&header, &input, false, None, None).unwrap();
+    let (signature, public_key, _) =
+        Function::unpack_signature(&function.abi_version, SliceData::load_builder(body).unwrap()).unwrap();
+    assert!(signature.is_none() && public_key.is_none());
+}
+
+#[test]
+fn test_unpack_call_signature() {
+    use crate::function::SignaturePlacement;
+    use ed25519::signature::Verifier;
+
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("no_output").unwrap();
+
+    let address = MsgAddressInt::with_standart(None, 0, [0x11; 32].into()).unwrap();
+    let pair = ed25519_dalek::Keypair::generate(&mut rand::thread_rng());
+    let mut header = HashMap::new();
+    header.insert("a".to_owned(), TokenValue::Uint(Uint::new(789, 64)));
+    header.insert("pubkey".to_owned(), TokenValue::PublicKey(Some(pair.public)));
+    let input = vec![Token::new("a", TokenValue::Uint(Uint::new(1, 15)))];
+
+    for placement in [SignaturePlacement::InCell, SignaturePlacement::InReference] {
+        let call = function
+            .prepare_unsigned_call_with_placement(&header, &input, false, true, placement)
+            .unwrap();
+        let hash = call.hash(Some(address.clone())).unwrap();
+        let unsigned = call.builder.clone();
+        let body = SliceData::load_builder(call.sign(&pair, Some(address.clone()), None).unwrap()).unwrap();
+
+        let (signature, public_key, rest) = function.unpack_call_signature(body.clone(), placement).unwrap();
+        assert_eq!(public_key, Some(pair.public));
+        assert_eq!(rest, SliceData::load_builder(unsigned).unwrap());
+        pair.public.verify(hash.as_slice(), &signature.unwrap()).unwrap();
+
+        let (_, public_key, _) =
+            Function::unpack_signature_with_placement(&function.abi_version, body, placement).unwrap();
+        assert_eq!(public_key.is_some(), placement == SignaturePlacement::InReference);
+
+        let body = function
+            .prepare_unsigned_call_with_placement(&header, &input, false, false, placement)
+            .unwrap()
+            .fill_sign(None, None)
+            .unwrap();
+        let (signature, public_key, _) =
+            function.unpack_call_signature(SliceData::load_builder(body).unwrap(), placement).unwrap();
+        assert!(signature.is_none() && public_key.is_none());
+    }
+}
+
+#[test]
+fn test_event_selectors() {
+    use sha2::{Digest, Sha256};
+
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let selectors = contract.event_selectors();
+    assert_eq!(selectors.len(), contract.events.len());
+
+    let selector = &selectors["input"];
+    assert_eq!(selector.signature, "input(uint64)v2");
+    assert_eq!(selector.id, Function::calc_function_id("input(uint64)v2") & 0x7FFFFFFF);
+    assert_eq!(selector.topic.as_slice(), Sha256::digest(b"input(uint64)v2").as_slice());
+
+    assert_eq!(selectors["has_id"].id, 0x89abcdef);
+}
+
+#[test]
+fn test_lookup_by_id() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("input_and_output").unwrap().clone();
+
+    assert_eq!(contract.function_by_input_id(function.get_input_id()).unwrap(), &function);
+    assert_eq!(contract.function_by_output_id(function.get_output_id()).unwrap(), &function);
+    assert!(contract.function_by_input_id(function.get_output_id()).is_err());
+    assert_eq!(contract.event_by_id(0x89abcdef).unwrap().name, "has_id");
+    assert!(contract.event_by_id(0x01234567).is_err());
+
+    // functions and events inserted into public maps are found without index rebuild
+    let mut contract = contract;
+    let mut added = function.clone();
+    added.name = "added".to_owned();
+    added.input_id = 0x01234567;
+    added.output_id = 0x81234567;
+    contract.functions.insert(added.name.clone(), added.clone());
+    let mut event = contract.event("has_id").unwrap().clone();
+    event.name = "added".to_owned();
+    event.id = 0x01234567;
+    contract.events.insert(event.name.clone(), event);
+    assert_eq!(contract.function_by_input_id(0x01234567).unwrap(), &added);
+    assert_eq!(contract.function_by_output_id(0x81234567).unwrap(), &added);
+    assert_eq!(contract.event_by_id(0x01234567).unwrap().name, "added");
+}
+
+#[test]
+fn test_fingerprint() {
+    let abi = r#"{"version":"2.4","header":["time"],"functions":[
+        {"name":"a","inputs":[{"name":"x","type":"uint8"}],"outputs":[]},
+        {"name":"b","inputs":[],"outputs":[{"name":"y","type":"tuple","components":[{"name":"z","type":"bool"}]}]}
+    ],"events":[{"name":"e1","inputs":[]},{"name":"e2","inputs":[]}],
+    "fields":[{"name":"f","type":"uint32","init":true}]}"#;
+    let reordered = r#"{
+        "version": "2.4",
+        "header": ["time"],
+        "events": [{"name": "e2", "inputs": []}, {"name": "e1", "inputs": []}],
+        "functions": [
+            {"name": "b", "inputs": [], "outputs": [
+                {"name": "y", "type": "tuple", "components": [{"name": "z", "type": "bool"}]}
+            ]},
+            {"name": "a", "inputs": [{"name": "x", "type": "uint8"}], "outputs": []}
+        ],
+        "fields": [{"name": "f", "type": "uint32", "init": true}]
+    }"#;
+    let renamed = abi.replace(r#""name":"z""#, r#""name":"w""#);
+
+    let contract = Contract::load(abi.as_bytes()).unwrap();
+    assert_eq!(contract.fingerprint(), Contract::load(reordered.as_bytes()).unwrap().fingerprint());
+    assert_ne!(contract.fingerprint(), Contract::load(renamed.as_bytes()).unwrap().fingerprint());
+
+    let canonical = contract.canonical_form();
+    assert!(canonical.starts_with("version:2.4\nheader:time:time\nfunction:a(x:uint8)()"));
+    assert!(canonical.contains("b()(y:(z:bool))"));
+    assert!(canonical.ends_with("fields:init f:uint32"));
+}
+
+#[test]
+fn test_merge() {
+    let interface = r#"{
+        "version": "2.4",
+        "header": ["time", "expire", "pubkey", {"name": "a", "type": "uint64"}],
+        "functions": [
+            {"name": "no_output", "inputs": [{"name": "a", "type": "uint15"}], "outputs": []},
+            {"name": "transfer", "inputs": [{"name": "to", "type": "address"}], "outputs": []}
+        ],
+        "events": [{"name": "Transferred", "inputs": []}]
+    }"#;
+    let implementation = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let interface = Contract::load(interface.as_bytes()).unwrap();
+
+    let merged = implementation.merge(&interface).unwrap();
+    assert_eq!(merged.functions.len(), implementation.functions.len() + 1);
+    assert_eq!(merged.events.len(), implementation.events.len() + 1);
+    assert_eq!(merged.fields, implementation.fields);
+    let transfer = merged.function("transfer").unwrap();
+    assert_eq!(merged.function_by_input_id(transfer.get_input_id()).unwrap(), transfer);
+
+    let mut conflicting = interface.clone();
+    conflicting.functions.get_mut("no_output").unwrap().inputs[0].kind = ParamType::Uint(16);
+    assert!(implementation.merge(&conflicting).is_err());
+
+    let mut conflicting = interface.clone();
+    let mut function = conflicting.functions.remove("transfer").unwrap();
+    function.name = "transfer2".to_owned();
+    function.input_id = implementation.function("no_input").unwrap().get_input_id();
+    conflicting.functions.insert("transfer2".to_owned(), function);
+    assert!(implementation.merge(&conflicting).is_err());
+
+    // merged ABI is checked for id collisions of all kinds
+    let mut conflicting = interface.clone();
+    let mut event = conflicting.events.remove("Transferred").unwrap();
+    event.id = implementation.function("no_input").unwrap().get_output_id();
+    conflicting.events.insert(event.name.clone(), event);
+    assert!(implementation.merge(&conflicting).is_err());
+
+    let getters = r#"{
+        "version": "2.4",
+        "functions": [],
+        "getters": [{"name": "balance", "id": "0x1000", "inputs": [], "outputs": []}]
+    }"#;
+    let getters = Contract::load(getters.as_bytes()).unwrap();
+    let mut conflicting = getters.clone();
+    let mut getter = conflicting.getters.remove("balance").unwrap();
+    getter.name = "balance2".to_owned();
+    conflicting.getters.insert(getter.name.clone(), getter);
+    let err = getters.merge(&conflicting).unwrap_err();
+    assert!(err.to_string().contains("Getters balance, balance2 have the same id"), "{}", err);
+
+    let mut conflicting = interface;
+    conflicting.header.pop();
+    assert!(implementation.merge(&conflicting).is_err());
+}
+
+#[test]
+fn test_load_cached() {
+    let first = Contract::load_cached(TEST_ABI.as_bytes()).unwrap();
+    let second = Contract::load_cached(TEST_ABI.as_bytes()).unwrap();
+    assert!(std::sync::Arc::ptr_eq(&first, &second));
+    assert_eq!(*first, Contract::load(TEST_ABI.as_bytes()).unwrap());
+
+    assert!(Contract::load_cached(b"{}").is_err());
+}
+
+#[test]
+fn test_try_from_state_init() {
+    use ton_block::StateInitLib;
+    use ton_types::{Cell, HashmapE, UInt256};
+
+    let library_hash = UInt256::from([0x11; 32]);
+    let mut state_init = StateInit::default();
+    assert!(matches!(
+        Contract::try_from_state_init(&state_init, &library_hash).unwrap_err().downcast_ref::<crate::AbiError>(),
+        Some(crate::AbiError::NoEmbeddedAbi)
+    ));
+
+    let mut next: Option<Cell> = None;
+    for chunk in TEST_ABI.as_bytes().chunks(127).rev() {
+        let mut builder = BuilderData::with_raw(chunk.to_vec().into(), chunk.len() * 8).unwrap();
+        if let Some(cell) = next {
+            builder.checked_append_reference(cell).unwrap();
+        }
+        next = Some(builder.into_cell().unwrap());
+    }
+    let abi_cell = next.unwrap();
+    assert_eq!(
+        Contract::load_from_cell(abi_cell.clone()).unwrap(),
+        Contract::load(TEST_ABI.as_bytes()).unwrap()
+    );
+
+    let mut lib = BuilderData::new();
+    lib.append_bit_one().unwrap();
+    lib.checked_append_reference(abi_cell).unwrap();
+
+    let mut map = HashmapE::with_bit_len(256);
+    map.set_builder(SliceData::from_raw(vec![0x11; 32], 256), &lib).unwrap();
+    state_init.library = StateInitLib::construct_from_cell(map.serialize().unwrap()).unwrap();
+
+    assert_eq!(
+        Contract::try_from_state_init(&state_init, &library_hash).unwrap(),
+        Contract::load(TEST_ABI.as_bytes()).unwrap()
+    );
+    assert!(matches!(
+        Contract::try_from_state_init(&state_init, &UInt256::from([0x22; 32]))
+            .unwrap_err().downcast_ref::<crate::AbiError>(),
+        Some(crate::AbiError::NoEmbeddedAbi)
+    ));
+}
+
+#[test]
+fn test_load_duplicates() {
+    let abi = r#"{"version":"2.2","header":["time"],"functions":[
+        {"name":"a","inputs":[{"name":"x","type":"uint8"}],"outputs":[]},
+        {"name":"a","inputs":[{"name":"x","type":"uint16"}],"outputs":[]}
+    ],"events":[]}"#;
+    let strict = || LoadOptions { deny_duplicates: true, ..Default::default() };
+    assert!(Contract::load_with_options(abi.as_bytes(), strict()).is_err());
+    let contract = Contract::load(abi.as_bytes()).unwrap();
+    assert_eq!(contract.function("a").unwrap().inputs[0].kind, ParamType::Uint(16));
+
+    let abi = r#"{"version":"2.2","functions":[],"events":[
+        {"name":"e","inputs":[]},
+        {"name":"e","inputs":[{"name":"x","type":"bool"}]}
+    ]}"#;
+    assert!(Contract::load_with_options(abi.as_bytes(), strict()).is_err());
+    assert!(Contract::load(abi.as_bytes()).is_ok());
+}
+
+#[test]
+fn test_declaration_order() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    assert_eq!(
+        contract.functions_in_order().iter().map(|f| f.name.as_str()).collect::<Vec<_>>(),
+        vec!["input_and_output", "no_output", "no_input", "constructor", "has_id"]
+    );
+    assert_eq!(
+        contract.events_in_order().iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+        vec!["input", "no_input", "has_id"]
+    );
+    assert_eq!(
+        contract.data_in_order().iter().map(|d| d.value.name.as_str()).collect::<Vec<_>>(),
+        vec!["a"]
+    );
+
+    // declaration order doesn't affect contract equality
+    let mut unordered = contract.clone();
+    unordered.order = Default::default();
+    assert_ne!(unordered.functions_in_order(), contract.functions_in_order());
+    assert_eq!(unordered, contract);
+
+    let mut contract = contract;
+    let mut extra = contract.function("constructor").unwrap().clone();
+    extra.name = "extra".to_owned();
+    contract.functions.insert("extra".to_owned(), extra.clone());
+    extra.name = "another".to_owned();
+    contract.functions.insert("another".to_owned(), extra);
+    assert_eq!(
+        contract.functions_in_order().iter().map(|f| f.name.as_str()).collect::<Vec<_>>(),
+        vec!["input_and_output", "no_output", "no_input", "constructor", "has_id", "another", "extra"]
+    );
+}
+
+#[test]
+fn test_load_from_file() {
+    use std::error::Error;
+
+    let source = |err: ton_types::Error| match err.downcast::<crate::AbiError>() {
+        Ok(crate::AbiError::InvalidFile { source, .. }) => source,
+        other => panic!("unexpected error {:?}", other),
+    };
+
+    let dir = std::env::temp_dir();
+    let plain = dir.join(format!("ton_abi_test_{}.abi.json", std::process::id()));
+    std::fs::write(&plain, TEST_ABI).unwrap();
+    let expected = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    assert_eq!(Contract::load_from_file(&plain).unwrap(), expected);
+
+    std::fs::write(&plain, "{\n  \"version\": \"2.4\"\n}").unwrap();
+    let err = Contract::load_from_file(&plain).unwrap_err();
+    assert!(err.to_string().contains(&plain.display().to_string()), "{}", err);
+    assert!(err.downcast_ref::<crate::AbiError>().unwrap().source().is_some());
+    match source(err).downcast::<crate::AbiError>().map(|err| *err) {
+        Ok(crate::AbiError::InvalidAbiJson { line, .. }) => assert_eq!(line, 3),
+        other => panic!("unexpected error {:?}", other),
+    }
+
+    std::fs::remove_file(plain).unwrap();
+    let err = Contract::load_from_file(dir.join("ton_abi_missing.json")).unwrap_err();
+    let kind = source(err).downcast::<std::io::Error>().unwrap().kind();
+    assert_eq!(kind, std::io::ErrorKind::NotFound);
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_load_from_gzip_file() {
+    use std::io::Write;
+
+    let compressed = std::env::temp_dir().join(format!("ton_abi_test_{}.abi.json.gz", std::process::id()));
+    let mut encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+    encoder.write_all(TEST_ABI.as_bytes()).unwrap();
+    std::fs::write(&compressed, encoder.finish().unwrap()).unwrap();
+    let expected = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    assert_eq!(Contract::load_from_file(&compressed).unwrap(), expected);
+    std::fs::remove_file(compressed).unwrap();
+}
+
+#[test]
+fn test_encode_deploy_message() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let pair = ed25519_dalek::Keypair::generate(&mut rand::thread_rng());
+    let code = BuilderData::with_raw(vec![0x55], 8).unwrap().into_cell().unwrap();
+    let mut init = HashMap::new();
+    init.insert("b".to_owned(), TokenValue::Int(crate::Int::new(-1, 128)));
+
+    let message = contract
+        .encode_deploy_message(&HashMap::new(), &[], code.clone(), init.clone(), Some((&pair, None)), 0)
+        .unwrap();
+
+    let address = contract
+        .compute_address(code, init, 0, Some(&pair.public.to_bytes()))
+        .unwrap();
+    assert_eq!(message.dst().unwrap(), address);
+    assert!(message.state_init().is_some());
+
+    let constructor = contract.function("constructor").unwrap();
+    let body = message.body().unwrap();
+    assert!(constructor.verify_signature(body.clone(), &pair.public, Some(address), None).unwrap());
+    let (header, _) = constructor.decode_input_with_header(body, false, false).unwrap();
+    assert!(header.contains(&Token::new("pubkey", TokenValue::PublicKey(Some(pair.public)))));
+}
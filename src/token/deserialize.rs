@@ -12,20 +12,41 @@
 */
 
 use crate::{
-    contract::{AbiVersion, ABI_VERSION_1_0, ABI_VERSION_2_0, ABI_VERSION_2_2, ABI_VERSION_2_4},
+    contract::{AbiVersion, ABI_VERSION_1_0, ABI_VERSION_2_0, ABI_VERSION_2_2},
     error::AbiError,
     int::{Int, Uint},
     param::Param,
-    param_type::ParamType,
-    token::{Token, TokenValue},
+    param_type::{ParamType, MAX_SLICE_BITS, SLICE_LEN_BITS},
+    token::{MapKeyTokenValue, ParamPath, Token, TokenValue},
+    warnings::Warnings,
 };
 
-use ton_types::{HashmapType, BuilderData, fail, error, Cell, HashmapE, IBitstring, Result, SliceData, serialize_tree_of_cells};
+use ton_types::{HashmapType, BuilderData, fail, error, Cell, CellType, HashmapE, IBitstring, Result, SliceData, serialize_tree_of_cells};
 use ton_block::{types::Grams, MsgAddress};
 use num_bigint::{BigInt, BigUint};
 use num_traits::ToPrimitive;
 use std::{collections::BTreeMap, convert::TryInto};
 
+/// Default maximum nesting depth of decoded param types
+pub const MAX_DECODE_DEPTH: usize = 64;
+
+/// Nesting level of decoded value together with maximum allowed level
+#[derive(Clone, Copy, Debug)]
+struct DecodeDepth {
+    level: usize,
+    max: usize,
+}
+
+impl DecodeDepth {
+    fn new(max: usize) -> Self {
+        Self { level: 1, max }
+    }
+
+    fn nested(self) -> Self {
+        Self { level: self.level + 1, ..self }
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct Cursor {
     pub used_bits: usize,
@@ -43,6 +64,36 @@ impl From<SliceData> for Cursor {
     }
 }
 
+/// Defines how data remaining after all params are decoded is handled
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TrailingDataPolicy {
+    /// Remaining data causes `IncompleteDeserializationError`
+    Error,
+    /// Remaining data is ignored
+    Ignore,
+    /// Remaining data is returned to caller
+    ReturnRemainder,
+}
+
+/// Param decoded from data which can contain pruned branch cells (e.g. extracted from
+/// Merkle proof)
+#[derive(Debug, PartialEq, Clone)]
+pub enum DecodedToken {
+    /// Param value is present in data
+    Available(Token),
+    /// Param value is stored in pruned branch. Contains param name
+    Unavailable(String),
+}
+
+/// Error occurred while decoding particular param
+#[derive(Debug)]
+pub struct ParamDecodeError {
+    /// Path to param, tuple components are separated by dots
+    pub path: String,
+    /// Decoding error
+    pub error: anyhow::Error,
+}
+
 impl TokenValue {
     /// Deserializes value from `SliceData` to `TokenValue`
     pub fn read_from(
@@ -52,29 +103,66 @@ impl TokenValue {
         abi_version: &AbiVersion,
         allow_partial: bool,
     ) -> Result<(Self, Cursor)> {
+        Self::read_value(param_type, cursor, last, abi_version, allow_partial, DecodeDepth::new(MAX_DECODE_DEPTH))
+    }
+
+    /// Reads value nested at `depth` (top level values have level 1). Nested values are read
+    /// recursively, so reading fails if level exceeds maximum depth to keep recursion bounded.
+    fn read_value(
+        param_type: &ParamType,
+        mut cursor: Cursor,
+        last: bool,
+        abi_version: &AbiVersion,
+        allow_partial: bool,
+        depth: DecodeDepth,
+    ) -> Result<(Self, Cursor)> {
+        if depth.level > depth.max {
+            fail!(AbiError::MaxDepthExceeded {
+                name: param_type.type_signature(),
+                depth: depth.level,
+                max: depth.max,
+            });
+        }
         let slice = cursor.slice.clone();
         let (value, slice) = match param_type {
             ParamType::Uint(size) => Self::read_uint(*size, slice),
             ParamType::Int(size) => Self::read_int(*size, slice),
             ParamType::VarUint(size) => Self::read_varuint(*size, slice),
             ParamType::VarInt(size) => Self::read_varint(*size, slice),
+            ParamType::FixedPoint { bits, decimals } => {
+                let (number, slice) = Self::read_int_from_chain(*bits, slice)?;
+                Ok((TokenValue::FixedPoint(Int { number, size: *bits }, *decimals), slice))
+            }
+            ParamType::Bits(size) => {
+                let (vec, slice) = get_next_bits_from_chain(slice, *size)?;
+                Ok((TokenValue::Bits(SliceData::from_raw(vec, *size)), slice))
+            }
+            ParamType::Slice => Self::read_slice(slice),
+            ParamType::Enum(variants) => {
+                let (index, slice) = Self::read_uint_from_chain(8, slice)?;
+                let index = match index.to_u8() {
+                    Some(index) if (index as usize) < variants.len() => index,
+                    _ => fail!(AbiError::InvalidData { msg: format!("Unknown enum variant index {}", index) }),
+                };
+                Ok((TokenValue::Enum(variants.clone(), index), slice))
+            }
             ParamType::Bool => {
                 let mut slice = find_next_bits(slice, 1)?;
                 Ok((TokenValue::Bool(slice.get_next_bit()?), slice))
             }
             ParamType::Tuple(tuple_params) => {
-                return Self::read_tuple(tuple_params, cursor, last, abi_version, allow_partial);
+                return Self::read_tuple(tuple_params, cursor, last, abi_version, allow_partial, depth);
             }
             ParamType::Array(item_type) => {
-                Self::read_array(&item_type, slice, abi_version, allow_partial)
+                Self::read_array(&item_type, slice, abi_version, allow_partial, depth)
             }
             ParamType::FixedArray(item_type, size) => {
-                Self::read_fixed_array(&item_type, *size, slice, abi_version, allow_partial)
+                Self::read_fixed_array(&item_type, *size, slice, abi_version, allow_partial, depth)
             }
             ParamType::Cell => Self::read_cell(slice, last, abi_version)
                 .map(|(cell, slice)| (TokenValue::Cell(cell), slice)),
             ParamType::Map(key_type, value_type) => {
-                Self::read_hashmap(key_type, value_type, slice, abi_version, allow_partial)
+                Self::read_hashmap(key_type, value_type, slice, abi_version, allow_partial, depth)
             }
             ParamType::Address => {
                 let mut slice = find_next_bits(slice, 1)?;
@@ -97,6 +185,36 @@ impl TokenValue {
                 }
                 Ok((TokenValue::AddressStd(address), slice))
             }
+            ParamType::AddressVar => {
+                let mut slice = find_next_bits(slice, 1)?;
+                let address =
+                    <MsgAddress as ton_block::Deserializable>::construct_from(&mut slice)?;
+                match address {
+                    MsgAddress::AddrNone => {}
+                    MsgAddress::AddrVar(_) => {}
+                    MsgAddress::AddrStd(_) | MsgAddress::AddrExt(_) => {
+                        fail!(AbiError::InvalidData {
+                            msg: "Expected var or none address".to_string(),
+                        })
+                    }
+                }
+                Ok((TokenValue::AddressVar(address), slice))
+            }
+            ParamType::AddressExt => {
+                let mut slice = find_next_bits(slice, 1)?;
+                let address =
+                    <MsgAddress as ton_block::Deserializable>::construct_from(&mut slice)?;
+                match address {
+                    MsgAddress::AddrNone => {}
+                    MsgAddress::AddrExt(_) => {}
+                    MsgAddress::AddrStd(_) | MsgAddress::AddrVar(_) => {
+                        fail!(AbiError::InvalidData {
+                            msg: "Expected external or none address".to_string(),
+                        })
+                    }
+                }
+                Ok((TokenValue::AddressExt(address), slice))
+            }
             ParamType::Bytes => Self::read_bytes(slice, last, abi_version),
             ParamType::FixedBytes(size) => Self::read_fixed_bytes(*size, slice, last, abi_version),
             ParamType::String => Self::read_string(slice, last, abi_version),
@@ -109,10 +227,10 @@ impl TokenValue {
             ParamType::Expire => Self::read_expire(slice),
             ParamType::PublicKey => Self::read_public_key(slice),
             ParamType::Optional(inner_type) => {
-                Self::read_optional(&inner_type, slice, last, abi_version, allow_partial)
+                Self::read_optional(&inner_type, slice, last, abi_version, allow_partial, depth)
             }
             ParamType::Ref(inner_type) => {
-                Self::read_ref(&inner_type, slice, last, abi_version, allow_partial)
+                Self::read_ref(&inner_type, slice, last, abi_version, allow_partial, depth)
             }
         }?;
 
@@ -147,8 +265,8 @@ impl TokenValue {
         let orig_cell = cursor.slice.cell();
         if abi_version >= &ABI_VERSION_2_2 {
             println!("{param_type}");
-            let param_max_bits = Self::max_bit_size(param_type, abi_version);
-            let param_max_refs = Self::max_refs_count(param_type, abi_version);
+            let param_max_bits = param_type.max_bit_size(abi_version);
+            let param_max_refs = param_type.max_refs_count(abi_version);
             if new_cell != orig_cell {
                 if cursor.used_bits + param_max_bits <= BuilderData::bits_capacity()
                     && (last
@@ -244,19 +362,34 @@ impl TokenValue {
         }
     }
 
+    fn read_slice(cursor: SliceData) -> Result<(Self, SliceData)> {
+        let (len, cursor) = Self::read_uint_from_chain(SLICE_LEN_BITS, cursor)?;
+        let len = len.to_usize().unwrap();
+        if len > MAX_SLICE_BITS {
+            fail!(AbiError::InvalidData { msg: format!("Too long slice: {} bits", len) });
+        }
+        if len == 0 {
+            return Ok((TokenValue::Slice(SliceData::from_raw(vec![], 0)), cursor));
+        }
+        let (vec, cursor) = get_next_bits_from_chain(cursor, len)?;
+        Ok((TokenValue::Slice(SliceData::from_raw(vec, len)), cursor))
+    }
+
     fn read_tuple(
         tuple_params: &[Param],
         cursor: Cursor,
         last: bool,
         abi_version: &AbiVersion,
         allow_partial: bool,
+        depth: DecodeDepth,
     ) -> Result<(Self, Cursor)> {
-        let (tokens, cursor) = Self::decode_params_with_cursor(
+        let (tokens, cursor) = Self::read_params(
             tuple_params,
             cursor,
             abi_version,
             allow_partial,
             last,
+            depth.nested(),
         )?;
         Ok((TokenValue::Tuple(tokens), cursor))
     }
@@ -277,10 +410,11 @@ impl TokenValue {
         size: usize,
         abi_version: &AbiVersion,
         allow_partial: bool,
+        depth: DecodeDepth,
     ) -> Result<(Vec<Self>, SliceData)> {
         let original = cursor.clone();
         cursor = find_next_bits(cursor, 1)?;
-        let map = HashmapE::with_hashmap(32, cursor.get_dictionary()?.reference_opt(0));
+        let map = HashmapE::with_hashmap(32, read_dictionary_root(&mut cursor)?);
         if map.count(size + 1)? != size {
             fail!(AbiError::DeserializationError {
                 msg: "Array contains more items then declared",
@@ -292,26 +426,14 @@ impl TokenValue {
             let mut index = BuilderData::new();
             index.append_u32(i as u32)?;
             match map.get(SliceData::load_builder(index)?) {
-                Ok(Some(mut item_slice)) => {
-                    let do_load_ref =
-                        if abi_version == &ABI_VERSION_1_0 || abi_version == &ABI_VERSION_2_0 {
-                            item_slice.remaining_bits() == 0
-                                && Self::max_bit_size(item_type, abi_version) != 0
-                        } else {
-                            let value_len = Self::max_bit_size(item_type, abi_version);
-                            Self::map_value_in_ref(32, value_len)
-                        };
-                    if do_load_ref {
-                        item_slice = SliceData::load_cell(item_slice.checked_drain_reference()?)?;
-                    }
-                    let (token, _) = Self::read_from(
+                Ok(Some(item_slice)) => {
+                    result.push(Self::read_array_item(
                         item_type,
-                        item_slice.into(),
-                        true,
+                        item_slice,
                         abi_version,
                         allow_partial,
-                    )?;
-                    result.push(token);
+                        depth.nested(),
+                    ).map_err(|err| ParamPath::prepend(err, format!("[{}]", i)))?);
                 }
                 _ => fail!(AbiError::DeserializationError {
                     msg: "Array doesn't contain item with specified index",
@@ -323,11 +445,33 @@ impl TokenValue {
         Ok((result, cursor))
     }
 
+    fn read_array_item(
+        item_type: &ParamType,
+        mut item_slice: SliceData,
+        abi_version: &AbiVersion,
+        allow_partial: bool,
+        depth: DecodeDepth,
+    ) -> Result<Self> {
+        let do_load_ref = if abi_version == &ABI_VERSION_1_0 || abi_version == &ABI_VERSION_2_0 {
+            item_slice.remaining_bits() == 0 && item_type.max_bit_size(abi_version) != 0
+        } else {
+            let value_len = item_type.max_bit_size(abi_version);
+            Self::map_value_in_ref(32, value_len)
+        };
+        if do_load_ref {
+            item_slice = load_ref(item_slice.checked_drain_reference()?)?;
+        }
+        let (token, _) =
+            Self::read_value(item_type, item_slice.into(), true, abi_version, allow_partial, depth)?;
+        Ok(token)
+    }
+
     fn read_array(
         item_type: &ParamType,
         mut cursor: SliceData,
         abi_version: &AbiVersion,
         allow_partial: bool,
+        depth: DecodeDepth,
     ) -> Result<(Self, SliceData)> {
         cursor = find_next_bits(cursor, 32)?;
         let size = cursor.get_next_u32()?;
@@ -337,6 +481,7 @@ impl TokenValue {
             size as usize,
             abi_version,
             allow_partial,
+            depth,
         )?;
 
         Ok((TokenValue::Array(item_type.clone(), result), cursor))
@@ -348,9 +493,10 @@ impl TokenValue {
         cursor: SliceData,
         abi_version: &AbiVersion,
         allow_partial: bool,
+        depth: DecodeDepth,
     ) -> Result<(Self, SliceData)> {
         let (result, cursor) =
-            Self::read_array_from_map(item_type, cursor, size, abi_version, allow_partial)?;
+            Self::read_array_from_map(item_type, cursor, size, abi_version, allow_partial, depth)?;
 
         Ok((TokenValue::FixedArray(item_type.clone(), result), cursor))
     }
@@ -365,7 +511,7 @@ impl TokenValue {
                 && cursor.cell().references_count() == BuilderData::references_capacity())
                 || (abi_version != &ABI_VERSION_1_0 && !last && cursor.remaining_bits() == 0) =>
                 {
-                    cursor = SliceData::load_cell(cursor.reference(0)?)?;
+                    cursor = load_ref(cursor.reference(0)?)?;
                     cursor.checked_drain_reference()?
                 }
             _ => cursor.checked_drain_reference()?,
@@ -379,22 +525,23 @@ impl TokenValue {
         mut cursor: SliceData,
         abi_version: &AbiVersion,
         allow_partial: bool,
+        depth: DecodeDepth,
     ) -> Result<(Self, SliceData)> {
         let bit_len = TokenValue::get_map_key_size(key_type)?;
-        let value_len = Self::max_bit_size(value_type, abi_version);
+        let value_len = value_type.max_bit_size(abi_version);
         let value_in_ref = Self::map_value_in_ref(bit_len, value_len);
 
         cursor = find_next_bits(cursor, 1)?;
         let mut new_map = BTreeMap::new();
-        let hashmap = HashmapE::with_hashmap(bit_len, cursor.get_dictionary()?.reference_opt(0));
+        let hashmap = HashmapE::with_hashmap(bit_len, read_dictionary_root(&mut cursor)?);
         hashmap.iterate_slices(|key, mut value| {
             let key = Self::read_from(key_type, key.into(), true, abi_version, allow_partial)?.0;
 
             if value_in_ref {
-                value = SliceData::load_cell(value.checked_drain_reference()?)?;
+                value = load_ref(value.checked_drain_reference()?)?;
             }
-            let value =
-                Self::read_from(value_type, value.into(), true, abi_version, allow_partial)?.0;
+            let value = Self::read_value(value_type, value.into(), true, abi_version, allow_partial, depth.nested())
+                .map_err(|err| ParamPath::prepend(err, format!("[{}]", key)))?.0;
             new_map.insert(key.try_into()?, value);
             Ok(true)
         })?;
@@ -420,6 +567,7 @@ impl TokenValue {
                     cursor: original
                 });
             }
+            check_not_pruned(&cell)?;
             data.extend_from_slice(cell.data());
             cell = match cell.reference(0) {
                 Ok(cell) => cell.clone(),
@@ -436,7 +584,7 @@ impl TokenValue {
         last: bool,
         abi_version: &AbiVersion,
     ) -> Result<(Self, SliceData)> {
-        if abi_version >= &ABI_VERSION_2_4 {
+        if Self::is_inline_fixed_bytes(size, abi_version) {
             let (data, cursor) = get_next_bits_from_chain(cursor, size * 8)?;
             Ok((TokenValue::FixedBytes(data), cursor))
         } else {
@@ -503,25 +651,28 @@ impl TokenValue {
         last: bool,
         abi_version: &AbiVersion,
         allow_partial: bool,
+        depth: DecodeDepth,
     ) -> Result<(Self, SliceData)> {
         let mut cursor = find_next_bits(cursor, 1)?;
         if cursor.get_next_bit()? {
             if Self::is_large_optional(inner_type, abi_version) {
                 let cell = cursor.checked_drain_reference()?;
-                let (result, _) = Self::read_from(
+                let (result, _) = Self::read_value(
                     inner_type,
-                    SliceData::load_cell(cell)?.into(),
+                    load_ref(cell)?.into(),
                     true,
                     abi_version,
                     allow_partial,
+                    depth.nested(),
                 )?;
                 Ok((
                     TokenValue::Optional(inner_type.clone(), Some(Box::new(result))),
                     cursor,
                 ))
             } else {
-                let (result, cursor) =
-                    Self::read_from(inner_type, cursor.into(), last, abi_version, allow_partial)?;
+                let (result, cursor) = Self::read_value(
+                    inner_type, cursor.into(), last, abi_version, allow_partial, depth.nested()
+                )?;
                 Ok((
                     TokenValue::Optional(inner_type.clone(), Some(Box::new(result))),
                     cursor.slice,
@@ -538,18 +689,84 @@ impl TokenValue {
         last: bool,
         abi_version: &AbiVersion,
         allow_partial: bool,
+        depth: DecodeDepth,
     ) -> Result<(Self, SliceData)> {
         let (cell, cursor) = Self::read_cell(cursor, last, abi_version)?;
-        let (result, _) = Self::read_from(
+        let (result, _) = Self::read_value(
             inner_type,
-            SliceData::load_cell(cell)?.into(),
+            load_ref(cell)?.into(),
             true,
             abi_version,
             allow_partial,
+            depth.nested(),
         )?;
         Ok((TokenValue::Ref(Box::new(result)), cursor))
     }
 
+    /// Decodes single element of an array (`T[]` or `T[k]`) by its index without decoding
+    /// the whole array. `cursor` should point to the encoded array value.
+    pub fn decode_array_element(
+        param_type: &ParamType,
+        cursor: SliceData,
+        index: u32,
+        abi_version: &AbiVersion,
+    ) -> Result<Option<Self>> {
+        let (item_type, mut cursor) = match param_type {
+            ParamType::Array(item_type) => {
+                let mut cursor = find_next_bits(cursor, 32)?;
+                let size = cursor.get_next_u32()?;
+                if index >= size {
+                    return Ok(None);
+                }
+                (item_type, cursor)
+            }
+            ParamType::FixedArray(item_type, size) => {
+                if index as usize >= *size {
+                    return Ok(None);
+                }
+                (item_type, cursor)
+            }
+            _ => fail!(AbiError::WrongParameterType),
+        };
+        cursor = find_next_bits(cursor, 1)?;
+        let map = HashmapE::with_hashmap(32, read_dictionary_root(&mut cursor)?);
+
+        let mut key = BuilderData::new();
+        key.append_u32(index)?;
+        map.get(SliceData::load_builder(key)?)?
+            .map(|item_slice| Self::read_array_item(item_type, item_slice, abi_version, false, 2))
+            .transpose()
+    }
+
+    /// Decodes single value of a map by its key without decoding the whole map.
+    /// `cursor` should point to the encoded map value.
+    pub fn decode_map_value(
+        key_type: &ParamType,
+        value_type: &ParamType,
+        cursor: SliceData,
+        key: &MapKeyTokenValue,
+        abi_version: &AbiVersion,
+    ) -> Result<Option<Self>> {
+        if !key.type_check(key_type) {
+            fail!(AbiError::WrongParameterType);
+        }
+        let bit_len = TokenValue::get_map_key_size(key_type)?;
+        let value_len = value_type.max_bit_size(abi_version);
+        let value_in_ref = Self::map_value_in_ref(bit_len, value_len);
+
+        let mut cursor = find_next_bits(cursor, 1)?;
+        let hashmap = HashmapE::with_hashmap(bit_len, read_dictionary_root(&mut cursor)?);
+
+        let Some(mut value) = hashmap.get(SliceData::load_builder(key.write_to_cell()?)?)? else {
+            return Ok(None);
+        };
+        if value_in_ref {
+            value = load_ref(value.checked_drain_reference()?)?;
+        }
+        Self::read_value(value_type, value.into(), true, abi_version, false, DecodeDepth::new(MAX_DECODE_DEPTH).nested())
+            .map(|(value, _)| Some(value))
+    }
+
     /// Decodes provided params from SliceData
     pub fn decode_params(
         params: &[Param],
@@ -561,12 +778,243 @@ impl TokenValue {
             .map(|(tokens, _)| tokens)
     }
 
+    /// Decodes provided params from SliceData handling remaining data according to `policy`.
+    /// Remaining data is returned only for `TrailingDataPolicy::ReturnRemainder`.
+    pub fn decode_params_with_policy(
+        params: &[Param],
+        cursor: SliceData,
+        abi_version: &AbiVersion,
+        policy: TrailingDataPolicy,
+    ) -> Result<(Vec<Token>, Option<SliceData>)> {
+        let allow_partial = policy != TrailingDataPolicy::Error;
+        let (tokens, cursor) =
+            Self::decode_params_with_cursor(params, cursor.into(), abi_version, allow_partial, true)?;
+        if params.is_empty() {
+            Self::check_full_decode(allow_partial, &cursor.slice)?;
+        }
+        let remainder = match policy {
+            TrailingDataPolicy::ReturnRemainder => Some(cursor.slice),
+            _ => None,
+        };
+        Ok((tokens, remainder))
+    }
+
+    /// Decodes provided params from SliceData which can contain pruned branch cells. Params
+    /// which can not be decoded because of pruned data are marked as unavailable.
+    pub fn decode_params_allow_pruned(
+        params: &[Param],
+        cursor: SliceData,
+        abi_version: &AbiVersion,
+        allow_partial: bool,
+    ) -> Result<Vec<DecodedToken>> {
+        Self::check_params_depth(params)?;
+        let mut cursor: Cursor = cursor.into();
+        let mut tokens = Vec::with_capacity(params.len());
+
+        for (i, param) in params.iter().enumerate() {
+            let last = i + 1 == params.len();
+
+            match Self::read_from(&param.kind, cursor.clone(), last, abi_version, allow_partial) {
+                Ok((value, new_cursor)) => {
+                    cursor = new_cursor;
+                    tokens.push(DecodedToken::Available(Token {
+                        name: param.name.clone(),
+                        value,
+                    }));
+                }
+                Err(err) => {
+                    if !is_pruned_access(&err) {
+                        return Err(ParamPath::prepend(err, &param.name));
+                    }
+                    tokens.push(DecodedToken::Unavailable(param.name.clone()));
+                    match Self::skip_value(&param.kind, cursor.slice.clone(), last, abi_version) {
+                        Ok(slice) => cursor.slice = slice,
+                        Err(err) if !is_pruned_access(&err) => {
+                            return Err(ParamPath::prepend(err, &param.name));
+                        }
+                        Err(_) => {
+                            tokens.extend(params[i + 1..]
+                                .iter()
+                                .map(|param| DecodedToken::Unavailable(param.name.clone())));
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(tokens)
+    }
+
+    /// Decodes provided params from SliceData. When some param can not be decoded its error
+    /// is recorded and decoding continues with the following params if position of the next
+    /// param can be determined. Returns successfully decoded tokens and recorded errors.
+    pub fn decode_params_recovering(
+        params: &[Param],
+        cursor: SliceData,
+        abi_version: &AbiVersion,
+        allow_partial: bool,
+    ) -> (Vec<Token>, Vec<ParamDecodeError>) {
+        let mut errors = vec![];
+        if let Err(error) = Self::check_params_depth(params) {
+            errors.push(ParamDecodeError { path: String::new(), error });
+            return (vec![], errors);
+        }
+        let (tokens, _) = Self::read_params_recovering(
+            params,
+            cursor.into(),
+            true,
+            abi_version,
+            allow_partial,
+            "",
+            &mut errors,
+        );
+        (tokens, errors)
+    }
+
+    fn read_params_recovering(
+        params: &[Param],
+        mut cursor: Cursor,
+        last: bool,
+        abi_version: &AbiVersion,
+        allow_partial: bool,
+        path: &str,
+        errors: &mut Vec<ParamDecodeError>,
+    ) -> (Vec<Token>, Option<Cursor>) {
+        let mut tokens = vec![];
+        for (i, param) in params.iter().enumerate() {
+            let last = i + 1 == params.len() && last;
+            let param_path = if path.is_empty() {
+                param.name.clone()
+            } else {
+                format!("{}.{}", path, param.name)
+            };
+
+            if let ParamType::Tuple(tuple_params) = &param.kind {
+                let errors_count = errors.len();
+                let (tuple_tokens, new_cursor) = Self::read_params_recovering(
+                    tuple_params,
+                    cursor,
+                    last,
+                    abi_version,
+                    allow_partial,
+                    &param_path,
+                    errors,
+                );
+                if errors.len() == errors_count {
+                    tokens.push(Token {
+                        name: param.name.clone(),
+                        value: TokenValue::Tuple(tuple_tokens),
+                    });
+                }
+                match new_cursor {
+                    Some(new_cursor) => cursor = new_cursor,
+                    None => return (tokens, None),
+                }
+                continue;
+            }
+
+            match Self::read_from(&param.kind, cursor.clone(), last, abi_version, allow_partial) {
+                Ok((value, new_cursor)) => {
+                    cursor = new_cursor;
+                    tokens.push(Token {
+                        name: param.name.clone(),
+                        value,
+                    });
+                }
+                Err(error) => {
+                    errors.push(ParamDecodeError { path: param_path, error });
+                    match Self::skip_value(&param.kind, cursor.slice.clone(), last, abi_version) {
+                        Ok(slice) => cursor.slice = slice,
+                        Err(_) => return (tokens, None),
+                    }
+                }
+            }
+        }
+        (tokens, Some(cursor))
+    }
+
+    /// Decodes provided params from Cell
+    pub fn decode_params_from_cell(
+        params: &[Param],
+        cell: &Cell,
+        abi_version: &AbiVersion,
+        allow_partial: bool,
+    ) -> Result<Vec<Token>> {
+        Self::decode_params(params, SliceData::load_cell(cell.clone())?, abi_version, allow_partial)
+    }
+
+    /// Decodes provided params reporting data remaining after them into `warnings`. Remaining
+    /// data is an error unless `allow_partial` is set
+    pub fn decode_params_with_warnings(
+        params: &[Param],
+        data: SliceData,
+        abi_version: &AbiVersion,
+        allow_partial: bool,
+        warnings: &mut Warnings,
+    ) -> Result<Vec<Token>> {
+        let (tokens, cursor) =
+            Self::decode_params_with_cursor(params, data.into(), abi_version, allow_partial, true)?;
+        warnings.check_trailing_data(&cursor.slice);
+        Ok(tokens)
+    }
+
     pub fn decode_params_with_cursor(
+        params: &[Param],
+        cursor: Cursor,
+        abi_version: &AbiVersion,
+        allow_partial: bool,
+        last: bool,
+    ) -> Result<(Vec<Token>, Cursor)> {
+        Self::check_params_depth(params)?;
+        Self::read_params(params, cursor, abi_version, allow_partial, last, DecodeDepth::new(MAX_DECODE_DEPTH))
+    }
+
+    /// Decodes provided params from SliceData allowing param types nested up to `max_depth`
+    /// levels instead of `MAX_DECODE_DEPTH`. Decoding is recursive, so large limits require
+    /// enough stack space.
+    pub fn decode_params_with_max_depth(
+        params: &[Param],
+        cursor: SliceData,
+        abi_version: &AbiVersion,
+        allow_partial: bool,
+        max_depth: usize,
+    ) -> Result<Vec<Token>> {
+        Self::check_params_max_depth(params, max_depth)?;
+        let depth = DecodeDepth::new(max_depth);
+        let (tokens, cursor) = Self::read_params(params, cursor.into(), abi_version, allow_partial, true, depth)?;
+        Self::check_full_decode(allow_partial, &cursor.slice)?;
+        Ok(tokens)
+    }
+
+    /// Checks that params nesting depth doesn't exceed `MAX_DECODE_DEPTH`, so decoding
+    /// recursion is bounded
+    pub fn check_params_depth(params: &[Param]) -> Result<()> {
+        Self::check_params_max_depth(params, MAX_DECODE_DEPTH)
+    }
+
+    /// Checks that params nesting depth doesn't exceed `max_depth`
+    pub fn check_params_max_depth(params: &[Param], max_depth: usize) -> Result<()> {
+        for param in params {
+            let depth = param.kind.nesting_depth();
+            if depth > max_depth {
+                fail!(AbiError::MaxDepthExceeded {
+                    name: param.name.clone(),
+                    depth,
+                    max: max_depth,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn read_params(
         params: &[Param],
         mut cursor: Cursor,
         abi_version: &AbiVersion,
         allow_partial: bool,
         last: bool,
+        depth: DecodeDepth,
     ) -> Result<(Vec<Token>, Cursor)> {
         let mut tokens = vec![];
 
@@ -574,8 +1022,8 @@ impl TokenValue {
             let last = Some(param) == params.last() && last;
 
             let (token_value, new_cursor) =
-                Self::read_from(&param.kind, cursor, last, abi_version, allow_partial)?;
-
+                Self::read_value(&param.kind, cursor, last, abi_version, allow_partial, depth)
+                    .map_err(|err| ParamPath::prepend(err, &param.name))?;
 
             cursor = new_cursor;
             tokens.push(Token {
@@ -586,6 +1034,179 @@ impl TokenValue {
 
         Ok((tokens, cursor))
     }
+
+    /// Decodes provided params from SliceData. If data ends before all params are read,
+    /// remaining params are filled with default values. Returns decoded tokens and names
+    /// of the params which were defaulted.
+    pub fn decode_params_with_defaults(
+        params: &[Param],
+        cursor: SliceData,
+        abi_version: &AbiVersion,
+        allow_partial: bool,
+    ) -> Result<(Vec<Token>, Vec<String>)> {
+        Self::check_params_depth(params)?;
+        let mut cursor: Cursor = cursor.into();
+        let mut tokens = vec![];
+        let mut defaulted = vec![];
+
+        for param in params {
+            if cursor.slice.remaining_bits() == 0 && cursor.slice.remaining_references() == 0 {
+                defaulted.push(param.name.clone());
+                tokens.push(Token {
+                    name: param.name.clone(),
+                    value: Self::default_value(&param.kind),
+                });
+                continue;
+            }
+
+            let last = Some(param) == params.last();
+
+            let (token_value, new_cursor) =
+                Self::read_from(&param.kind, cursor, last, abi_version, allow_partial)
+                    .map_err(|err| ParamPath::prepend(err, &param.name))?;
+
+            cursor = new_cursor;
+            tokens.push(Token {
+                name: param.name.clone(),
+                value: token_value,
+            });
+        }
+        Self::check_full_decode(allow_partial, &cursor.slice)?;
+
+        Ok((tokens, defaulted))
+    }
+
+    /// Advances cursor over encoded values of provided params without constructing tokens.
+    /// Skipped values are supposed to be followed by other values.
+    pub fn skip(params: &[Param], mut cursor: Cursor, abi_version: &AbiVersion) -> Result<Cursor> {
+        Self::check_params_depth(params)?;
+        for param in params {
+            cursor.slice = Self::skip_value(&param.kind, cursor.slice, false, abi_version)?;
+        }
+        Ok(cursor)
+    }
+
+    fn skip_value(
+        param_type: &ParamType,
+        slice: SliceData,
+        last: bool,
+        abi_version: &AbiVersion,
+    ) -> Result<SliceData> {
+        match param_type {
+            ParamType::Uint(size)
+            | ParamType::Int(size)
+            | ParamType::FixedPoint { bits: size, .. } => skip_bits_in_chain(slice, *size),
+            ParamType::VarUint(size) | ParamType::VarInt(size) => {
+                let (len, slice) =
+                    Self::read_uint_from_chain(TokenValue::varint_size_len(*size), slice)?;
+                let len = len.to_usize().unwrap();
+                if len == 0 {
+                    Ok(slice)
+                } else {
+                    skip_bits_in_chain(slice, len * 8)
+                }
+            }
+            ParamType::Enum(_) => skip_bits_in_chain(slice, 8),
+            ParamType::Bits(size) => skip_bits_in_chain(slice, *size),
+            ParamType::Slice => Self::read_slice(slice).map(|(_, slice)| slice),
+            ParamType::Bool => skip_bits_in_chain(slice, 1),
+            ParamType::Time => skip_bits_in_chain(slice, 64),
+            ParamType::Expire => skip_bits_in_chain(slice, 32),
+            ParamType::Tuple(params) => {
+                let mut slice = slice;
+                for param in params {
+                    let last = Some(param) == params.last() && last;
+                    slice = Self::skip_value(&param.kind, slice, last, abi_version)?;
+                }
+                Ok(slice)
+            }
+            ParamType::Array(_) => {
+                let mut slice = skip_bits_in_chain(slice, 32)?;
+                slice = find_next_bits(slice, 1)?;
+                slice.get_dictionary()?;
+                Ok(slice)
+            }
+            ParamType::FixedArray(_, _) | ParamType::Map(_, _) => {
+                let mut slice = find_next_bits(slice, 1)?;
+                slice.get_dictionary()?;
+                Ok(slice)
+            }
+            ParamType::Cell | ParamType::Bytes | ParamType::String | ParamType::Ref(_) => {
+                Self::read_cell(slice, last, abi_version).map(|(_, slice)| slice)
+            }
+            ParamType::FixedBytes(size) => {
+                if Self::is_inline_fixed_bytes(*size, abi_version) {
+                    skip_bits_in_chain(slice, size * 8)
+                } else {
+                    Self::read_cell(slice, last, abi_version).map(|(_, slice)| slice)
+                }
+            }
+            ParamType::Address
+            | ParamType::AddressStd
+            | ParamType::AddressVar
+            | ParamType::AddressExt => {
+                let mut slice = find_next_bits(slice, 1)?;
+                <MsgAddress as ton_block::Deserializable>::construct_from(&mut slice)?;
+                Ok(slice)
+            }
+            ParamType::Token => {
+                let mut slice = find_next_bits(slice, 1)?;
+                <Grams as ton_block::Deserializable>::construct_from(&mut slice)?;
+                Ok(slice)
+            }
+            ParamType::PublicKey => {
+                let mut slice = find_next_bits(slice, 1)?;
+                if slice.get_next_bit()? {
+                    skip_bits_in_chain(slice, 256)
+                } else {
+                    Ok(slice)
+                }
+            }
+            ParamType::Optional(inner_type) => {
+                let mut slice = find_next_bits(slice, 1)?;
+                if !slice.get_next_bit()? {
+                    Ok(slice)
+                } else if Self::is_large_optional(inner_type, abi_version) {
+                    slice.checked_drain_reference()?;
+                    Ok(slice)
+                } else {
+                    Self::skip_value(inner_type, slice, last, abi_version)
+                }
+            }
+        }
+    }
+}
+
+fn is_pruned_access(err: &ton_types::Error) -> bool {
+    matches!(err.downcast_ref::<AbiError>(), Some(AbiError::PrunedCellAccess))
+}
+
+/// Fails with `PrunedCellAccess` if cell is pruned branch: such cells keep only hash of the
+/// original data which can not be read
+fn check_not_pruned(cell: &Cell) -> Result<()> {
+    if cell.cell_type() == CellType::PrunedBranch {
+        fail!(AbiError::PrunedCellAccess)
+    }
+    Ok(())
+}
+
+fn load_ref(cell: Cell) -> Result<SliceData> {
+    check_not_pruned(&cell)?;
+    SliceData::load_cell(cell)
+}
+
+fn read_dictionary_root(cursor: &mut SliceData) -> Result<Option<Cell>> {
+    let root = cursor.get_dictionary()?.reference_opt(0);
+    if let Some(root) = &root {
+        check_not_pruned(root)?;
+    }
+    Ok(root)
+}
+
+fn skip_bits_in_chain(mut cursor: SliceData, bits: usize) -> Result<SliceData> {
+    cursor = find_next_bits(cursor, bits)?;
+    cursor.move_by(bits)?;
+    Ok(cursor)
 }
 
 fn get_next_bits_from_chain(mut cursor: SliceData, bits: usize) -> Result<(Vec<u8>, SliceData)> {
@@ -600,7 +1221,7 @@ fn find_next_bits(mut cursor: SliceData, bits: usize) -> Result<SliceData> {
         if cursor.reference(1).is_ok() {
             fail!(AbiError::IncompleteDeserializationError)
         }
-        cursor = SliceData::load_cell(cursor.reference(0)?)?;
+        cursor = load_ref(cursor.reference(0)?)?;
     }
     match cursor.remaining_bits() >= bits {
         true => Ok(cursor),
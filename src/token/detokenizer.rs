@@ -177,6 +177,13 @@ impl Serialize for TokenValue {
                 Token::detokenize_big_uint(uint, (size - 1) * 8, serializer)
             }
             TokenValue::VarInt(_, int) => Token::detokenize_big_int(int, serializer),
+            TokenValue::FixedPoint(int, decimals) => {
+                serializer.serialize_str(&TokenValue::format_fixed_point(&int.number, *decimals))
+            }
+            TokenValue::Enum(..) => serializer.serialize_str(&self.to_string()),
+            TokenValue::Bits(slice) | TokenValue::Slice(slice) => {
+                serializer.serialize_str(&TokenValue::format_bits(slice))
+            }
             TokenValue::Bool(b) => serializer.serialize_bool(*b),
             TokenValue::Tuple(tokens) => {
                 FunctionParams {params: tokens}.serialize(serializer)
@@ -187,7 +194,9 @@ impl Serialize for TokenValue {
             TokenValue::Map(key_type, _, ref map) =>
                 Token::detokenize_hashmap(key_type, map, serializer),
             TokenValue::Address(ref address) => serializer.serialize_str(&address.to_string()),
-            TokenValue::AddressStd(ref address) => serializer.serialize_str(&address.to_string()),
+            TokenValue::AddressStd(ref address)
+            | TokenValue::AddressVar(ref address)
+            | TokenValue::AddressExt(ref address) => serializer.serialize_str(&address.to_string()),
             TokenValue::Bytes(ref arr) => Token::detokenize_bytes(arr, serializer),
             TokenValue::FixedBytes(ref arr) => Token::detokenize_bytes(arr, serializer),
             TokenValue::String(string) => serializer.serialize_str(string),
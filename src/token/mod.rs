@@ -13,13 +13,14 @@
 
 //! TON ABI params.
 use crate::{
-    error::AbiError, int::{Int, Uint}, param::Param, param_type::ParamType,
+    error::AbiError, int::{Int, Uint}, param::Param, param_type::{ParamType, MAX_SLICE_BITS},
 };
 
 use std::collections::BTreeMap;
 use std::fmt;
+use std::sync::Arc;
 use ton_block::{Grams, MsgAddress};
-use ton_types::{Result, Cell, BuilderData};
+use ton_types::{Result, Cell, BuilderData, SliceData};
 use num_bigint::{BigInt, BigUint};
 use ton_types::error;
 use crate::contract::{AbiVersion, ABI_VERSION_2_4};
@@ -61,6 +62,42 @@ impl fmt::Display for Token {
     }
 }
 
+/// Path to param which caused encoding or decoding error, e.g. `recipients[17].addr`.
+/// Path is attached to error as context, so original error can still be downcasted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamPath {
+    /// Tuple components are separated by dots, array indexes and map keys are in brackets
+    pub path: String,
+    message: String,
+}
+
+impl ParamPath {
+    /// Returns path to param which caused error if it is known
+    pub fn of(err: &anyhow::Error) -> Option<&str> {
+        err.downcast_ref::<ParamPath>().map(|path| path.path.as_str())
+    }
+
+    /// Prepends `step` (param name or `[index]`) to path to param which caused error
+    pub(crate) fn prepend(mut err: anyhow::Error, step: impl fmt::Display) -> anyhow::Error {
+        if let Some(path) = err.downcast_mut::<ParamPath>() {
+            path.path = if path.path.starts_with('[') {
+                format!("{}{}", step, path.path)
+            } else {
+                format!("{}.{}", step, path.path)
+            };
+            return err;
+        }
+        let message = err.to_string();
+        err.context(ParamPath { path: step.to_string(), message })
+    }
+}
+
+impl fmt::Display for ParamPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (param `{}`)", self.message, self.path)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum MapKeyTokenValue {
     Uint(Uint),
@@ -178,6 +215,14 @@ pub enum TokenValue {
     ///
     /// Encoded according to blockchain specification
     VarUint(usize, BigUint),
+    /// fixed<M>x<N>: fixed-point decimal number stored as integer scaled by 10^N.
+    ///
+    /// Encoded as underlying `int<M>`.
+    FixedPoint(Int, usize),
+    /// enum: variant index of enum with provided variants names.
+    ///
+    /// Encoded as `uint8`.
+    Enum(Arc<[String]>, u8),
     /// bool: boolean value.
     ///
     /// Encoded as one bit put into cell data.
@@ -205,10 +250,22 @@ pub enum TokenValue {
     Address(MsgAddress),
     /// AddrStd or AddrNone
     AddressStd(MsgAddress),
+    /// AddrVar or AddrNone
+    AddressVar(MsgAddress),
+    /// AddrExt or AddrNone
+    AddressExt(MsgAddress),
     /// Raw byte array
     ///
     /// Encoded as separate cells chain
     Bytes(Vec<u8>),
+    /// Raw bits placed inline
+    ///
+    /// Encoded as is into cell data
+    Bits(SliceData),
+    /// Variable length raw bits placed inline
+    ///
+    /// Encoded as length prefix followed by bits into cell data
+    Slice(SliceData),
     /// Fixed sized raw byte array
     ///
     /// Encoded as separate cells chain
@@ -239,6 +296,12 @@ impl fmt::Display for TokenValue {
             TokenValue::Int(u) => write!(f, "{}", u.number),
             TokenValue::VarUint(_, u) => write!(f, "{u}"),
             TokenValue::VarInt(_, u) => write!(f, "{u}"),
+            TokenValue::FixedPoint(int, decimals) => write!(f, "{}", Self::format_fixed_point(&int.number, *decimals)),
+            TokenValue::Bits(slice) | TokenValue::Slice(slice) => write!(f, "{}", Self::format_bits(slice)),
+            TokenValue::Enum(variants, index) => match variants.get(*index as usize) {
+                Some(variant) => write!(f, "{variant}"),
+                None => write!(f, "{index}"),
+            },
             TokenValue::Bool(b) => write!(f, "{b}"),
             TokenValue::Tuple(tokens) => {
                 let mut first = true;
@@ -274,7 +337,10 @@ impl fmt::Display for TokenValue {
 
                 write!(f, "{{{}}}", s)
             }
-            TokenValue::Address(a) | TokenValue::AddressStd(a) => write!(f, "{}", a),
+            TokenValue::Address(a)
+            | TokenValue::AddressStd(a)
+            | TokenValue::AddressVar(a)
+            | TokenValue::AddressExt(a) => write!(f, "{}", a),
             TokenValue::Bytes(bytes) | TokenValue::FixedBytes(bytes) => write!(f, "{bytes:?}"),
             TokenValue::String(string) => write!(f, "{string}"),
             TokenValue::Token(g) => write!(f, "{g}"),
@@ -306,6 +372,15 @@ impl TokenValue {
             TokenValue::Int(int) => *param_type == ParamType::Int(int.size),
             TokenValue::VarUint(size, _) => *param_type == ParamType::VarUint(*size),
             TokenValue::VarInt(size, _) => *param_type == ParamType::VarInt(*size),
+            TokenValue::FixedPoint(int, decimals) => {
+                *param_type == ParamType::FixedPoint { bits: int.size, decimals: *decimals }
+            }
+            TokenValue::Enum(variants, index) => {
+                (*index as usize) < variants.len()
+                    && matches!(param_type, ParamType::Enum(names) if names == variants)
+            }
+            TokenValue::Bits(slice) => *param_type == ParamType::Bits(slice.remaining_bits()),
+            TokenValue::Slice(slice) => *param_type == ParamType::Slice && slice.remaining_bits() <= MAX_SLICE_BITS,
             TokenValue::Bool(_) => *param_type == ParamType::Bool,
             TokenValue::Tuple(ref arr) => {
                 if let ParamType::Tuple(params) = param_type {
@@ -343,6 +418,8 @@ impl TokenValue {
             }
             TokenValue::Address(_) => *param_type == ParamType::Address,
             TokenValue::AddressStd(_) => *param_type == ParamType::AddressStd,
+            TokenValue::AddressVar(_) => *param_type == ParamType::AddressVar,
+            TokenValue::AddressExt(_) => *param_type == ParamType::AddressExt,
             TokenValue::Bytes(_) => *param_type == ParamType::Bytes,
             TokenValue::FixedBytes(ref arr) => *param_type == ParamType::FixedBytes(arr.len()),
             TokenValue::String(_) => *param_type == ParamType::String,
@@ -378,6 +455,10 @@ impl TokenValue {
             TokenValue::Int(int) => ParamType::Int(int.size),
             TokenValue::VarUint(size, _) => ParamType::VarUint(*size),
             TokenValue::VarInt(size, _) => ParamType::VarInt(*size),
+            TokenValue::FixedPoint(int, decimals) => ParamType::FixedPoint { bits: int.size, decimals: *decimals },
+            TokenValue::Enum(variants, _) => ParamType::Enum(variants.clone()),
+            TokenValue::Bits(slice) => ParamType::Bits(slice.remaining_bits()),
+            TokenValue::Slice(_) => ParamType::Slice,
             TokenValue::Bool(_) => ParamType::Bool,
             TokenValue::Tuple(ref arr) => {
                 ParamType::Tuple(arr.iter().map(|token| token.get_param()).collect())
@@ -392,6 +473,8 @@ impl TokenValue {
             }
             TokenValue::Address(_) => ParamType::Address,
             TokenValue::AddressStd(_) => ParamType::AddressStd,
+            TokenValue::AddressVar(_) => ParamType::AddressVar,
+            TokenValue::AddressExt(_) => ParamType::AddressExt,
             TokenValue::Bytes(_) => ParamType::Bytes,
             TokenValue::FixedBytes(ref arr) => ParamType::FixedBytes(arr.len()),
             TokenValue::String(_) => ParamType::String,
@@ -430,86 +513,60 @@ impl TokenValue {
         }
     }
 
-    pub(crate) fn varint_size_len(size: usize) -> usize {
-        8 - ((size - 1) as u8).leading_zeros() as usize
+    /// Formats integer scaled by 10^`decimals` as decimal string, e.g. `-1.005`
+    pub fn format_fixed_point(number: &BigInt, decimals: usize) -> String {
+        let digits = number.magnitude().to_string();
+        let digits = if digits.len() <= decimals {
+            format!("{}{}", "0".repeat(decimals + 1 - digits.len()), digits)
+        } else {
+            digits
+        };
+        let (integer, fraction) = digits.split_at(digits.len() - decimals);
+        let sign = if number.sign() == num_bigint::Sign::Minus { "-" } else { "" };
+        if fraction.is_empty() {
+            format!("{}{}", sign, integer)
+        } else {
+            format!("{}{}.{}", sign, integer, fraction)
+        }
     }
 
-    pub(crate) fn is_large_optional(param_type: &ParamType, abi_version: &AbiVersion) -> bool {
-        Self::max_bit_size(param_type, abi_version) >= BuilderData::bits_capacity()
-            || Self::max_refs_count(param_type, abi_version) >= BuilderData::references_capacity()
+    /// Formats raw bits as hex string. If bits count is not divisible by 4, completion tag
+    /// is used: value is padded with one `1` bit and `0` bits and `_` is appended
+    pub fn format_bits(slice: &SliceData) -> String {
+        let mut bits = (0..slice.remaining_bits())
+            .map(|index| slice.get_bits(index, 1).map(|bit| bit == 1).unwrap_or_default())
+            .collect::<Vec<_>>();
+        let tag = bits.len() % 4 != 0;
+        if tag {
+            bits.push(true);
+            bits.resize((bits.len() + 3) / 4 * 4, false);
+        }
+        let mut result = bits
+            .chunks(4)
+            .map(|nibble| {
+                let digit = nibble.iter().fold(0, |acc, bit| (acc << 1) | *bit as u32);
+                std::char::from_digit(digit, 16).unwrap()
+            })
+            .collect::<String>();
+        if tag {
+            result.push('_');
+        }
+        result
     }
 
-    pub(crate) fn max_refs_count(param_type: &ParamType, abi_version: &AbiVersion) -> usize {
-        match param_type {
-            // in-cell serialized types
-            ParamType::Uint(_)
-            | ParamType::Int(_)
-            | ParamType::VarUint(_)
-            | ParamType::VarInt(_)
-            | ParamType::Bool
-            | ParamType::Address
-            | ParamType::AddressStd
-            | ParamType::Token
-            | ParamType::Time
-            | ParamType::Expire
-            | ParamType::PublicKey => 0,
-            ParamType::FixedBytes(_) if abi_version >= &ABI_VERSION_2_4 => 0,
-            // reference serialized types
-            ParamType::Array(_)
-            | ParamType::FixedArray(_, _)
-            | ParamType::Cell
-            | ParamType::String
-            | ParamType::Map(_, _)
-            | ParamType::Bytes
-            | ParamType::FixedBytes(_)
-            | ParamType::Ref(_) => 1,
-            // tuple refs is sum of inner types refs
-            ParamType::Tuple(params) => params.iter().fold(0, |acc, param| {
-                acc + Self::max_refs_count(&param.kind, abi_version)
-            }),
-            // large optional is serialized into reference
-            ParamType::Optional(param_type) => {
-                if Self::is_large_optional(param_type, abi_version) {
-                    1
-                } else {
-                    Self::max_refs_count(param_type, abi_version)
-                }
-            }
-        }
+    /// Checks if `fixedbytes<size>` value is stored inline. Since ABI 2.4 values fitting into
+    /// one cell are stored inline, longer values use `bytes` layout: cell chain in reference
+    pub fn is_inline_fixed_bytes(size: usize, abi_version: &AbiVersion) -> bool {
+        abi_version >= &ABI_VERSION_2_4 && size * 8 <= BuilderData::bits_capacity()
     }
 
-    pub(crate) fn max_bit_size(param_type: &ParamType, abi_version: &AbiVersion) -> usize {
-        match param_type {
-            ParamType::Uint(size) => *size,
-            ParamType::Int(size) => *size,
-            ParamType::VarUint(size) => Self::varint_size_len(*size) + (size - 1) * 8,
-            ParamType::VarInt(size) => Self::varint_size_len(*size) + (size - 1) * 8,
-            ParamType::Bool => 1,
-            ParamType::Array(_) => 33,
-            ParamType::FixedArray(_, _) => 1,
-            ParamType::Cell => 0,
-            ParamType::Map(_, _) => 1,
-            ParamType::Address => 591,
-            ParamType::AddressStd => 2 + (1 + 5 + 30) + 8 + 256,
-            ParamType::FixedBytes(size) if  abi_version >= &ABI_VERSION_2_4 => size * 8,
-            ParamType::Bytes | ParamType::FixedBytes(_) => 0,
-            ParamType::String => 0,
-            ParamType::Token => 124,
-            ParamType::Time => 64,
-            ParamType::Expire => 32,
-            ParamType::PublicKey => 257,
-            ParamType::Ref(_) => 0,
-            ParamType::Tuple(params) => params.iter().fold(0, |acc, param| {
-                acc + Self::max_bit_size(&param.kind, abi_version)
-            }),
-            ParamType::Optional(param_type) => {
-                if Self::is_large_optional(param_type, abi_version) {
-                    1
-                } else {
-                    1 + Self::max_bit_size(param_type, abi_version)
-                }
-            }
-        }
+    pub(crate) fn varint_size_len(size: usize) -> usize {
+        8 - ((size - 1) as u8).leading_zeros() as usize
+    }
+
+    pub(crate) fn is_large_optional(param_type: &ParamType, abi_version: &AbiVersion) -> bool {
+        param_type.max_bit_size(abi_version) >= BuilderData::bits_capacity()
+            || param_type.max_refs_count(abi_version) >= BuilderData::references_capacity()
     }
 
     pub(crate) fn default_value(param_type: &ParamType) -> TokenValue {
@@ -518,6 +575,10 @@ impl TokenValue {
             ParamType::Int(size) => TokenValue::Int(Int::new(0, *size)),
             ParamType::VarUint(size) => TokenValue::VarUint(*size, 0u32.into()),
             ParamType::VarInt(size) => TokenValue::VarInt(*size, 0.into()),
+            ParamType::FixedPoint { bits, decimals } => TokenValue::FixedPoint(Int::new(0, *bits), *decimals),
+            ParamType::Enum(variants) => TokenValue::Enum(variants.clone(), 0),
+            ParamType::Bits(size) => TokenValue::Bits(SliceData::from_raw(vec![0; (size + 7) / 8], *size)),
+            ParamType::Slice => TokenValue::Slice(SliceData::from_raw(vec![], 0)),
             ParamType::Bool => TokenValue::Bool(false),
             ParamType::Array(inner) => TokenValue::Array(inner.as_ref().clone(), vec![]),
             ParamType::FixedArray(inner, size) => TokenValue::FixedArray(
@@ -534,6 +595,8 @@ impl TokenValue {
             ),
             ParamType::Address => TokenValue::Address(MsgAddress::AddrNone),
             ParamType::AddressStd => TokenValue::AddressStd(MsgAddress::AddrNone),
+            ParamType::AddressVar => TokenValue::AddressVar(MsgAddress::AddrNone),
+            ParamType::AddressExt => TokenValue::AddressExt(MsgAddress::AddrNone),
             ParamType::Bytes => TokenValue::Bytes(vec![]),
             ParamType::FixedBytes(size) => TokenValue::FixedBytes(vec![0; *size]),
             ParamType::String => TokenValue::String(Default::default()),
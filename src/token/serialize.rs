@@ -11,15 +11,15 @@
 * limitations under the License.
 */
 
-use crate::{contract::{ABI_VERSION_1_0, ABI_VERSION_2_2, AbiVersion}, error::AbiError, int::{Int, Uint}, param_type::ParamType, token::{Token, MapKeyTokenValue, TokenValue}};
+use crate::{contract::{ABI_VERSION_1_0, ABI_VERSION_2_2, AbiVersion}, error::AbiError, int::{Int, Uint}, param_type::{ParamType, MAX_SLICE_BITS, SLICE_LEN_BITS}, token::{Token, MapKeyTokenValue, ParamPath, TokenValue}};
 
 use num_bigint::{BigInt, BigUint, Sign};
 use std::collections::BTreeMap;
 use ton_block::Serializable;
 use ton_types::{fail, BuilderData, Cell, HashmapE, IBitstring, Result, SliceData};
 use smallvec::smallvec;
-use crate::contract::ABI_VERSION_2_4;
 
+#[derive(Clone)]
 pub struct SerializedValue {
     pub data: BuilderData,
     pub max_bits: usize,
@@ -49,7 +49,8 @@ impl MapKeyTokenValue {
 impl TokenValue {
     pub fn pack_values_into_chain(tokens: &[Token], mut cells: Vec<SerializedValue>, abi_version: &AbiVersion) -> Result<BuilderData> {
         for token in tokens {
-            cells.append(&mut token.value.write_to_cells(abi_version)?);
+            cells.append(&mut token.value.write_to_cells(abi_version)
+                .map_err(|err| ParamPath::prepend(err, &token.name))?);
         }
 
         Self::pack_cells_into_chain(cells, abi_version)
@@ -153,11 +154,16 @@ impl TokenValue {
             TokenValue::Int(int) => Self::write_int(int),
             TokenValue::VarUint(size, uint) => Self::write_varuint(uint, *size),
             TokenValue::VarInt(size, int) => Self::write_varint(int, *size),
+            TokenValue::FixedPoint(int, _) => Self::write_int(int),
+            TokenValue::Enum(_, index) => Self::write_uint(&Uint::new(*index as u128, 8)),
+            TokenValue::Bits(slice) => Self::write_bits(slice),
+            TokenValue::Slice(slice) => Self::write_slice(slice),
             TokenValue::Bool(b) => Self::write_bool(*b),
             TokenValue::Tuple(ref tokens) => {
                 let mut vec = vec![];
                 for token in tokens.iter() {
-                    vec.append(&mut token.value.write_to_cells(abi_version)?);
+                    vec.append(&mut token.value.write_to_cells(abi_version)
+                        .map_err(|err| ParamPath::prepend(err, &token.name))?);
                 }
                 return Ok(vec);
             }
@@ -172,7 +178,9 @@ impl TokenValue {
                 Self::write_map(key_type, value_type, value, abi_version)
             }
             TokenValue::Address(address) => Ok(address.write_to_new_cell()?),
-            TokenValue::AddressStd(address) => Ok(address.write_to_new_cell()?),
+            TokenValue::AddressStd(address)
+            | TokenValue::AddressVar(address)
+            | TokenValue::AddressExt(address) => Ok(address.write_to_new_cell()?),
             TokenValue::Bytes(ref arr) => Self::write_bytes(arr, abi_version),
             TokenValue::FixedBytes(ref arr) => Self::write_fixed_bytes(arr, abi_version),
             TokenValue::String(ref string) => Self::write_bytes(string.as_bytes(), abi_version),
@@ -191,8 +199,8 @@ impl TokenValue {
         let param_type = self.get_param_type();
         Ok(vec![SerializedValue {
             data,
-            max_bits: Self::max_bit_size(&param_type, abi_version),
-            max_refs: Self::max_refs_count(&param_type, abi_version),
+            max_bits: param_type.max_bit_size(abi_version),
+            max_refs: param_type.max_refs_count(abi_version),
         }])
     }
 
@@ -284,6 +292,25 @@ impl TokenValue {
         Ok(builder)
     }
 
+    fn write_bits(slice: &SliceData) -> Result<BuilderData> {
+        let mut builder = BuilderData::new();
+        builder.append_raw(&slice.get_bytestring(0), slice.remaining_bits())?;
+        Ok(builder)
+    }
+
+    fn write_slice(slice: &SliceData) -> Result<BuilderData> {
+        let len = slice.remaining_bits();
+        if len > MAX_SLICE_BITS {
+            fail!(AbiError::InvalidData {
+                msg: format!("Too long value for slice: {} bits", len)
+            });
+        }
+        let mut builder = BuilderData::new();
+        builder.append_bits(len, SLICE_LEN_BITS)?;
+        builder.append_raw(&slice.get_bytestring(0), len)?;
+        Ok(builder)
+    }
+
     fn write_cell(cell: &Cell) -> Result<BuilderData> {
         let mut builder = BuilderData::new();
         builder.checked_append_reference(cell.clone())?;
@@ -295,13 +322,14 @@ impl TokenValue {
     fn put_array_into_dictionary(param_type: &ParamType, array: &[TokenValue], abi_version: &AbiVersion) -> Result<HashmapE> {
         let mut map = HashmapE::with_bit_len(32);
 
-        let value_in_ref = Self::map_value_in_ref(32, Self::max_bit_size(param_type, abi_version));
+        let value_in_ref = Self::map_value_in_ref(32, param_type.max_bit_size(abi_version));
 
         for (i, item) in array.iter().enumerate() {
             let index = (i as u32).serialize().and_then(ton_types::SliceData::load_cell)?;
 
-            let data =
-                Self::pack_cells_into_chain(item.write_to_cells(abi_version)?, abi_version)?;
+            let cells = item.write_to_cells(abi_version)
+                .map_err(|err| ParamPath::prepend(err, format!("[{}]", i)))?;
+            let data = Self::pack_cells_into_chain(cells, abi_version)?;
 
             if value_in_ref {
                 map.setref(index, &data.into_cell()?)?;
@@ -335,12 +363,7 @@ impl TokenValue {
     }
 
     fn write_fixed_bytes(data: &[u8], abi_version: &AbiVersion) -> Result<BuilderData> {
-        if abi_version >= &ABI_VERSION_2_4 {
-            if data.len() * 8 > BuilderData::bits_capacity() {
-                fail!(AbiError::InvalidData {
-                    msg: "FixedBytes value size is limited to 127 bytes".to_owned()
-                })
-            }
+        if Self::is_inline_fixed_bytes(data.len(), abi_version) {
             let mut builder = BuilderData::new();
             builder.append_raw(data, data.len() * 8)?;
             Ok(builder)
@@ -392,7 +415,7 @@ impl TokenValue {
         abi_version: &AbiVersion,
     ) -> Result<HashmapE> {
         let key_len = Self::get_map_key_size(key_type)?;
-        let value_len = Self::max_bit_size(value_type, abi_version);
+        let value_len = value_type.max_bit_size(abi_version);
         let value_in_ref = Self::map_value_in_ref(key_len, value_len);
 
         let mut hashmap = HashmapE::with_bit_len(key_len);
@@ -415,8 +438,9 @@ impl TokenValue {
                 })
             }
 
-            let data =
-                Self::pack_cells_into_chain(value.write_to_cells(abi_version)?, abi_version)?;
+            let cells = value.write_to_cells(abi_version)
+                .map_err(|err| ParamPath::prepend(err, format!("[{}]", key)))?;
+            let data = Self::pack_cells_into_chain(cells, abi_version)?;
 
             let slice_key = SliceData::load_builder(key_vec.pop().unwrap().data)?;
             if value_in_ref {
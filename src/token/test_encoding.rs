@@ -1262,3 +1262,52 @@ fn test_fixed_bytes() {
         &[ABI_VERSION_2_4],
     );
 }
+
+#[test]
+fn test_fixed_point() {
+    // test prefix with one ref and u32
+    let mut builder = BuilderData::new();
+    builder.append_u32(0).unwrap();
+    builder.checked_append_reference(Cell::default()).unwrap();
+
+    builder.append_i64(-1005).unwrap();
+
+    let values = vec![TokenValue::FixedPoint(Int::new(-1005, 64), 3)];
+
+    test_parameters_set(
+        &tokens_from_values(values.clone()),
+        None,
+        builder,
+        &[ABI_VERSION_2_1, ABI_VERSION_2_4],
+    );
+    assert_eq!(values[0].to_string(), "-1.005");
+}
+
+#[test]
+fn test_long_fixed_bytes() {
+    // values longer than one cell are stored as bytes cell chain
+    let bytes = vec![0x55; 256]; // 256 = 127 + 127 + 2
+    let mut builder = BuilderData::with_raw(smallvec![0x55; 127], 127 * 8).unwrap();
+    builder.checked_append_reference(BuilderData::with_raw(smallvec![0x55; 2], 2 * 8).unwrap().into_cell().unwrap()).unwrap();
+    let mut bytes_builder = BuilderData::with_raw(smallvec![0x55; 127], 127 * 8).unwrap();
+    bytes_builder.checked_append_reference(builder.into_cell().unwrap()).unwrap();
+
+    // test prefix with one ref and u32
+    let mut builder = BuilderData::new();
+    builder.append_u32(0).unwrap();
+    builder.checked_append_reference(Cell::default()).unwrap();
+
+    builder.checked_append_reference(bytes_builder.into_cell().unwrap()).unwrap();
+    builder.append_u8(7).unwrap();
+
+    let values = vec![TokenValue::FixedBytes(bytes), TokenValue::Uint(Uint::new(7, 8))];
+
+    test_parameters_set(
+        &tokens_from_values(values),
+        None,
+        builder,
+        &[ABI_VERSION_2_3, ABI_VERSION_2_4],
+    );
+    assert!(!TokenValue::is_inline_fixed_bytes(128, &ABI_VERSION_2_4));
+    assert!(TokenValue::is_inline_fixed_bytes(127, &ABI_VERSION_2_4));
+}
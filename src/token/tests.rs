@@ -17,8 +17,9 @@ mod tokenize_tests {
     use std::collections::BTreeMap;
     use crate::token::{Detokenizer, Tokenizer};
     use ton_block::{MsgAddress};
-    use ton_types::{AccountId, BuilderData, SliceData};
+    use ton_types::{AccountId, BuilderData, IBitstring, SliceData};
     use smallvec::smallvec;
+    use crate::contract::ABI_VERSION_2_2;
 
     #[test]
     fn test_tokenize_ints() {
@@ -671,6 +672,151 @@ mod tokenize_tests {
         );
     }
 
+    #[test]
+    fn test_tokenize_address_var_ext() {
+        let input = r#"{
+            "var": "-177:555_",
+            "ext": ":555_",
+            "none": null
+        }"#;
+
+        let params = vec![
+            Param::new("var", ParamType::AddressVar),
+            Param::new("ext", ParamType::AddressExt),
+            Param::new("none", ParamType::AddressExt),
+        ];
+
+        let expected_tokens = vec![
+            Token::new("var", TokenValue::AddressVar(
+                MsgAddress::with_variant(None, -177, SliceData::new(vec![0x55, 0x50])).unwrap())),
+            Token::new("ext", TokenValue::AddressExt(
+                MsgAddress::with_extern(SliceData::new(vec![0x55, 0x50])).unwrap())),
+            Token::new("none", TokenValue::AddressExt(MsgAddress::AddrNone)),
+        ];
+
+        assert_eq!(
+            Tokenizer::tokenize_all_params(&params, &serde_json::from_str(input).unwrap()).unwrap(),
+            expected_tokens
+        );
+
+        // check that detokenizer gives the same result
+        let input = Detokenizer::detokenize(&expected_tokens).unwrap();
+        assert_eq!(
+            Tokenizer::tokenize_all_params(&params, &serde_json::from_str(&input).unwrap()).unwrap(),
+            expected_tokens
+        );
+
+        let std = serde_json::json!("0:5555555555555555555555555555555555555555555555555555555555555555");
+        assert!(Tokenizer::tokenize_parameter(&ParamType::AddressVar, &std, "var").is_err());
+        assert!(Tokenizer::tokenize_parameter(&ParamType::AddressExt, &std, "ext").is_err());
+        assert!(Tokenizer::tokenize_parameter(&ParamType::AddressStd, &serde_json::json!(":555_"), "std").is_err());
+
+        // check encoding
+        let encoded = TokenValue::pack_values_into_chain(&expected_tokens, vec![], &ABI_VERSION_2_2).unwrap();
+        assert_eq!(
+            TokenValue::decode_params(&params, SliceData::load_builder(encoded).unwrap(), &ABI_VERSION_2_2, false).unwrap(),
+            expected_tokens
+        );
+        assert!(TokenValue::decode_params(
+            &[Param::new("ext", ParamType::AddressVar)],
+            SliceData::load_builder(expected_tokens[1].value.pack_into_chain(&ABI_VERSION_2_2).unwrap()).unwrap(),
+            &ABI_VERSION_2_2,
+            false,
+        ).is_err());
+    }
+
+    #[test]
+    fn test_tokenize_bits() {
+        let input = r#"{
+            "a": "0b10101",
+            "b": "ac_",
+            "c": "5f"
+        }"#;
+
+        let params = vec![
+            Param::new("a", ParamType::Bits(5)),
+            Param::new("b", ParamType::Bits(5)),
+            Param::new("c", ParamType::Bits(8)),
+        ];
+
+        let expected_tokens = vec![
+            Token::new("a", TokenValue::Bits(SliceData::from_raw(vec![0b10101000], 5))),
+            Token::new("b", TokenValue::Bits(SliceData::from_raw(vec![0b10101000], 5))),
+            Token::new("c", TokenValue::Bits(SliceData::from_raw(vec![0x5f], 8))),
+        ];
+
+        assert_eq!(
+            Tokenizer::tokenize_all_params(&params, &serde_json::from_str(input).unwrap()).unwrap(),
+            expected_tokens
+        );
+        assert_eq!(
+            Detokenizer::detokenize_to_json_value(&expected_tokens).unwrap(),
+            serde_json::json!({"a": "ac_", "b": "ac_", "c": "5f"})
+        );
+
+        assert!(Tokenizer::tokenize_parameter(&ParamType::Bits(4), &serde_json::json!("0b101"), "a").is_err());
+        assert!(Tokenizer::tokenize_parameter(&ParamType::Bits(8), &serde_json::json!("xy"), "a").is_err());
+        assert!(Tokenizer::tokenize_parameter(&ParamType::Bits(4), &serde_json::json!("00_"), "a").is_err());
+
+        // bits are placed inline
+        let mut builder = BuilderData::new();
+        builder.append_raw(&[0b10101101, 0b01010111, 0b11000000], 18).unwrap();
+        let encoded = TokenValue::pack_values_into_chain(&expected_tokens, vec![], &ABI_VERSION_2_2).unwrap();
+        assert_eq!(encoded, builder);
+        assert_eq!(
+            TokenValue::decode_params(&params, SliceData::load_builder(encoded).unwrap(), &ABI_VERSION_2_2, false).unwrap(),
+            expected_tokens
+        );
+    }
+
+    #[test]
+    fn test_tokenize_slice() {
+        let params = vec![
+            Param::new("a", ParamType::Slice),
+            Param::new("b", ParamType::Slice),
+            Param::new("c", ParamType::Uint(8)),
+        ];
+        let input = serde_json::json!({"a": "0b10101", "b": "", "c": 7});
+
+        let expected_tokens = vec![
+            Token::new("a", TokenValue::Slice(SliceData::from_raw(vec![0b10101000], 5))),
+            Token::new("b", TokenValue::Slice(SliceData::from_raw(vec![], 0))),
+            Token::new("c", TokenValue::Uint(Uint::new(7, 8))),
+        ];
+        assert_eq!(Tokenizer::tokenize_all_params(&params, &input).unwrap(), expected_tokens);
+        assert_eq!(
+            Detokenizer::detokenize_to_json_value(&expected_tokens).unwrap(),
+            serde_json::json!({"a": "ac_", "b": "", "c": "7"})
+        );
+
+        // length prefix is followed by bits, values are packed by actual size before ABI 2.2
+        let mut builder = BuilderData::new();
+        builder.append_bits(5, 10).unwrap();
+        builder.append_bits(0b10101, 5).unwrap();
+        builder.append_bits(0, 10).unwrap();
+        builder.append_u8(7).unwrap();
+        let abi_version = crate::contract::ABI_VERSION_2_1;
+        let encoded = TokenValue::pack_values_into_chain(&expected_tokens, vec![], &abi_version).unwrap();
+        assert_eq!(encoded, builder);
+        assert_eq!(
+            TokenValue::decode_params(&params, SliceData::load_builder(encoded).unwrap(), &abi_version, false).unwrap(),
+            expected_tokens
+        );
+
+        // since ABI 2.2 slice occupies maximum size in the layout
+        let encoded = TokenValue::pack_values_into_chain(&expected_tokens, vec![], &ABI_VERSION_2_2).unwrap();
+        assert_eq!(encoded.references_used(), 1);
+        assert_eq!(
+            TokenValue::decode_params(&params, SliceData::load_builder(encoded).unwrap(), &ABI_VERSION_2_2, false).unwrap(),
+            expected_tokens
+        );
+
+        let max = "f".repeat(crate::param_type::MAX_SLICE_BITS / 4) + "c_";
+        assert!(Tokenizer::tokenize_parameter(&ParamType::Slice, &serde_json::json!(max), "a").is_ok());
+        let long = "f".repeat(crate::param_type::MAX_SLICE_BITS / 4 + 1);
+        assert!(Tokenizer::tokenize_parameter(&ParamType::Slice, &serde_json::json!(long), "a").is_err());
+    }
+
     #[test]
     fn test_tokenize_bytes() {
         let input = r#"{
@@ -940,6 +1086,45 @@ mod tokenize_tests {
         );
     }
 
+    #[test]
+    fn test_tokenize_fixed_point() {
+        let input = r#"{
+            "a": "1.5",
+            "b": "-0.005",
+            "c": 42,
+            "d": "-128"
+        }"#;
+
+        let params = vec![
+            Param::new("a", ParamType::FixedPoint { bits: 64, decimals: 3 }),
+            Param::new("b", ParamType::FixedPoint { bits: 64, decimals: 3 }),
+            Param::new("c", ParamType::FixedPoint { bits: 64, decimals: 0 }),
+            Param::new("d", ParamType::FixedPoint { bits: 8, decimals: 0 }),
+        ];
+
+        let expected_tokens = vec![
+            Token::new("a", TokenValue::FixedPoint(Int::new(1500, 64), 3)),
+            Token::new("b", TokenValue::FixedPoint(Int::new(-5, 64), 3)),
+            Token::new("c", TokenValue::FixedPoint(Int::new(42, 64), 0)),
+            Token::new("d", TokenValue::FixedPoint(Int::new(-128, 8), 0)),
+        ];
+
+        let tokens = Tokenizer::tokenize_all_params(&params, &serde_json::from_str(input).unwrap()).unwrap();
+        assert_eq!(tokens, expected_tokens);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&Detokenizer::detokenize(&tokens).unwrap()).unwrap(),
+            serde_json::json!({"a": "1.500", "b": "-0.005", "c": "42", "d": "-128"})
+        );
+
+        // too many fractional digits
+        assert!(Tokenizer::tokenize_parameter(&params[0].kind, &serde_json::json!("1.0001"), "a").is_err());
+        // out of range
+        assert!(Tokenizer::tokenize_parameter(&params[3].kind, &serde_json::json!("128"), "d").is_err());
+        // not a number
+        assert!(Tokenizer::tokenize_parameter(&params[0].kind, &serde_json::json!("1.2.3"), "a").is_err());
+        assert!(Tokenizer::tokenize_parameter(&params[0].kind, &serde_json::json!("."), "a").is_err());
+    }
+
     #[test]
     fn test_tokenize_ref() {
         let input = r#"{
@@ -1328,3 +1513,254 @@ mod default_values_tests {
         }
     }
 }
+
+mod partial_decode_tests {
+    use crate::{Int, MapKeyTokenValue, Param, ParamType, Token, TokenValue, Uint};
+    use crate::contract::ABI_VERSION_2_2;
+    use crate::token::{DecodedToken, TrailingDataPolicy};
+    use std::collections::BTreeMap;
+    use ton_block::MerkleProof;
+    use ton_types::{BuilderData, IBitstring, SliceData};
+
+    #[test]
+    fn test_decode_array_element() {
+        let items = (0..10u128)
+            .map(|i| TokenValue::Uint(Uint::new(i * 100, 32)))
+            .collect::<Vec<_>>();
+        let param_type = ParamType::Array(Box::new(ParamType::Uint(32)));
+        let array = TokenValue::Array(ParamType::Uint(32), items.clone());
+        let slice = SliceData::load_builder(array.pack_into_chain(&ABI_VERSION_2_2).unwrap()).unwrap();
+
+        assert_eq!(
+            TokenValue::decode_array_element(&param_type, slice.clone(), 7, &ABI_VERSION_2_2).unwrap(),
+            Some(items[7].clone())
+        );
+        assert_eq!(
+            TokenValue::decode_array_element(&param_type, slice, 10, &ABI_VERSION_2_2).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_decode_map_value() {
+        let mut map = BTreeMap::new();
+        map.insert(MapKeyTokenValue::Int(Int::new(-5, 16)), TokenValue::Bool(true));
+        map.insert(MapKeyTokenValue::Int(Int::new(42, 16)), TokenValue::Bool(false));
+        let value = TokenValue::Map(ParamType::Int(16), ParamType::Bool, map);
+        let slice = SliceData::load_builder(value.pack_into_chain(&ABI_VERSION_2_2).unwrap()).unwrap();
+
+        let decode = |key: i128| TokenValue::decode_map_value(
+            &ParamType::Int(16),
+            &ParamType::Bool,
+            slice.clone(),
+            &MapKeyTokenValue::Int(Int::new(key, 16)),
+            &ABI_VERSION_2_2,
+        ).unwrap();
+
+        assert_eq!(decode(-5), Some(TokenValue::Bool(true)));
+        assert_eq!(decode(42), Some(TokenValue::Bool(false)));
+        assert_eq!(decode(0), None);
+    }
+
+    #[test]
+    fn test_trailing_data_policy() {
+        let mut builder = BuilderData::new();
+        builder.append_u32(1).unwrap();
+        builder.append_u8(0xAB).unwrap();
+        let slice = SliceData::load_builder(builder).unwrap();
+        let params = [Param::new("a", ParamType::Uint(32))];
+        let expected = vec![Token::new("a", TokenValue::Uint(Uint::new(1, 32)))];
+
+        assert!(TokenValue::decode_params_with_policy(
+            &params, slice.clone(), &ABI_VERSION_2_2, TrailingDataPolicy::Error
+        ).is_err());
+
+        let (tokens, remainder) = TokenValue::decode_params_with_policy(
+            &params, slice.clone(), &ABI_VERSION_2_2, TrailingDataPolicy::Ignore
+        ).unwrap();
+        assert_eq!(tokens, expected);
+        assert!(remainder.is_none());
+
+        let (tokens, remainder) = TokenValue::decode_params_with_policy(
+            &params, slice, &ABI_VERSION_2_2, TrailingDataPolicy::ReturnRemainder
+        ).unwrap();
+        assert_eq!(tokens, expected);
+        assert_eq!(remainder.unwrap().get_next_byte().unwrap(), 0xAB);
+    }
+
+    #[test]
+    fn test_decode_params_recovering() {
+        let mut builder = BuilderData::new();
+        builder.append_u32(1).unwrap();
+        builder.append_bits(0b11, 2).unwrap();  // truncated var address
+        builder.append_u8(7).unwrap();
+        let slice = SliceData::load_builder(builder).unwrap();
+
+        let params = [
+            Param::new("a", ParamType::Uint(32)),
+            Param::new("b", ParamType::AddressStd),
+            Param::new("c", ParamType::Uint(8)),
+        ];
+
+        let (tokens, errors) = TokenValue::decode_params_recovering(
+            &params, slice, &ABI_VERSION_2_2, false
+        );
+        assert_eq!(tokens, vec![Token::new("a", TokenValue::Uint(Uint::new(1, 32)))]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "b");
+    }
+
+    #[test]
+    fn test_read_from_depth_limit() {
+        let nested = |levels: usize| {
+            let mut kind = ParamType::Bool;
+            for _ in 0..levels {
+                kind = ParamType::Optional(Box::new(kind));
+            }
+            kind
+        };
+        let mut data = BuilderData::new();
+        for _ in 0..=crate::token::MAX_DECODE_DEPTH {
+            data.append_bit_one().unwrap();
+        }
+        let data = SliceData::load_builder(data).unwrap();
+
+        let kind = nested(crate::token::MAX_DECODE_DEPTH - 1);
+        assert!(TokenValue::read_from(&kind, data.clone().into(), false, &ABI_VERSION_2_2, true).is_ok());
+
+        let kind = nested(crate::token::MAX_DECODE_DEPTH);
+        let err = TokenValue::read_from(&kind, data.clone().into(), false, &ABI_VERSION_2_2, true).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<crate::AbiError>(),
+            Some(crate::AbiError::MaxDepthExceeded { depth, .. }) if *depth == crate::token::MAX_DECODE_DEPTH + 1
+        ), "{}", err);
+
+        // limit can be raised explicitly
+        let params = [Param::new("a", kind)];
+        let max_depth = crate::token::MAX_DECODE_DEPTH + 1;
+        assert!(TokenValue::decode_params(&params, data.clone(), &ABI_VERSION_2_2, true).is_err());
+        let tokens = TokenValue::decode_params_with_max_depth(&params, data.clone(), &ABI_VERSION_2_2, true, max_depth).unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert!(TokenValue::decode_params_with_max_depth(&params, data, &ABI_VERSION_2_2, true, max_depth - 1).is_err());
+    }
+
+    #[test]
+    fn test_decode_params_allow_pruned() {
+        let mut string = BuilderData::new();
+        string.append_raw(b"hidden", 48).unwrap();
+        let string = string.into_cell().unwrap();
+        let pruned_hash = string.repr_hash();
+
+        let mut root = BuilderData::new();
+        root.append_u32(1).unwrap();
+        root.checked_append_reference(string).unwrap();
+        root.append_u8(2).unwrap();
+        let root = root.into_cell().unwrap();
+        let proof = MerkleProof::create(&root, |hash| *hash != pruned_hash).unwrap().proof;
+        let slice = SliceData::load_cell(proof).unwrap();
+
+        let params = [
+            Param::new("a", ParamType::Uint(32)),
+            Param::new("s", ParamType::String),
+            Param::new("b", ParamType::Uint(8)),
+        ];
+        assert_eq!(
+            TokenValue::decode_params_allow_pruned(&params, slice.clone(), &ABI_VERSION_2_2, false).unwrap(),
+            vec![
+                DecodedToken::Available(Token::new("a", TokenValue::Uint(Uint::new(1, 32)))),
+                DecodedToken::Unavailable("s".to_owned()),
+                DecodedToken::Available(Token::new("b", TokenValue::Uint(Uint::new(2, 8)))),
+            ]
+        );
+
+        // errors not caused by reading pruned cell are reported even if pruned cell is present
+        let params = [
+            Param::new("a", ParamType::Uint(32)),
+            Param::new("x", ParamType::Uint(64)),
+            Param::new("s", ParamType::String),
+        ];
+        assert!(TokenValue::decode_params_allow_pruned(&params, slice.clone(), &ABI_VERSION_2_2, false).is_err());
+
+        // the same applies to errors raised while skipping value located in pruned cell
+        let params = [
+            Param::new("a", ParamType::Uint(32)),
+            Param::new("t", ParamType::Tuple(vec![
+                Param::new("s", ParamType::String),
+                Param::new("x", ParamType::Uint(64)),
+            ])),
+        ];
+        assert!(TokenValue::decode_params_allow_pruned(&params, slice, &ABI_VERSION_2_2, false).is_err());
+    }
+}
+
+
+mod param_path_tests {
+    use crate::{AbiError, Param, ParamType, Token, TokenValue, Uint};
+    use crate::contract::ABI_VERSION_2_2;
+    use crate::token::{ParamPath, Tokenizer};
+    use ton_types::SliceData;
+
+    fn params(item_type: ParamType) -> Vec<Param> {
+        vec![Param::new("data", ParamType::Tuple(vec![
+            Param::new("values", ParamType::Array(Box::new(item_type))),
+        ]))]
+    }
+
+    #[test]
+    fn test_tokenize_error_path() {
+        let params = vec![Param::new("recipients", ParamType::Array(Box::new(ParamType::Tuple(vec![
+            Param::new("addr", ParamType::Address),
+            Param::new("amount", ParamType::Uint(8)),
+        ]))))];
+        let values = serde_json::json!({"recipients": [
+            {"addr": null, "amount": 1},
+            {"addr": "xyz", "amount": 2},
+        ]});
+
+        let err = Tokenizer::tokenize_all_params(&params, &values).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AbiError>(),
+            Some(AbiError::InvalidParameterValue { name, .. }) if name == "recipients[1].addr"
+        ), "{}", err);
+
+        let params = vec![Param::new("amounts", ParamType::Map(Box::new(ParamType::Uint(8)), Box::new(ParamType::Uint(8))))];
+        let err = Tokenizer::tokenize_all_params(&params, &serde_json::json!({"amounts": {"1": 1, "2": 300}})).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AbiError>(),
+            Some(AbiError::InvalidParameterValue { name, .. }) if name == "amounts[2]"
+        ), "{}", err);
+    }
+
+    #[test]
+    fn test_serialize_error_path() {
+        let tokens = vec![Token::new("data", TokenValue::Tuple(vec![
+            Token::new("values", TokenValue::Array(ParamType::Uint(8), vec![
+                TokenValue::Uint(Uint::new(1, 8)),
+                TokenValue::Uint(Uint::new(300, 8)),
+            ])),
+        ]))];
+
+        let err = TokenValue::pack_values_into_chain(&tokens, vec![], &ABI_VERSION_2_2).unwrap_err();
+        assert_eq!(ParamPath::of(&err), Some("data.values[1]"));
+        assert!(matches!(err.downcast_ref::<AbiError>(), Some(AbiError::InvalidData { .. })));
+        assert!(err.to_string().contains("data.values[1]"));
+    }
+
+    #[test]
+    fn test_deserialize_error_path() {
+        let tokens = vec![Token::new("data", TokenValue::Tuple(vec![
+            Token::new("values", TokenValue::Array(ParamType::Uint(8), vec![
+                TokenValue::Uint(Uint::new(1, 8)),
+            ])),
+        ]))];
+        let data = TokenValue::pack_values_into_chain(&tokens, vec![], &ABI_VERSION_2_2).unwrap();
+
+        let err = TokenValue::decode_params(
+            &params(ParamType::Uint(32)),
+            SliceData::load_builder(data).unwrap(),
+            &ABI_VERSION_2_2,
+            false,
+        ).unwrap_err();
+        assert_eq!(ParamPath::of(&err), Some("data.values[0]"));
+    }
+}
@@ -13,16 +13,16 @@
 
 //! ABI param and parsing for it.
 use crate::{
-    error::AbiError, int::{Int, Uint}, param::Param, param_type::ParamType,
-    token::{Token, MapKeyTokenValue, TokenValue}
+    error::AbiError, int::{Int, Uint}, param::{Param, ParamAttributes, NO_ATTRIBUTES}, param_type::{ParamType, MAX_SLICE_BITS},
+    token::{Token, MapKeyTokenValue, TokenValue}, warnings::Warnings,
 };
 
 use serde_json::Value;
-use std::{collections::{HashMap, BTreeMap}, str::FromStr};
+use std::{collections::{HashMap, BTreeMap}, str::FromStr, sync::Arc};
 use num_bigint::{Sign, BigInt, BigUint};
 use num_traits::cast::ToPrimitive;
 use ton_block::{Grams, MsgAddress};
-use ton_types::{deserialize_tree_of_cells, error, fail, Cell, Result};
+use ton_types::{deserialize_tree_of_cells, error, fail, Cell, Result, SliceData};
 //use ton_types::cells_serialization::deserialize_tree_of_cells;
 
 /// This struct should be used to parse string values as tokens.
@@ -89,14 +89,20 @@ impl Tokenizer {
             ParamType::Int(size) => Self::tokenize_int(*size, value, name),
             ParamType::VarUint(size) => Self::tokenize_varuint(*size, value, name),
             ParamType::VarInt(size) => Self::tokenize_varint(*size, value, name),
+            ParamType::FixedPoint { bits, decimals } => Self::tokenize_fixed_point(*bits, *decimals, value, name),
+            ParamType::Enum(variants) => Self::tokenize_enum(variants, value, name),
+            ParamType::Bits(size) => Self::tokenize_bits(Some(*size), value, name),
+            ParamType::Slice => Self::tokenize_bits(None, value, name),
             ParamType::Bool => Self::tokenize_bool(value, name),
-            ParamType::Tuple(tuple_params) => Self::tokenize_tuple(tuple_params, value),
+            ParamType::Tuple(tuple_params) => Self::tokenize_tuple(tuple_params, value, name),
             ParamType::Array(param_type) => Self::tokenize_array(param_type, value, name),
             ParamType::FixedArray(param_type, size) => Self::tokenize_fixed_array(param_type, *size, value, name),
             ParamType::Cell => Self::tokenize_cell(value, name),
             ParamType::Map(key_type, value_type) => Self::tokenize_hashmap(key_type, value_type, value, name),
             ParamType::Address => Self::tokenize_address(value, name),
             ParamType::AddressStd => Self::tokenize_address_std(value, name),
+            ParamType::AddressVar => Self::tokenize_address_var(value, name),
+            ParamType::AddressExt => Self::tokenize_address_ext(value, name),
             ParamType::Bytes => Self::tokenize_bytes(value, None, name),
             ParamType::FixedBytes(size) => Self::tokenize_bytes(value, Some(*size), name),
             ParamType::String => Self::tokenize_string(value, name),
@@ -111,13 +117,60 @@ impl Tokenizer {
 
     /// Tries to parse parameters from JSON values to tokens.
     pub fn tokenize_all_params(params: &[Param], values: &Value) -> Result<Vec<Token>> {
+        Self::tokenize_params_at(params, &[], values, "")
+    }
+
+    /// Tries to parse parameters from JSON values to tokens. Values of params and their
+    /// tuple components missing in `values` are taken from `default` of corresponding
+    /// `attributes` item
+    pub fn tokenize_all_params_with_defaults(
+        params: &[Param],
+        attributes: &[ParamAttributes],
+        values: &Value,
+    ) -> Result<Vec<Token>> {
+        Self::tokenize_params_at(params, attributes, values, "")
+    }
+
+    /// Tries to parse parameters from JSON values to tokens applying defaults from
+    /// `attributes`. JSON object fields which don't correspond to any param are reported
+    /// into `warnings`
+    pub fn tokenize_all_params_with_warnings(
+        params: &[Param],
+        attributes: &[ParamAttributes],
+        values: &Value,
+        warnings: &mut Warnings,
+    ) -> Result<Vec<Token>> {
+        let tokens = Self::tokenize_params_at(params, attributes, values, "")?;
+        warnings.check_ignored_fields(params, values);
+        Ok(tokens)
+    }
+
+    /// Tries to parse parameters from JSON values to tokens. Errors refer to params by
+    /// their full path starting with `path`
+    fn tokenize_params_at(
+        params: &[Param],
+        attributes: &[ParamAttributes],
+        values: &Value,
+        path: &str,
+    ) -> Result<Vec<Token>> {
         if let Value::Object(map) = values {
             let mut tokens = Vec::new();
-            for param in params {
+            for (i, param) in params.iter().enumerate() {
+                let attributes = attributes.get(i).unwrap_or(&NO_ATTRIBUTES);
                 let value = map
                     .get(&param.name)
+                    .or(attributes.default.as_ref())
                     .unwrap_or(&Value::Null);
-                let token_value = Self::tokenize_parameter(&param.kind, value, &param.name)?;
+                let name = if path.is_empty() {
+                    param.name.clone()
+                } else {
+                    format!("{}.{}", path, param.name)
+                };
+                let token_value = match &param.kind {
+                    ParamType::Tuple(components) =>
+                        Self::tokenize_tuple_with_defaults(components, &attributes.components, value, &name)?,
+                    kind => Self::tokenize_parameter(kind, value, &name)?,
+                };
                 tokens.push(Token { name: param.name.clone(), value: token_value});
             }
 
@@ -166,8 +219,8 @@ impl Tokenizer {
     fn read_array(item_type: &ParamType, value: &Value, name: &str) -> Result<Vec<TokenValue>> {
         if let Value::Array(array) = value {
             let mut tokens = Vec::new();
-            for value in array {
-                tokens.push(Self::tokenize_parameter(item_type, value, name)?);
+            for (index, value) in array.iter().enumerate() {
+                tokens.push(Self::tokenize_parameter(item_type, value, &format!("{}[{}]", name, index))?);
             }
 
             Ok(tokens)
@@ -226,6 +279,86 @@ impl Tokenizer {
         }
     }
 
+    /// Tries to parse a value as enum variant. Value should be variant name or its index
+    fn tokenize_enum(variants: &Arc<[String]>, value: &Value, name: &str) -> Result<TokenValue> {
+        let index = match value {
+            Value::String(string) => variants.iter().position(|variant| variant == string)
+                .or_else(|| string.parse::<usize>().ok()),
+            Value::Number(number) => number.as_u64().map(|index| index as usize),
+            _ => fail!(AbiError::WrongDataFormat {
+                val: value.clone(),
+                name: name.to_string(),
+                expected: "enum variant name or index".to_string()
+            }),
+        };
+        match index {
+            Some(index) if index < variants.len() => Ok(TokenValue::Enum(variants.clone(), index as u8)),
+            _ => fail!(AbiError::InvalidParameterValue {
+                val: value.clone(),
+                name: name.to_string(),
+                err: format!("unknown enum variant, expected one of: {}", variants.join(", "))
+            }),
+        }
+    }
+
+    /// Tries to parse a value as raw bits of `bits<size>` or `slice` if `size` is not provided.
+    /// Value should be hex string with optional `_` completion tag or binary string with `0b`
+    /// prefix, e.g. `"a8_"` or `"0b10101"`
+    fn tokenize_bits(size: Option<usize>, value: &Value, name: &str) -> Result<TokenValue> {
+        let string = value.as_str().ok_or_else(|| AbiError::WrongDataFormat {
+            val: value.clone(),
+            name: name.to_string(),
+            expected: "hex or binary string".to_string()
+        })?;
+        let invalid = |err: &str| AbiError::InvalidParameterValue {
+            val: value.clone(),
+            name: name.to_string(),
+            err: err.to_string(),
+        };
+
+        let mut bits = vec![];
+        if let Some(binary) = string.strip_prefix("0b") {
+            for c in binary.chars() {
+                match c {
+                    '0' => bits.push(false),
+                    '1' => bits.push(true),
+                    _ => fail!(invalid("can not parse binary string")),
+                }
+            }
+        } else {
+            let (hex, tag) = match string.strip_suffix('_') {
+                Some(hex) => (hex, true),
+                None => (string, false),
+            };
+            for c in hex.chars() {
+                let digit = c.to_digit(16).ok_or_else(|| invalid("can not parse hex string"))?;
+                bits.extend((0..4).rev().map(|i| digit & (1 << i) != 0));
+            }
+            if tag {
+                match bits.iter().rposition(|bit| *bit) {
+                    Some(position) => bits.truncate(position),
+                    None => fail!(invalid("completion tag is not found")),
+                }
+            }
+        }
+
+        let len = bits.len();
+        match size {
+            Some(size) if len != size => fail!(invalid(&format!("expected {} bits, provided {}", size, len))),
+            None if len > MAX_SLICE_BITS => fail!(invalid(&format!("slice can't be longer than {} bits", MAX_SLICE_BITS))),
+            _ => {}
+        }
+
+        let mut data = vec![0u8; (len + 7) / 8];
+        for (index, bit) in bits.into_iter().enumerate() {
+            if bit {
+                data[index / 8] |= 0x80 >> (index % 8);
+            }
+        }
+        let slice = SliceData::from_raw(data, len);
+        Ok(if size.is_some() { TokenValue::Bits(slice) } else { TokenValue::Slice(slice) })
+    }
+
     /// Tries to read integer number from `Value`
     fn read_int(value: &Value, name: &str) -> Result<BigInt> {
         if let Some(number) = value.as_i64() {
@@ -373,6 +506,51 @@ impl Tokenizer {
         }
     }
 
+    /// Tries to parse a value as fixed-point decimal number. Value should be decimal string
+    /// or integer number with no more than `decimals` fractional digits
+    fn tokenize_fixed_point(bits: usize, decimals: usize, value: &Value, name: &str) -> Result<TokenValue> {
+        let invalid = |err: &str| AbiError::InvalidParameterValue {
+            val: value.clone(),
+            name: name.to_string(),
+            err: err.to_string(),
+        };
+        let string = match value {
+            Value::String(string) => string.trim().to_owned(),
+            Value::Number(number) if number.is_i64() || number.is_u64() => number.to_string(),
+            _ => fail!(AbiError::WrongDataFormat {
+                val: value.clone(),
+                name: name.to_string(),
+                expected: "decimal string or integer number".to_string()
+            }),
+        };
+
+        let (negative, unsigned) = match string.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, string.strip_prefix('+').unwrap_or(&string)),
+        };
+        let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if integer.is_empty() && fraction.is_empty()
+            || !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+        {
+            fail!(invalid("can not parse decimal number from string"));
+        }
+        if fraction.len() > decimals {
+            fail!(invalid("too many fractional digits"));
+        }
+
+        let digits = format!("{}{}{}", integer, fraction, "0".repeat(decimals - fraction.len()));
+        let mut number = BigInt::parse_bytes(digits.as_bytes(), 10)
+            .ok_or_else(|| invalid("can not parse decimal number from string"))?;
+        if negative {
+            number = -number;
+        }
+
+        if !Self::check_int_size(&number, bits) {
+            fail!(invalid("provided number is out of type range"))
+        }
+        Ok(TokenValue::FixedPoint(Int { number, size: bits }, decimals))
+    }
+
     fn tokenize_cell(value: &Value, name: &str) -> Result<TokenValue> {
         let string = value.as_str().ok_or_else(|| AbiError::WrongDataFormat {
             val: value.clone(),
@@ -408,8 +586,9 @@ impl Tokenizer {
         if let Value::Object(map) = map_value {
             let mut new_map = BTreeMap::<MapKeyTokenValue, TokenValue>::new();
             for (key, value) in map.iter() {
-                let key = Self::tokenize_map_key_parameter(key_type, key, name)?;
-                let value = Self::tokenize_parameter(value_type, value, name)?;
+                let path = format!("{}[{}]", name, key);
+                let key = Self::tokenize_map_key_parameter(key_type, key, &path)?;
+                let value = Self::tokenize_parameter(value_type, value, &path)?;
                 new_map.insert(key, value);
             }
             Ok(TokenValue::Map(
@@ -466,8 +645,17 @@ impl Tokenizer {
     }
 
     /// Tries to parse a value as tuple.
-    fn tokenize_tuple(params: &[Param], value: &Value) -> Result<TokenValue> {
-        let tokens = Self::tokenize_all_params(params, value)?;
+    fn tokenize_tuple(params: &[Param], value: &Value, name: &str) -> Result<TokenValue> {
+        Self::tokenize_tuple_with_defaults(params, &[], value, name)
+    }
+
+    fn tokenize_tuple_with_defaults(
+        params: &[Param],
+        attributes: &[ParamAttributes],
+        value: &Value,
+        name: &str,
+    ) -> Result<TokenValue> {
+        let tokens = Self::tokenize_params_at(params, attributes, value, name)?;
 
         Ok(TokenValue::Tuple(tokens))
     }
@@ -575,6 +763,38 @@ impl Tokenizer {
         }
         Ok(TokenValue::AddressStd(address))
     }
+
+    fn tokenize_address_var(value: &Value, name: &str) -> Result<TokenValue> {
+        let address = Self::get_msg_address(value, name)?;
+        match address {
+            MsgAddress::AddrNone => {}
+            MsgAddress::AddrVar(_) => {}
+            MsgAddress::AddrStd(_) | MsgAddress::AddrExt(_) => {
+                fail!(AbiError::InvalidParameterValue {
+                    val: value.clone(),
+                    name: name.to_string(),
+                    err: "Expected var or none address".to_string(),
+                })
+            }
+        }
+        Ok(TokenValue::AddressVar(address))
+    }
+
+    fn tokenize_address_ext(value: &Value, name: &str) -> Result<TokenValue> {
+        let address = Self::get_msg_address(value, name)?;
+        match address {
+            MsgAddress::AddrNone => {}
+            MsgAddress::AddrExt(_) => {}
+            MsgAddress::AddrStd(_) | MsgAddress::AddrVar(_) => {
+                fail!(AbiError::InvalidParameterValue {
+                    val: value.clone(),
+                    name: name.to_string(),
+                    err: "Expected external or none address".to_string(),
+                })
+            }
+        }
+        Ok(TokenValue::AddressExt(address))
+    }
 }
 
 fn read_int_string(string: &str) -> Option<BigInt> {
@@ -0,0 +1,344 @@
+/*
+* Copyright 2018-2020 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Named type aliases declared in `types` section and named tuple layouts declared
+//! in `structs` section of ABI JSON.
+//!
+//! Alias is declared either as type string (`"Amount": "uint128"`) or as param
+//! description without name (`"Payload": {"type": "tuple", "components": [...]}`).
+//! Struct is declared as `{"name": "Transfer", "components": [...]}` and referenced as
+//! `struct:Transfer`. Aliases and structs can be used in param types anywhere a type name
+//! is allowed, e.g. `Payload[]` or `map(uint32,struct:Transfer)`, and are expanded when
+//! ABI is loaded. Name of referenced struct is kept in `struct` field of expanded param.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde_json::{Map, Value};
+use ton_types::{fail, Result};
+
+use crate::error::AbiError;
+use crate::param::Param;
+use crate::param_type::read_type;
+use crate::token::MAX_DECODE_DEPTH;
+
+/// Name of ABI JSON section with type aliases
+pub const TYPES_SECTION: &str = "types";
+/// Name of ABI JSON section with named structs
+pub const STRUCTS_SECTION: &str = "structs";
+/// Prefix of type referencing named struct
+pub const STRUCT_PREFIX: &str = "struct:";
+/// Param field keeping name of struct referenced by param type
+pub const STRUCT_FIELD: &str = "struct";
+
+/// Named tuple layout declared in `structs` section
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StructDef {
+    /// Struct name
+    pub name: String,
+    /// Struct fields
+    pub components: Vec<Param>,
+}
+
+const RESERVED_NAMES: &[&str] = &["map", "optional", "ref", "tuple", "enum"];
+
+/// `components` or `variants` field of composite alias or struct together with struct name
+#[derive(Clone)]
+struct Extra {
+    field: &'static str,
+    value: Value,
+    struct_name: Option<String>,
+}
+
+#[derive(Clone)]
+struct Expanded {
+    kind: String,
+    extra: Option<Extra>,
+}
+
+struct Aliases<'a> {
+    declared: &'a Map<String, Value>,
+    structs: &'a [(String, Value)],
+    expanded: HashMap<String, Expanded>,
+    expanded_structs: HashMap<String, Value>,
+    in_progress: Vec<String>,
+}
+
+/// Error of ABI element referenced by JSON `pointer`. Position in source text is filled in
+/// when ABI is loaded.
+fn invalid(pointer: &str, msg: String) -> AbiError {
+    AbiError::InvalidAbiJson { pointer: pointer.to_owned(), line: 0, column: 0, msg }
+}
+
+fn alias_pointer(name: &str) -> String {
+    format!("/{}/{}", TYPES_SECTION, name.replace('~', "~0").replace('/', "~1"))
+}
+
+fn is_identifier(name: &str) -> bool {
+    name.chars().next().map_or(false, |c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl<'a> Aliases<'a> {
+    fn new(declared: &'a Map<String, Value>, structs: &'a [(String, Value)]) -> Result<Self> {
+        for name in declared.keys() {
+            if !is_identifier(name) || RESERVED_NAMES.contains(&name.as_str()) || read_type(name).is_ok() {
+                fail!(invalid(&alias_pointer(name), format!("Invalid type alias name {}", name)));
+            }
+        }
+        Ok(Self {
+            declared,
+            structs,
+            expanded: HashMap::new(),
+            expanded_structs: HashMap::new(),
+            in_progress: vec![],
+        })
+    }
+
+    fn set_extra(
+        extra: &mut Option<Extra>,
+        value: Extra,
+        kind: &str,
+        pointer: &str,
+    ) -> Result<()> {
+        if extra.is_some() {
+            fail!(invalid(pointer, format!("Type {} references several tuple or enum aliases", kind)));
+        }
+        *extra = Some(value);
+        Ok(())
+    }
+
+    fn get(&mut self, name: &str) -> Result<Expanded> {
+        if let Some(expanded) = self.expanded.get(name) {
+            return Ok(expanded.clone());
+        }
+        let pointer = alias_pointer(name);
+        if self.in_progress.iter().any(|alias| alias == name) {
+            fail!(invalid(&pointer, format!("Type alias {} is recursive", name)));
+        }
+
+        let (kind, mut extra) = match &self.declared[name] {
+            Value::String(kind) => (kind.clone(), None),
+            Value::Object(object) => {
+                let kind = object.get("type").and_then(Value::as_str).ok_or_else(|| invalid(
+                    &pointer,
+                    format!("Type alias {} should have string `type` field", name),
+                ))?;
+                let mut extra = None;
+                for field in ["components", "variants"] {
+                    if let Some(value) = object.get(field) {
+                        if extra.is_some() {
+                            fail!(invalid(&pointer, format!("Type alias {} has both components and variants", name)));
+                        }
+                        extra = Some(Extra { field, value: value.clone(), struct_name: None });
+                    }
+                }
+                (kind.to_owned(), extra)
+            }
+            _ => fail!(invalid(&pointer, format!("Type alias {} should be type string or object", name))),
+        };
+
+        // components are expanded while alias is in progress to detect self references
+        self.in_progress.push(name.to_owned());
+        let expanded = self.expand(&kind, &mut extra, &pointer).and_then(|kind| {
+            if let Some(extra) = &mut extra {
+                let pointer = format!("{}/{}", pointer, extra.field);
+                self.expand_value(&mut extra.value, &pointer, 0)?;
+            }
+            Ok(kind)
+        });
+        self.in_progress.pop();
+
+        let expanded = Expanded { kind: expanded?, extra };
+        self.expanded.insert(name.to_owned(), expanded.clone());
+        Ok(expanded)
+    }
+
+    /// Returns struct components with expanded aliases and structs
+    fn get_struct(&mut self, name: &str, pointer: &str) -> Result<Value> {
+        if let Some(components) = self.expanded_structs.get(name) {
+            return Ok(components.clone());
+        }
+        let index = self.structs.iter().position(|(item, _)| item == name)
+            .ok_or_else(|| invalid(pointer, format!("Unknown struct {}", name)))?;
+        let in_progress = format!("{}{}", STRUCT_PREFIX, name);
+        let pointer = format!("/{}/{}", STRUCTS_SECTION, index);
+        if self.in_progress.contains(&in_progress) {
+            fail!(invalid(&pointer, format!("Struct {} is recursive", name)));
+        }
+
+        let mut components = self.structs[index].1.clone();
+        self.in_progress.push(in_progress);
+        let expanded = self.expand_value(&mut components, &format!("{}/components", pointer), 0);
+        self.in_progress.pop();
+        expanded?;
+
+        self.expanded_structs.insert(name.to_owned(), components.clone());
+        Ok(components)
+    }
+
+    /// Replaces all alias names in type string with aliased types. Composite alias fields are
+    /// stored in `extra`, type can reference only one composite alias. `pointer` references
+    /// element declaring the type.
+    fn expand(
+        &mut self,
+        kind: &str,
+        extra: &mut Option<Extra>,
+        pointer: &str,
+    ) -> Result<String> {
+        let mut result = String::with_capacity(kind.len());
+        let mut rest = kind;
+        while let Some(start) = rest.find(|c: char| c.is_ascii_alphanumeric() || c == '_') {
+            result += &rest[..start];
+            rest = &rest[start..];
+            let end = rest.find(|c: char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(rest.len());
+            let word = &rest[..end];
+            if word == "struct" && rest[end..].starts_with(':') {
+                let name = &rest[STRUCT_PREFIX.len()..];
+                let name_end = name.find(|c: char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(name.len());
+                let name = &name[..name_end];
+                let components = self.get_struct(name, pointer)?;
+                let struct_extra = Extra {
+                    field: "components",
+                    value: components,
+                    struct_name: Some(name.to_owned()),
+                };
+                Self::set_extra(extra, struct_extra, kind, pointer)?;
+                result += "tuple";
+                rest = &rest[STRUCT_PREFIX.len() + name_end..];
+                continue;
+            } else if self.declared.contains_key(word) {
+                let alias = self.get(word)?;
+                if let Some(alias_extra) = alias.extra {
+                    Self::set_extra(extra, alias_extra, kind, pointer)?;
+                }
+                result += &alias.kind;
+            } else {
+                result += word;
+            }
+            rest = &rest[end..];
+        }
+        result += rest;
+        Ok(result)
+    }
+
+    fn expand_param(&mut self, param: &mut Map<String, Value>, pointer: &str) -> Result<()> {
+        let kind = match param.get("type") {
+            Some(Value::String(kind)) => kind.clone(),
+            _ => return Ok(()),
+        };
+        let mut extra = None;
+        let expanded = self.expand(&kind, &mut extra, pointer)?;
+        if let Some(extra) = extra {
+            let mut fields = vec![(extra.field, extra.value)];
+            if let Some(name) = extra.struct_name {
+                fields.push((STRUCT_FIELD, Value::String(name)));
+            }
+            for (field, value) in fields {
+                if param.contains_key(field) {
+                    fail!(invalid(pointer, format!("Param with aliased type {} can't have own `{}` field", kind, field)));
+                }
+                param.insert(field.to_owned(), value);
+            }
+        }
+        param.insert("type".to_owned(), Value::String(expanded));
+        Ok(())
+    }
+
+    fn expand_value(&mut self, value: &mut Value, pointer: &str, depth: usize) -> Result<()> {
+        if depth > MAX_DECODE_DEPTH {
+            fail!(invalid(pointer, "Type aliases nesting is too deep".to_owned()));
+        }
+        match value {
+            Value::Object(object) => {
+                self.expand_param(object, pointer)?;
+                for (key, value) in object.iter_mut() {
+                    let pointer = format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"));
+                    self.expand_value(value, &pointer, depth + 1)?;
+                }
+            }
+            Value::Array(array) => {
+                for (i, value) in array.iter_mut().enumerate() {
+                    self.expand_value(value, &format!("{}/{}", pointer, i), depth + 1)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+fn read_structs(section: Option<Value>) -> Result<Vec<(String, Value)>> {
+    let pointer = format!("/{}", STRUCTS_SECTION);
+    let section = match section {
+        Some(Value::Array(section)) => section,
+        Some(_) => fail!(invalid(&pointer, "`structs` section should be an array".to_owned())),
+        None => return Ok(vec![]),
+    };
+    let mut structs: Vec<(String, Value)> = vec![];
+    for (i, item) in section.into_iter().enumerate() {
+        let pointer = format!("{}/{}", pointer, i);
+        let name = item.get("name").and_then(Value::as_str).unwrap_or_default();
+        if !is_identifier(name) {
+            fail!(invalid(&pointer, format!("Invalid struct name {}", name)));
+        }
+        if structs.iter().any(|(existing, _)| existing == name) {
+            fail!(invalid(&pointer, format!("Duplicate struct {}", name)));
+        }
+        match item.get("components") {
+            Some(Value::Array(components)) if !components.is_empty() => {
+                structs.push((name.to_owned(), Value::Array(components.clone())))
+            }
+            _ => fail!(invalid(&pointer, format!("Struct {} should have non empty `components` field", name))),
+        }
+    }
+    Ok(structs)
+}
+
+/// Removes `types` and `structs` sections from ABI JSON and expands all aliases and structs
+/// used in params. `extra_aliases` are used along with aliases declared in ABI. Returns
+/// declared structs with expanded components in declaration order.
+pub(crate) fn expand_type_aliases(
+    abi: &mut Value,
+    extra_aliases: &BTreeMap<String, String>,
+) -> Result<Vec<StructDef>> {
+    let (declared, structs) = match abi.as_object_mut() {
+        Some(abi) => (abi.remove(TYPES_SECTION), abi.remove(STRUCTS_SECTION)),
+        None => return Ok(vec![]),
+    };
+    let mut declared = match declared {
+        Some(Value::Object(declared)) => declared,
+        Some(_) => fail!(invalid(&format!("/{}", TYPES_SECTION), "`types` section should be an object".to_owned())),
+        None => Map::new(),
+    };
+    for (alias, kind) in extra_aliases {
+        declared.entry(alias.clone()).or_insert_with(|| Value::String(kind.clone()));
+    }
+    let structs = read_structs(structs)?;
+
+    let mut aliases = Aliases::new(&declared, &structs)?;
+    aliases.expand_value(abi, "", 0)?;
+
+    let mut result = Vec::with_capacity(structs.len());
+    for (i, (name, _)) in structs.iter().enumerate() {
+        let pointer = format!("/{}/{}/components", STRUCTS_SECTION, i);
+        let components = aliases.get_struct(name, &pointer)?;
+        let components = serde_json::from_value(components)
+            .map_err(|err| invalid(&pointer, err.to_string()))?;
+        result.push(StructDef { name: name.clone(), components });
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+#[path = "tests/test_typedefs.rs"]
+mod tests;
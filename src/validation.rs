@@ -0,0 +1,236 @@
+/*
+* Copyright 2018-2020 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Contract ABI validation.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use crate::contract::Contract;
+use crate::param::{Param, ParamAttributes, NO_ATTRIBUTES};
+use crate::param_type::ParamType;
+use crate::token::Tokenizer;
+
+/// Problem found in contract ABI
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ValidationIssue {
+    /// Several functions have the same input or output id
+    DuplicateFunctionId { id: u32, names: Vec<String> },
+    /// Several events have the same id
+    DuplicateEventId { id: u32, names: Vec<String> },
+    /// Several getters have the same get-method id
+    DuplicateGetterId { id: u32, names: Vec<String> },
+    /// Event id is equal to function output id, so responses and events can't be distinguished
+    EventFunctionIdCollision { id: u32, event: String, function: String },
+    /// Parameter type is not supported in declared ABI version
+    UnsupportedType { location: String, kind: String },
+    /// Tuple has no components
+    EmptyTuple { location: String },
+    /// Header contains several params with the same name or type
+    InvalidHeader { msg: String },
+    /// Several data items have the same key
+    DuplicateDataKey { key: u64, names: Vec<String> },
+    /// Param default value doesn't match param type
+    InvalidDefault { location: String, msg: String },
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidationIssue::DuplicateFunctionId { id, names } =>
+                write!(f, "Functions {} have the same id 0x{:08X}", names.join(", "), id),
+            ValidationIssue::DuplicateEventId { id, names } =>
+                write!(f, "Events {} have the same id 0x{:08X}", names.join(", "), id),
+            ValidationIssue::DuplicateGetterId { id, names } =>
+                write!(f, "Getters {} have the same id 0x{:08X}", names.join(", "), id),
+            ValidationIssue::EventFunctionIdCollision { id, event, function } =>
+                write!(f, "Event {} id 0x{:08X} is equal to function {} output id", event, id, function),
+            ValidationIssue::UnsupportedType { location, kind } =>
+                write!(f, "Type {} of {} is not supported in declared ABI version", kind, location),
+            ValidationIssue::EmptyTuple { location } =>
+                write!(f, "Tuple {} has no components", location),
+            ValidationIssue::InvalidHeader { msg } =>
+                write!(f, "Invalid header: {}", msg),
+            ValidationIssue::DuplicateDataKey { key, names } =>
+                write!(f, "Data items {} have the same key {}", names.join(", "), key),
+            ValidationIssue::InvalidDefault { location, msg } =>
+                write!(f, "Default value of {} is invalid: {}", location, msg),
+        }
+    }
+}
+
+impl Contract {
+    /// Checks ABI for problems not detected by `load` and returns all of them.
+    /// Empty result means ABI is valid.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = vec![];
+
+        let mut input_ids = BTreeMap::<u32, Vec<String>>::new();
+        let mut output_ids = BTreeMap::<u32, Vec<String>>::new();
+        for function in self.functions.values() {
+            input_ids.entry(function.get_input_id()).or_default().push(function.name.clone());
+            output_ids.entry(function.get_output_id()).or_default().push(function.name.clone());
+        }
+        for (id, names) in input_ids.into_iter().chain(output_ids.clone()) {
+            if names.len() > 1 {
+                issues.push(ValidationIssue::DuplicateFunctionId { id, names: sorted(names) });
+            }
+        }
+
+        let mut event_ids = BTreeMap::<u32, Vec<String>>::new();
+        for event in self.events.values() {
+            event_ids.entry(event.get_id()).or_default().push(event.name.clone());
+        }
+        for (id, names) in event_ids {
+            if let Some(functions) = output_ids.get(&id) {
+                for event in &names {
+                    for function in functions {
+                        issues.push(ValidationIssue::EventFunctionIdCollision {
+                            id,
+                            event: event.clone(),
+                            function: function.clone(),
+                        });
+                    }
+                }
+            }
+            if names.len() > 1 {
+                issues.push(ValidationIssue::DuplicateEventId { id, names: sorted(names) });
+            }
+        }
+
+        let mut getter_ids = BTreeMap::<u32, Vec<String>>::new();
+        for getter in self.getters.values() {
+            getter_ids.entry(getter.get_id()).or_default().push(getter.name.clone());
+        }
+        for (id, names) in getter_ids {
+            if names.len() > 1 {
+                issues.push(ValidationIssue::DuplicateGetterId { id, names: sorted(names) });
+            }
+        }
+
+        let mut names = HashSet::new();
+        let mut special = HashSet::new();
+        for param in &self.header {
+            if !names.insert(&param.name) {
+                issues.push(ValidationIssue::InvalidHeader {
+                    msg: format!("duplicate param {}", param.name),
+                });
+            }
+            let is_special = matches!(param.kind, ParamType::Time | ParamType::Expire | ParamType::PublicKey);
+            if is_special && !special.insert(param.kind.type_signature()) {
+                issues.push(ValidationIssue::InvalidHeader {
+                    msg: format!("duplicate param of type {}", param.kind),
+                });
+            }
+        }
+
+        self.check_params("header", &self.header, &mut issues);
+        for function in sorted_values(self.functions.values().map(|f| (&f.name, f))) {
+            self.check_params(&format!("function {} inputs", function.name), &function.inputs, &mut issues);
+            self.check_params(&format!("function {} outputs", function.name), &function.outputs, &mut issues);
+        }
+        for getter in sorted_values(self.getters.values().map(|f| (&f.name, f))) {
+            self.check_params(&format!("getter {} inputs", getter.name), &getter.inputs, &mut issues);
+            self.check_params(&format!("getter {} outputs", getter.name), &getter.outputs, &mut issues);
+        }
+        for event in sorted_values(self.events.values().map(|e| (&e.name, e))) {
+            self.check_params(&format!("event {}", event.name), &event.inputs, &mut issues);
+        }
+        let data = self.data.values().map(|item| item.value.clone()).collect::<Vec<_>>();
+        self.check_params("data", &data, &mut issues);
+        self.check_params("fields", &self.fields, &mut issues);
+
+        let mut data_keys = BTreeMap::<u64, Vec<String>>::new();
+        for item in self.data.values() {
+            data_keys.entry(item.key).or_default().push(item.value.name.clone());
+        }
+        for (key, names) in data_keys {
+            if names.len() > 1 {
+                issues.push(ValidationIssue::DuplicateDataKey { key, names: sorted(names) });
+            }
+        }
+
+        issues
+    }
+
+    fn check_params(&self, location: &str, params: &[Param], issues: &mut Vec<ValidationIssue>) {
+        let attributes = self.params_attributes(location, params);
+        self.check_params_with(location, params, &attributes, issues);
+    }
+
+    fn check_params_with(
+        &self,
+        location: &str,
+        params: &[Param],
+        attributes: &[ParamAttributes],
+        issues: &mut Vec<ValidationIssue>,
+    ) {
+        for (i, param) in params.iter().enumerate() {
+            let location = format!("{} {}", location, param.name);
+            let attributes = attributes.get(i).unwrap_or(&NO_ATTRIBUTES);
+            if let Some(default) = &attributes.default {
+                if let Err(err) = Tokenizer::tokenize_parameter(&param.kind, default, &param.name) {
+                    issues.push(ValidationIssue::InvalidDefault { location: location.clone(), msg: err.to_string() });
+                }
+            }
+            self.check_type(&location, &param.kind, attributes, issues);
+        }
+    }
+
+    fn check_type(
+        &self,
+        location: &str,
+        kind: &ParamType,
+        attributes: &ParamAttributes,
+        issues: &mut Vec<ValidationIssue>,
+    ) {
+        if !kind.is_supported(&self.abi_version) {
+            issues.push(ValidationIssue::UnsupportedType {
+                location: location.to_owned(),
+                kind: kind.type_signature(),
+            });
+        }
+        match kind {
+            ParamType::Tuple(components) => {
+                if components.is_empty() {
+                    issues.push(ValidationIssue::EmptyTuple { location: location.to_owned() });
+                }
+                self.check_params_with(location, components, &attributes.components, issues);
+            }
+            ParamType::Array(kind)
+            | ParamType::FixedArray(kind, _)
+            | ParamType::Optional(kind)
+            | ParamType::Ref(kind) => self.check_type(location, kind, attributes, issues),
+            ParamType::Map(key, value) => {
+                self.check_type(location, key, &NO_ATTRIBUTES, issues);
+                self.check_type(location, value, attributes, issues);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn sorted(mut names: Vec<String>) -> Vec<String> {
+    names.sort();
+    names
+}
+
+fn sorted_values<'a, T>(iter: impl Iterator<Item = (&'a String, &'a T)>) -> Vec<&'a T> {
+    let mut entries = iter.collect::<Vec<_>>();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries.into_iter().map(|(_, value)| value).collect()
+}
+
+#[cfg(test)]
+#[path = "tests/test_validation.rs"]
+mod tests;
@@ -0,0 +1,185 @@
+/*
+* Copyright 2018-2020 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Recoverable issues found during encoding and decoding which don't fail the operation.
+
+use std::fmt;
+
+use serde_json::Value;
+use ton_types::SliceData;
+
+use crate::param::Param;
+use crate::param_type::ParamType;
+
+/// Types which are still supported but should be replaced in new ABIs, with their replacements
+pub const DEPRECATED_TYPES: &[(&str, &str)] = &[
+    ("gram", "varuint16"),
+];
+
+/// Non-fatal problem found during encoding or decoding
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Warning {
+    /// Header value was not provided and default value of its type was used
+    DefaultedHeaderValue { name: String, kind: String },
+    /// JSON object field doesn't correspond to any param and was ignored
+    IgnoredField { path: String },
+    /// Data remaining after decoded params was ignored
+    TrailingDataIgnored { bits: usize, refs: usize },
+    /// Param has deprecated type
+    DeprecatedType { location: String, kind: String, replacement: String },
+}
+
+impl Warning {
+    /// Returns stable warning code suitable for machine processing
+    pub fn code(&self) -> &'static str {
+        match self {
+            Warning::DefaultedHeaderValue { .. } => "defaulted-header-value",
+            Warning::IgnoredField { .. } => "ignored-field",
+            Warning::TrailingDataIgnored { .. } => "trailing-data-ignored",
+            Warning::DeprecatedType { .. } => "deprecated-type",
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}] ", self.code())?;
+        match self {
+            Warning::DefaultedHeaderValue { name, kind } =>
+                write!(f, "Header {} is not provided, default {} value is used", name, kind),
+            Warning::IgnoredField { path } =>
+                write!(f, "Field {} doesn't match any param and is ignored", path),
+            Warning::TrailingDataIgnored { bits, refs } =>
+                write!(f, "{} bits and {} refs remaining after decoded params are ignored", bits, refs),
+            Warning::DeprecatedType { location, kind, replacement } =>
+                write!(f, "{} has deprecated type {}, use {} instead", location, kind, replacement),
+        }
+    }
+}
+
+/// Collector of warnings passed to encoding and decoding functions. Warnings are listed in
+/// the order they were found.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Warnings {
+    warnings: Vec<Warning>,
+}
+
+impl Warnings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, warning: Warning) {
+        self.warnings.push(warning);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.warnings.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Warning> {
+        self.warnings.iter()
+    }
+
+    pub fn into_vec(self) -> Vec<Warning> {
+        self.warnings
+    }
+
+    /// Reports params with deprecated types, `location` describes params list
+    pub(crate) fn check_deprecated_types(&mut self, location: &str, params: &[Param]) {
+        for param in params {
+            self.check_deprecated_type(&format!("{} {}", location, param.name), &param.kind);
+        }
+    }
+
+    fn check_deprecated_type(&mut self, location: &str, kind: &ParamType) {
+        match kind {
+            ParamType::Tuple(components) => self.check_deprecated_types(location, components),
+            ParamType::Array(kind) | ParamType::FixedArray(kind, _) |
+            ParamType::Optional(kind) | ParamType::Ref(kind) => self.check_deprecated_type(location, kind),
+            ParamType::Map(key, value) => {
+                self.check_deprecated_type(location, key);
+                self.check_deprecated_type(location, value);
+            }
+            _ => {
+                let name = kind.type_signature();
+                if let Some((_, replacement)) = DEPRECATED_TYPES.iter().find(|(kind, _)| *kind == name) {
+                    self.push(Warning::DeprecatedType {
+                        location: location.to_owned(),
+                        kind: name,
+                        replacement: replacement.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Reports JSON object fields which are not consumed by tokenizer
+    pub(crate) fn check_ignored_fields(&mut self, params: &[Param], values: &Value) {
+        self.check_object_fields(params, values, "");
+    }
+
+    fn check_object_fields(&mut self, params: &[Param], values: &Value, path: &str) {
+        let map = match values {
+            Value::Object(map) => map,
+            _ => return,
+        };
+        for (key, value) in map {
+            let path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+            match params.iter().find(|param| param.name == *key) {
+                Some(param) => self.check_value_fields(&param.kind, value, &path),
+                None => self.push(Warning::IgnoredField { path }),
+            }
+        }
+    }
+
+    fn check_value_fields(&mut self, kind: &ParamType, value: &Value, path: &str) {
+        match (kind, value) {
+            (ParamType::Tuple(components), _) => self.check_object_fields(components, value, path),
+            (ParamType::Array(kind), Value::Array(items)) | (ParamType::FixedArray(kind, _), Value::Array(items)) => {
+                for (i, item) in items.iter().enumerate() {
+                    self.check_value_fields(kind, item, &format!("{}[{}]", path, i));
+                }
+            }
+            (ParamType::Map(_, kind), Value::Object(map)) => {
+                for (key, item) in map {
+                    self.check_value_fields(kind, item, &format!("{}[{}]", path, key));
+                }
+            }
+            (ParamType::Optional(kind), _) | (ParamType::Ref(kind), _) => self.check_value_fields(kind, value, path),
+            _ => {}
+        }
+    }
+
+    /// Reports data remaining in `slice` after decoding
+    pub(crate) fn check_trailing_data(&mut self, slice: &SliceData) {
+        let bits = slice.remaining_bits();
+        let refs = slice.remaining_references();
+        if bits != 0 || refs != 0 {
+            self.push(Warning::TrailingDataIgnored { bits, refs });
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Warnings {
+    type Item = &'a Warning;
+    type IntoIter = std::slice::Iter<'a, Warning>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.warnings.iter()
+    }
+}